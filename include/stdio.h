@@ -1,6 +1,10 @@
-int printf(char *format, ...);
-int scanf(char *format, ...);
-int puts(char *s);
+int printf(const char *format, ...);
+int sprintf(char *s, const char *format, ...);
+int scanf(const char *format, ...);
+int puts(const char *s);
 int putchar(int c);
 int getchar(void);
-int atoi(char *str);
+
+#ifndef NULL
+#define NULL 0
+#endif
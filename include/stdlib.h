@@ -0,0 +1,13 @@
+void *malloc(unsigned long size);
+void *calloc(unsigned long count, unsigned long size);
+void *realloc(void *p, unsigned long size);
+void free(void *p);
+int atoi(const char *s);
+long atol(const char *s);
+int abs(int n);
+long labs(long n);
+void exit(int status);
+
+#ifndef NULL
+#define NULL 0
+#endif
@@ -0,0 +1,13 @@
+unsigned long strlen(const char *s);
+int strcmp(const char *a, const char *b);
+int strncmp(const char *a, const char *b, unsigned long n);
+char *strcpy(char *destination, const char *source);
+char *strcat(char *destination, const char *source);
+char *strchr(const char *s, int c);
+void *memcpy(void *destination, const void *source, unsigned long n);
+void *memset(void *p, int c, unsigned long n);
+int memcmp(const void *a, const void *b, unsigned long n);
+
+#ifndef NULL
+#define NULL 0
+#endif
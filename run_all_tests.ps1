@@ -50,7 +50,145 @@ $TestFiles = @(
     "hello_puts",
     "hello_printf",
     "simple_printf",
-    "exit_code"
+    "exit_code",
+    "comma_for",
+    "unsigned",
+    "pointer_arith",
+    "char_array",
+    "typed_deref",
+    "arith_chain",
+    "const_fold",
+    "uninitialized",
+    "missing_return",
+    "main_no_return",
+    "const_qualifier",
+    "multi_declarators",
+    "goto",
+    "escapes",
+    "line_splice",
+    "conditional_directives",
+    "macro_operators",
+    "global_pointers",
+    "big_expression",
+    "shadow_space",
+    "nested_calls",
+    "struct_by_value",
+    "double_arith",
+    "static_local",
+    "union_overlap",
+    "prototypes",
+    "global_rw",
+    "address_of",
+    "lvalue_assign",
+    "bss_globals",
+    "char_constants",
+    "compare_width",
+    "block_scope",
+    "many_locals",
+    "redeclaration",
+    "call_arguments",
+    "varargs",
+    "subscript_bounds",
+    "integer_literals",
+    "bool",
+    "standard_headers",
+    "char_array_init",
+    "strlen_literal",
+    "error_directives",
+    "volatile_register",
+    "function_pointers",
+    "assign_in_condition",
+    "array_sizes",
+    "comment_at_eof",
+    "break_continue",
+    "short_circuit",
+    "linked_list",
+    "mutual_recursion",
+    "arithmetic_conversions",
+    "large_constants",
+    "sizeof_unevaluated",
+    "typedefs",
+    "null_pointers",
+    "mixed_locals",
+    "string_escapes",
+    "casts",
+    "loop_short_circuit",
+    "multi_dimensional_arrays",
+    "empty_statements",
+    "division_widths",
+    "recursive_macros",
+    "else_if_chain",
+    "array_element_counts",
+    "array_decay",
+    "overflow_wraps",
+    "conditional_operator",
+    "return_widths",
+    "pointer_array_declarators",
+    "macro_rescan",
+    "varargs_saved_registers"
+)
+
+# List of test files in tests/errors that the compiler must reject
+$ErrorTestFiles = @(
+    "undeclared_global",
+    "function_as_variable",
+    "const_assign",
+    "const_pointee_assign",
+    "const_pointer_assign",
+    "goto_undefined_label",
+    "hex_escape_range",
+    "unterminated_if",
+    "invalid_paste",
+    "unknown_member",
+    "member_of_non_struct",
+    "extern_initializer",
+    "division_by_zero",
+    "modulo_by_zero",
+    "conflicting_prototype",
+    "duplicate_definition",
+    "empty_char_literal",
+    "sizeof_void",
+    "local_redefinition",
+    "parameter_redefinition",
+    "conflicting_global",
+    "global_redefinition",
+    "trailing_comma_call",
+    "va_start_fixed_args",
+    "subscript_not_integer",
+    "subscript_non_array",
+    "no_main",
+    "literal_overflow",
+    "string_too_long",
+    "array_init_not_string",
+    "error_directive",
+    "const_volatile_assign",
+    "function_pointer_arguments",
+    "call_non_function",
+    "negative_array_size",
+    "array_size_not_constant",
+    "void_call_result",
+    "void_variable",
+    "void_pointer_dereference",
+    "nested_comment",
+    "break_outside_loop",
+    "continue_outside_loop",
+    "call_integer_variable",
+    "pointer_integer_comparison",
+    "parenthesized_variable_cast",
+    "cast_pointer_to_double",
+    "array_inner_size_missing",
+    "else_if_branch_scope",
+    "array_argument_wrong_pointer",
+    "conditional_incompatible_arms",
+    "assign_to_conditional",
+    "array_of_pointers_as_pointer_to_array",
+    "nonzero_integer_to_pointer",
+    "mismatched_pointer_conversion",
+    "mismatched_pointer_assign",
+    "assign_to_sum",
+    "address_of_sum",
+    "address_of_conditional",
+    "struct_return_too_large"
 )
 
 # Results tracking
@@ -92,11 +230,41 @@ foreach ($Test in $TestFiles) {
     Write-Host ""
 }
 
+# Run each test that is expected to fail compilation
+foreach ($Test in $ErrorTestFiles) {
+    Write-Host "=======================================" -ForegroundColor Blue
+    Write-Host "Testing (expect error): $Test" -ForegroundColor Blue
+    Write-Host "=======================================" -ForegroundColor Blue
+
+    $StartTime = Get-Date
+    cargo run -- "tests/errors/$Test.c"
+    $CompileExitCode = $LASTEXITCODE
+    $EndTime = Get-Date
+    $Duration = ($EndTime - $StartTime).TotalSeconds
+
+    # The test passes only if the compiler reported an error
+    if ($CompileExitCode -ne 0) {
+        $Status = "PASSED"
+        $Passed++
+    } else {
+        $Status = "FAILED (compiled without error)"
+        $Failed++
+    }
+
+    $Results += [PSCustomObject]@{
+        Test = "errors/$Test"
+        Status = $Status
+        Duration = [math]::Round($Duration, 2)
+    }
+
+    Write-Host ""
+}
+
 # Print summary
 Write-Host "=======================================" -ForegroundColor Blue
 Write-Host "Test Summary" -ForegroundColor Blue
 Write-Host "=======================================" -ForegroundColor Blue
-Write-Host "Total tests: $($TestFiles.Count)" -ForegroundColor Cyan
+Write-Host "Total tests: $($TestFiles.Count + $ErrorTestFiles.Count)" -ForegroundColor Cyan
 Write-Host "Passed: $Passed" -ForegroundColor Green
 Write-Host "Failed: $Failed" -ForegroundColor Red
 Write-Host ""
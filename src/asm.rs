@@ -0,0 +1,115 @@
+use std::fmt;
+
+/// A single line of generated assembly
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    /// A label definition, e.g. `main:`
+    Label(String),
+    /// An assembler directive, e.g. `.globl main`
+    Directive(String),
+    /// A machine instruction, e.g. `mov rax, 1`
+    Op {
+        mnemonic: String,
+        operands: Vec<String>,
+    },
+    /// An empty line
+    Blank,
+}
+
+impl Instruction {
+    /// Create a machine instruction
+    pub fn op(mnemonic: &str, operands: &[&str]) -> Self {
+        Instruction::Op {
+            mnemonic: mnemonic.to_string(),
+            operands: operands.iter().map(|o| o.to_string()).collect(),
+        }
+    }
+
+    /// Parse one line of assembly text
+    fn parse(line: &str) -> Self {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            Instruction::Blank
+        } else if !line.starts_with(char::is_whitespace) && trimmed.ends_with(':') {
+            Instruction::Label(trimmed.trim_end_matches(':').to_string())
+        } else if trimmed.starts_with('.') {
+            Instruction::Directive(trimmed.to_string())
+        } else {
+            let (mnemonic, operands) = match trimmed.split_once(' ') {
+                Some((mnemonic, operands)) => (
+                    mnemonic,
+                    operands.split(',').map(|o| o.trim().to_string()).collect(),
+                ),
+                None => (trimmed, Vec::new()),
+            };
+
+            Instruction::Op {
+                mnemonic: mnemonic.to_string(),
+                operands,
+            }
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Label(name) => write!(f, "{}:", name),
+            Instruction::Directive(text) => write!(f, "    {}", text),
+            Instruction::Op { mnemonic, operands } => {
+                if operands.is_empty() {
+                    write!(f, "    {}", mnemonic)
+                } else {
+                    write!(f, "    {} {}", mnemonic, operands.join(", "))
+                }
+            }
+            Instruction::Blank => Ok(()),
+        }
+    }
+}
+
+/// Generated assembly, collected as a list of instructions.
+///
+/// The moves, pushes, pops and jumps the peephole passes rewrite are appended as
+/// instructions with `emit`. Other text written through `fmt::Write` is split
+/// into lines and parsed.
+#[derive(Debug, Default)]
+pub struct Assembly {
+    pub instructions: Vec<Instruction>,
+    line: String,
+}
+
+impl Assembly {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a machine instruction
+    pub fn emit(&mut self, mnemonic: &str, operands: &[&str]) {
+        self.instructions.push(Instruction::op(mnemonic, operands));
+    }
+}
+
+impl fmt::Write for Assembly {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '\n' {
+                self.instructions.push(Instruction::parse(&self.line));
+                self.line.clear();
+            } else {
+                self.line.push(c);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Assembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for instruction in &self.instructions {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//! Checking generated assembly with the platform's assembler, without linking it.
+//! Invalid assembly is reported at the C source line that generated it, when the
+//! assembly carries `.loc` directives, rather than as a failed link.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::ast::Location;
+use crate::error::{CompilerError, Result};
+use crate::target::Target;
+
+/// Assemble generated assembly with `gcc -c`, reading it from a pipe and
+/// discarding the object file, and report the first error the assembler finds
+pub fn check(assembly: &str, target: Target) -> Result<()> {
+    let null_device = if cfg!(windows) { "NUL" } else { "/dev/null" };
+    let mut child = Command::new(target.linker())
+        .args(["-c", "-x", "assembler", "-o", null_device, "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(assembly.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let messages = String::from_utf8_lossy(&output.stderr);
+    let Some((line, message)) = messages.lines().find_map(assembler_error) else {
+        return Err(CompilerError::CodeGenError {
+            location: None,
+            message: format!("Assembling failed: {}", messages.trim()),
+        });
+    };
+
+    let text = assembly.lines().nth(line - 1).unwrap_or_default().trim();
+    let (location, label) = generated_by(assembly, line);
+    let label = label.map(|label| format!(" in {}", label)).unwrap_or_default();
+    Err(CompilerError::CodeGenError {
+        location,
+        message: format!("Invalid assembly at line {}{} (`{}`): {}", line, label, text, message),
+    })
+}
+
+/// The assembly line number and message of an error reported by the assembler,
+/// either `{standard input}:12: Error: ...` from gas or `<stdin>:12:5: error: ...`
+/// from clang
+fn assembler_error(line: &str) -> Option<(usize, &str)> {
+    let rest = line.strip_prefix("{standard input}:").or_else(|| line.strip_prefix("<stdin>:"))?;
+    let (number, rest) = rest.split_once(':')?;
+    let number = number.parse().ok()?;
+    let message = rest.trim_start();
+    // Skip clang's column
+    let message = message.split_once(": ").filter(|(column, _)| column.parse::<usize>().is_ok()).map_or(message, |(_, m)| m);
+    let message = message.strip_prefix("Error: ").or_else(|| message.strip_prefix("error: "))?;
+    Some((number, message))
+}
+
+/// The C source location of the `.loc` directive in effect at a line of assembly,
+/// and the last label defined before it. A section switch ends the directive's
+/// reach, as data is laid out apart from the code of the statement that uses it.
+fn generated_by(assembly: &str, line: usize) -> (Option<Location>, Option<&str>) {
+    let mut files: Vec<&str> = Vec::new();
+    let mut location = None;
+    let mut label = None;
+    for text in assembly.lines().take(line.saturating_sub(1)) {
+        let text = text.trim();
+        if let Some(file) = text.strip_prefix(".file ") {
+            if let Some((_, name)) = file.split_once(' ') {
+                files.push(name.trim_matches('"'));
+            }
+        } else if let Some(directive) = text.strip_prefix(".loc ") {
+            let fields: Vec<usize> = directive.split_whitespace().filter_map(|field| field.parse().ok()).collect();
+            if let [file, line, column, ..] = fields[..] {
+                location = files.get(file.wrapping_sub(1)).map(|name| Location::point(*name, line, column));
+            }
+        } else if text.starts_with(".section") || text == ".data" || text == ".bss" || text == ".text" {
+            location = None;
+        } else if let Some(name) = text.strip_suffix(':') {
+            label = Some(name);
+        }
+    }
+    (location, label)
+}
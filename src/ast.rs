@@ -1,11 +1,43 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::fmt;
 
-/// Represents a location in the source code
+/// Represents a location in the source code: where a token or node starts, which
+/// is what diagnostics point at, and where its source text ends
 #[derive(Debug, Clone, PartialEq)]
 pub struct Location {
     pub file: String,
     pub line: usize,
     pub column: usize,
+    /// Line of the last character of the text
+    pub end_line: usize,
+    /// Column just past the last character of the text
+    pub end_column: usize,
+}
+
+impl Location {
+    /// A location without source text of its own, which ends where it starts
+    pub fn point(file: impl Into<String>, line: usize, column: usize) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+            end_line: line,
+            end_column: column,
+        }
+    }
+
+    /// The smallest location covering both this one and `other`. A location in
+    /// another file, such as that of a token from a macro defined in a header,
+    /// does not extend it.
+    pub fn join(&self, other: &Location) -> Self {
+        if other.file != self.file {
+            return self.clone();
+        }
+        let (line, column) = (self.line, self.column).min((other.line, other.column));
+        let (end_line, end_column) = (self.end_line, self.end_column).max((other.end_line, other.end_column));
+        Self { file: self.file.clone(), line, column, end_line, end_column }
+    }
 }
 
 impl fmt::Display for Location {
@@ -16,6 +48,7 @@ impl fmt::Display for Location {
 
 /// Represents a binary operator
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub enum BinaryOp {
     Add,      // +
     Subtract, // -
@@ -48,17 +81,282 @@ pub enum UnaryOp {
     AddressOf,   // &
 }
 
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Modulo => "%",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::LogicalAnd => "&&",
+            BinaryOp::LogicalOr => "||",
+            BinaryOp::BitwiseAnd => "&",
+            BinaryOp::BitwiseOr => "|",
+            BinaryOp::BitwiseXor => "^",
+            BinaryOp::ShiftLeft => "<<",
+            BinaryOp::ShiftRight => ">>",
+            BinaryOp::Assign => "=",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            UnaryOp::Negate => "-",
+            UnaryOp::LogicalNot => "!",
+            UnaryOp::BitwiseNot => "~",
+            UnaryOp::Dereference => "*",
+            UnaryOp::AddressOf => "&",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
 /// Represents a C type
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     Void,
+    Bool, // _Bool: 0 or 1 in a single byte
     Char,
     Int,
     Long,
+    UChar,
+    UInt,
+    ULong,
+    Float,
+    Double,
     Pointer(Box<Type>),
     Array(Box<Type>, Option<usize>),
     Function(Box<Type>, Vec<Type>, bool), // Return type, parameter types, is_variadic
     Struct(String, Vec<(String, Type)>),
+    Union(String, Vec<(String, Type)>), // all members share the same storage
+    Const(Box<Type>), // const-qualified type
+    Volatile(Box<Type>), // volatile-qualified type, which must be read and written in memory on every access
+}
+
+impl Type {
+    /// Get the type without its top-level qualifiers
+    pub fn unqualified(&self) -> &Type {
+        match self {
+            Type::Const(inner) | Type::Volatile(inner) => inner.unqualified(),
+            other => other,
+        }
+    }
+
+    /// Remove qualifiers at every level, e.g. `const char *const` becomes `char *`
+    pub fn strip_qualifiers(&self) -> Type {
+        match self {
+            Type::Const(inner) | Type::Volatile(inner) => inner.strip_qualifiers(),
+            Type::Pointer(inner) => Type::Pointer(Box::new(inner.strip_qualifiers())),
+            Type::Array(inner, size) => Type::Array(Box::new(inner.strip_qualifiers()), *size),
+            Type::Function(ret, params, is_variadic) => Type::Function(
+                Box::new(ret.strip_qualifiers()),
+                params.iter().map(Type::strip_qualifiers).collect(),
+                *is_variadic,
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Get the type of a value of this type: an array used as a value stands for a
+    /// pointer to its first element, and keeps its type only under `sizeof` and `&`
+    pub fn decay(&self) -> Type {
+        match self.unqualified() {
+            Type::Array(element, _) => Type::Pointer(element.clone()),
+            _ => self.clone(),
+        }
+    }
+
+    /// Wrap a type in the given qualifiers, const outermost
+    pub fn qualified(self, is_const: bool, is_volatile: bool) -> Type {
+        let type_ = if is_volatile { Type::Volatile(Box::new(self)) } else { self };
+        if is_const {
+            Type::Const(Box::new(type_))
+        } else {
+            type_
+        }
+    }
+
+    /// Get the type of a struct or union member by name. A member of a const or
+    /// volatile struct is const or volatile too.
+    pub fn member_type(&self, name: &str) -> Option<Type> {
+        let members = match self.unqualified() {
+            Type::Struct(_, members) | Type::Union(_, members) => members,
+            _ => return None,
+        };
+
+        let (_, member_type) = members.iter().find(|(member_name, _)| member_name == name)?;
+        Some(member_type.clone().qualified(
+            self.is_const() && !member_type.is_const(),
+            self.is_volatile() && !member_type.is_volatile(),
+        ))
+    }
+
+    /// Check if this is a struct or union type, whose values are accessed in memory
+    pub fn is_record(&self) -> bool {
+        matches!(self.unqualified(), Type::Struct(_, _) | Type::Union(_, _))
+    }
+
+    /// Check if this is a pointer to a function
+    pub fn is_function_pointer(&self) -> bool {
+        matches!(self.unqualified(), Type::Pointer(inner) if matches!(inner.unqualified(), Type::Function(_, _, _)))
+    }
+
+    /// Check if this type is const-qualified
+    pub fn is_const(&self) -> bool {
+        match self {
+            Type::Const(_) => true,
+            Type::Volatile(inner) => inner.is_const(),
+            _ => false,
+        }
+    }
+
+    /// Check if this type is volatile-qualified
+    pub fn is_volatile(&self) -> bool {
+        match self {
+            Type::Volatile(_) => true,
+            Type::Const(inner) => inner.is_volatile(),
+            _ => false,
+        }
+    }
+
+    /// Check if this is an integer type
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.unqualified(),
+            Type::Bool | Type::Char | Type::Int | Type::Long | Type::UChar | Type::UInt | Type::ULong
+        )
+    }
+
+    /// Check if this is a floating type
+    pub fn is_floating(&self) -> bool {
+        matches!(self.unqualified(), Type::Float | Type::Double)
+    }
+
+    /// Check if this is an integer or floating type
+    pub fn is_arithmetic(&self) -> bool {
+        self.is_integer() || self.is_floating()
+    }
+
+    /// Check if this is an unsigned integer type
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self.unqualified(), Type::Bool | Type::UChar | Type::UInt | Type::ULong)
+    }
+
+    /// Promote _Bool and char types to int, leaving wider types unchanged
+    pub fn promote(&self) -> Type {
+        match self.unqualified() {
+            Type::Bool | Type::Char | Type::UChar => Type::Int,
+            other => other.clone(),
+        }
+    }
+
+    /// Compute the result type of an arithmetic operation on two operands, by the
+    /// usual arithmetic conversions: both are promoted first, so chars never meet
+    /// as chars. A floating operand makes the result floating, double winning over float.
+    /// Among integers, unsigned long wins over everything, long can represent every
+    /// unsigned int, and unsigned int wins over int.
+    pub fn arithmetic_type(left: &Type, right: &Type) -> Type {
+        let left = left.promote();
+        let right = right.promote();
+
+        if left == Type::Double || right == Type::Double {
+            Type::Double
+        } else if left == Type::Float || right == Type::Float {
+            Type::Float
+        } else if left == Type::ULong || right == Type::ULong {
+            Type::ULong
+        } else if left == Type::Long || right == Type::Long {
+            Type::Long
+        } else if left == Type::UInt || right == Type::UInt {
+            Type::UInt
+        } else {
+            Type::Int
+        }
+    }
+}
+
+/// Types are displayed roughly as they are spelled in C, e.g. `const char *`
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Bool => write!(f, "_Bool"),
+            Type::Char => write!(f, "char"),
+            Type::Int => write!(f, "int"),
+            Type::Long => write!(f, "long"),
+            Type::UChar => write!(f, "unsigned char"),
+            Type::UInt => write!(f, "unsigned int"),
+            Type::ULong => write!(f, "unsigned long"),
+            Type::Float => write!(f, "float"),
+            Type::Double => write!(f, "double"),
+            Type::Pointer(inner) => write!(f, "{} *", inner),
+            Type::Array(_, _) => {
+                // The sizes of nested arrays are written outermost first, as in int[3][4]
+                let mut element = self;
+                let mut sizes = String::new();
+                while let Type::Array(inner, size) = element {
+                    match size {
+                        Some(size) => sizes.push_str(&format!("[{}]", size)),
+                        None => sizes.push_str("[]"),
+                    }
+                    element = inner;
+                }
+                write!(f, "{}{}", element, sizes)
+            }
+            Type::Function(return_type, params, is_variadic) => {
+                let mut params: Vec<String> = params.iter().map(Type::to_string).collect();
+                if *is_variadic {
+                    params.push("...".to_string());
+                }
+                write!(f, "{}({})", return_type, params.join(", "))
+            }
+            Type::Struct(name, _) if name.is_empty() => write!(f, "struct"),
+            Type::Struct(name, _) => write!(f, "struct {}", name),
+            Type::Union(name, _) if name.is_empty() => write!(f, "union"),
+            Type::Union(name, _) => write!(f, "union {}", name),
+            Type::Const(inner) => match inner.as_ref() {
+                // A const pointer is spelled with const after the *
+                Type::Pointer(_) => write!(f, "{} const", inner),
+                _ => write!(f, "const {}", inner),
+            },
+            Type::Volatile(inner) => match inner.as_ref() {
+                Type::Pointer(_) => write!(f, "{} volatile", inner),
+                _ => write!(f, "volatile {}", inner),
+            },
+        }
+    }
+}
+
+/// The storage class a variable is declared with
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum StorageClass {
+    /// No storage class: automatic in a function, external linkage at file scope
+    #[default]
+    Auto,
+    /// A single fixed slot, with internal linkage at file scope
+    Static,
+    /// A declaration of a variable defined elsewhere
+    Extern,
+}
+
+impl fmt::Display for StorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageClass::Auto => write!(f, "auto"),
+            StorageClass::Static => write!(f, "static"),
+            StorageClass::Extern => write!(f, "extern"),
+        }
+    }
 }
 
 /// Represents an AST node
@@ -66,6 +364,10 @@ pub enum Type {
 pub enum Node {
     // Expressions
     IntLiteral(i64, Location),
+    UIntLiteral(u64, Location),
+    LongLiteral(i64, Location),
+    ULongLiteral(u64, Location),
+    FloatLiteral(f64, Location),
     CharLiteral(char, Location),
     StringLiteral(String, Location),
     Identifier(String, Location),
@@ -85,19 +387,63 @@ pub enum Node {
         args: Vec<Node>,
         location: Location,
     },
+    /// A call through a function pointer computed by an expression, such as `(*fp)(1)`
+    /// or `table[i](x)`; a call through a pointer variable is a `FunctionCall`
+    IndirectCall {
+        callee: Box<Node>,
+        args: Vec<Node>,
+        location: Location,
+    },
+    CommaExpr {
+        left: Box<Node>,
+        right: Box<Node>,
+        location: Location,
+    },
+    /// `condition ? then_expr : else_expr`, located at the `?`
+    ConditionalExpr {
+        condition: Box<Node>,
+        then_expr: Box<Node>,
+        else_expr: Box<Node>,
+        location: Location,
+    },
+    MemberAccess {
+        expr: Box<Node>,
+        member: String,
+        location: Location,
+    },
+    SizeofExpr(Box<Node>, Location),
+    SizeofType(Type, Location),
+    /// An explicit conversion, `(type) expr`
+    Cast {
+        type_: Type,
+        expr: Box<Node>,
+        location: Location,
+    },
+    /// `va_start(ap, last)`: begin reading the arguments after the named parameter `last`
+    VaStart(Box<Node>, String, Location),
+    /// `va_arg(ap, type)`: read the next variable argument as a value of the type
+    VaArg(Box<Node>, Type, Location),
+    /// `va_end(ap)`: finish reading variable arguments
+    VaEnd(Box<Node>, Location),
 
     // Statements
     ExpressionStmt(Box<Node>),
+    /// `;` alone, which does nothing, as the body of `while (step());`
+    EmptyStmt(Location),
     ReturnStmt(Option<Box<Node>>, Location),
     IfStmt {
         condition: Box<Node>,
         then_branch: Box<Node>,
         else_branch: Option<Box<Node>>,
+        /// Whether the condition has parentheses of its own, as in `if ((x = f()))`
+        parenthesized: bool,
         location: Location,
     },
     WhileStmt {
         condition: Box<Node>,
         body: Box<Node>,
+        /// Whether the condition has parentheses of its own
+        parenthesized: bool,
         location: Location,
     },
     ForStmt {
@@ -108,11 +454,18 @@ pub enum Node {
         location: Location,
     },
     BlockStmt(Vec<Node>, Location),
+    LabelStmt(String, Box<Node>, Location),
+    GotoStmt(String, Location),
+    /// `break`: leave the innermost enclosing loop
+    BreakStmt(Location),
+    /// `continue`: go on with the next iteration of the innermost enclosing loop
+    ContinueStmt(Location),
 
     // Declarations
     VarDecl {
         name: String,
         type_: Type,
+        storage: StorageClass,
         initializer: Option<Box<Node>>,
         location: Location,
     },
@@ -127,3 +480,212 @@ pub enum Node {
     // Program
     Program(Vec<Node>),
 }
+
+impl Node {
+    /// Get the source location of a node; a program has none, and an expression
+    /// statement is where its expression is
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Node::IntLiteral(_, location)
+            | Node::UIntLiteral(_, location)
+            | Node::LongLiteral(_, location)
+            | Node::ULongLiteral(_, location)
+            | Node::FloatLiteral(_, location)
+            | Node::CharLiteral(_, location)
+            | Node::StringLiteral(_, location)
+            | Node::Identifier(_, location)
+            | Node::BinaryExpr { location, .. }
+            | Node::UnaryExpr { location, .. }
+            | Node::FunctionCall { location, .. }
+            | Node::IndirectCall { location, .. }
+            | Node::CommaExpr { location, .. }
+            | Node::ConditionalExpr { location, .. }
+            | Node::MemberAccess { location, .. }
+            | Node::SizeofExpr(_, location)
+            | Node::SizeofType(_, location)
+            | Node::Cast { location, .. }
+            | Node::VaStart(_, _, location)
+            | Node::VaArg(_, _, location)
+            | Node::VaEnd(_, location)
+            | Node::EmptyStmt(location)
+            | Node::ReturnStmt(_, location)
+            | Node::IfStmt { location, .. }
+            | Node::WhileStmt { location, .. }
+            | Node::ForStmt { location, .. }
+            | Node::BlockStmt(_, location)
+            | Node::LabelStmt(_, _, location)
+            | Node::GotoStmt(_, location)
+            | Node::BreakStmt(location)
+            | Node::ContinueStmt(location)
+            | Node::VarDecl { location, .. }
+            | Node::FunctionDecl { location, .. } => Some(location),
+            Node::ExpressionStmt(expr) => expr.location(),
+            Node::Program(_) => None,
+        }
+    }
+
+    /// Get the source text an expression covers, from the start of its first token
+    /// to the end of its last, such as all of `a + b * c`. Other nodes cover their
+    /// location only.
+    pub fn span(&self) -> Option<Location> {
+        let location = self.location()?;
+        let children: Vec<&Node> = match self {
+            Node::BinaryExpr { left, right, .. } | Node::CommaExpr { left, right, .. } => vec![left, right],
+            Node::ConditionalExpr { condition, then_expr, else_expr, .. } => vec![condition, then_expr, else_expr],
+            Node::UnaryExpr { expr, .. }
+            | Node::MemberAccess { expr, .. }
+            | Node::SizeofExpr(expr, _)
+            | Node::Cast { expr, .. } => vec![expr],
+            Node::VaStart(list, _, _) | Node::VaArg(list, _, _) | Node::VaEnd(list, _) => vec![list],
+            Node::FunctionCall { args, .. } => args.iter().collect(),
+            Node::IndirectCall { callee, args, .. } => std::iter::once(callee.as_ref()).chain(args).collect(),
+            _ => vec![],
+        };
+        Some(
+            children
+                .iter()
+                .filter_map(|child| child.span())
+                .fold(location.clone(), |span, child| span.join(&child)),
+        )
+    }
+
+    /// Rebuild the node with each of its children replaced by `f` of it, for passes
+    /// that rewrite the tree from the leaves up. The first error `f` returns is
+    /// returned instead.
+    pub fn try_map_children<E>(self, f: &mut impl FnMut(Node) -> Result<Node, E>) -> Result<Node, E> {
+        fn boxed<E>(node: Node, f: &mut impl FnMut(Node) -> Result<Node, E>) -> Result<Box<Node>, E> {
+            f(node).map(Box::new)
+        }
+        fn optional<E>(
+            node: Option<Box<Node>>,
+            f: &mut impl FnMut(Node) -> Result<Node, E>,
+        ) -> Result<Option<Box<Node>>, E> {
+            node.map(|node| boxed(*node, f)).transpose()
+        }
+        fn all<E>(nodes: Vec<Node>, f: &mut impl FnMut(Node) -> Result<Node, E>) -> Result<Vec<Node>, E> {
+            nodes.into_iter().map(f).collect()
+        }
+
+        Ok(match self {
+            Node::BinaryExpr { op, left, right, location } => Node::BinaryExpr {
+                op,
+                left: boxed(*left, f)?,
+                right: boxed(*right, f)?,
+                location,
+            },
+            Node::UnaryExpr { op, expr, location } => Node::UnaryExpr { op, expr: boxed(*expr, f)?, location },
+            Node::FunctionCall { name, args, location } => Node::FunctionCall { name, args: all(args, f)?, location },
+            Node::IndirectCall { callee, args, location } => Node::IndirectCall {
+                callee: boxed(*callee, f)?,
+                args: all(args, f)?,
+                location,
+            },
+            Node::CommaExpr { left, right, location } => Node::CommaExpr {
+                left: boxed(*left, f)?,
+                right: boxed(*right, f)?,
+                location,
+            },
+            Node::ConditionalExpr { condition, then_expr, else_expr, location } => Node::ConditionalExpr {
+                condition: boxed(*condition, f)?,
+                then_expr: boxed(*then_expr, f)?,
+                else_expr: boxed(*else_expr, f)?,
+                location,
+            },
+            Node::MemberAccess { expr, member, location } => Node::MemberAccess {
+                expr: boxed(*expr, f)?,
+                member,
+                location,
+            },
+            Node::SizeofExpr(expr, location) => Node::SizeofExpr(boxed(*expr, f)?, location),
+            Node::Cast { type_, expr, location } => Node::Cast { type_, expr: boxed(*expr, f)?, location },
+            Node::VaStart(list, last, location) => Node::VaStart(boxed(*list, f)?, last, location),
+            Node::VaArg(list, type_, location) => Node::VaArg(boxed(*list, f)?, type_, location),
+            Node::VaEnd(list, location) => Node::VaEnd(boxed(*list, f)?, location),
+            Node::ExpressionStmt(expr) => Node::ExpressionStmt(boxed(*expr, f)?),
+            Node::ReturnStmt(value, location) => Node::ReturnStmt(optional(value, f)?, location),
+            Node::IfStmt { condition, then_branch, else_branch, parenthesized, location } => Node::IfStmt {
+                condition: boxed(*condition, f)?,
+                then_branch: boxed(*then_branch, f)?,
+                else_branch: optional(else_branch, f)?,
+                parenthesized,
+                location,
+            },
+            Node::WhileStmt { condition, body, parenthesized, location } => Node::WhileStmt {
+                condition: boxed(*condition, f)?,
+                body: boxed(*body, f)?,
+                parenthesized,
+                location,
+            },
+            Node::ForStmt { init, condition, increment, body, location } => Node::ForStmt {
+                init: optional(init, f)?,
+                condition: optional(condition, f)?,
+                increment: optional(increment, f)?,
+                body: boxed(*body, f)?,
+                location,
+            },
+            Node::BlockStmt(statements, location) => Node::BlockStmt(all(statements, f)?, location),
+            Node::LabelStmt(name, statement, location) => Node::LabelStmt(name, boxed(*statement, f)?, location),
+            Node::VarDecl { name, type_, storage, initializer, location } => Node::VarDecl {
+                name,
+                type_,
+                storage,
+                initializer: optional(initializer, f)?,
+                location,
+            },
+            Node::FunctionDecl { name, return_type, params, body, location } => Node::FunctionDecl {
+                name,
+                return_type,
+                params,
+                body: optional(body, f)?,
+                location,
+            },
+            Node::Program(declarations) => Node::Program(all(declarations, f)?),
+            leaf => leaf,
+        })
+    }
+
+    /// Rebuild the node with each of its children replaced by `f` of it
+    pub fn map_children(self, mut f: impl FnMut(Node) -> Node) -> Node {
+        match self.try_map_children(&mut |node| Ok::<Node, Infallible>(f(node))) {
+            Ok(node) => node,
+            Err(never) => match never {},
+        }
+    }
+}
+
+/// The bytes of a string literal's value. The lexer keeps each byte in a character
+/// of its own, whether it comes from a numeric escape or from the UTF-8 encoding of
+/// a character outside ASCII.
+pub fn literal_bytes(value: &str) -> impl Iterator<Item = u8> + '_ {
+    value.chars().map(|c| c as u32 as u8)
+}
+
+/// The text a string literal's bytes spell, for where it names a file or a message
+pub fn literal_text(value: &str) -> String {
+    String::from_utf8_lossy(&literal_bytes(value).collect::<Vec<u8>>()).into_owned()
+}
+
+/// Types computed by the type checker for each checked node.
+///
+/// Nodes are keyed by their address, so the map is only meaningful for the exact
+/// AST that was checked; rebuilding or moving the tree invalidates the entries.
+#[derive(Debug, Clone, Default)]
+pub struct TypeMap {
+    types: HashMap<*const Node, Type>,
+}
+
+impl TypeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the type of a node
+    pub fn insert(&mut self, node: &Node, type_: Type) {
+        self.types.insert(node as *const Node, type_);
+    }
+
+    /// Look up the recorded type of a node
+    pub fn get(&self, node: &Node) -> Option<&Type> {
+        self.types.get(&(node as *const Node))
+    }
+}
@@ -1,17 +1,112 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
-use crate::ast::{BinaryOp, Node, Type, UnaryOp};
+use crate::asm::{Assembly, Instruction};
+use crate::ast::{literal_bytes, BinaryOp, Location, Node, StorageClass, Type, TypeMap, UnaryOp};
+use crate::cse;
 use crate::error::{codegen_error, Result};
+use crate::peephole;
+use crate::target::Target;
+
+/// Registers that hold intermediate values while another operand is evaluated,
+/// in allocation order. The volatile ones come first because the call sequence
+/// already preserves them; the rest are callee-saved, so a function that uses
+/// them saves them in its prologue.
+const SCRATCH_REGISTERS: [&str; 7] = ["r10", "r11", "rbx", "r12", "r13", "r14", "r15"];
+const CALLEE_SAVED_REGISTERS: [&str; 5] = ["rbx", "r12", "r13", "r14", "r15"];
+
+/// Label of the routine that aborts the program when signed arithmetic overflows
+/// under -ftrap-overflow
+const OVERFLOW_TRAP: &str = ".overflow_trap";
 
 /// Code generator for x86-64 assembly
 pub struct CodeGenerator {
-    output: String,
+    output: Assembly,
     label_count: usize,
     string_literals: Vec<String>,
     variables: HashMap<String, Variable>,
+    /// Labels of the static locals of the current function, by name
+    static_locals: HashMap<String, String>,
+    functions: HashMap<String, Type>,
+    /// Names of the functions the program defines with a body
+    defined_functions: HashSet<String>,
+    /// Names of the functions called or whose address was taken so far, in the order
+    /// they were first used
+    called_functions: Vec<String>,
+    globals: HashMap<String, Type>,
+    types: TypeMap,
     current_function: Option<String>,
     stack_offset: usize,
+    /// Bytes of the current function's frame: the deepest stack offset of any local
+    frame_size: usize,
+    scratch_depth: usize,
+    saved_registers: Vec<&'static str>,
+    /// Where the current function keeps its variable arguments, if it is variadic
+    var_args: Option<VarArgFrame>,
+    target: Target,
+    /// Whether to emit `.loc` directives mapping instructions to source lines
+    debug_info: bool,
+    /// Source files named by `.file` directives so far, numbered from 1
+    debug_files: Vec<String>,
+    /// Optimization level: 1 also leaves out code that can never run and computes
+    /// repeated subexpressions of a statement once
+    opt_level: u8,
+    /// Whether signed `+`, `-` and `*` jump to the overflow trap when they overflow
+    trap_overflow: bool,
+    /// Whether any code jumps to the overflow trap, so it must be emitted
+    overflow_trap_used: bool,
+    /// Subexpressions of the current statement already computed, with the frame
+    /// offsets of the temporaries holding their values
+    common_values: Vec<(Node, usize)>,
+    /// Jump targets of the statements around the current one that `break` and
+    /// `continue` can leave, innermost last
+    jump_targets: Vec<JumpTargets>,
+}
+
+/// Where `break` and `continue` jump to inside a statement that they can leave
+#[derive(Debug, Clone)]
+struct JumpTargets {
+    /// Label just after the statement, for `break`
+    break_label: String,
+    /// Label of the next iteration, for `continue`. Only loops have one: a
+    /// `continue` inside a switch goes on with the loop around it.
+    continue_label: Option<String>,
+}
+
+/// The function a call jumps to
+#[derive(Clone, Copy)]
+enum Callee<'a> {
+    /// A function called by its name
+    Named(&'a str),
+    /// An expression evaluating to the address of the function
+    Pointer(&'a Node),
+}
+
+/// Where the calling convention passes an argument, or one 8-byte word of a
+/// struct split across two
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArgLocation {
+    /// An integer register
+    Register(&'static str),
+    /// The XMM register with this number
+    Xmm(usize),
+    /// The stack, above the shadow space
+    Stack,
+}
+
+/// The frame slots a variadic function's va_list points to. A va_list holds
+/// the address of a control block of three pointers: the next argument, the
+/// end of the saved argument registers (or 0 where register and stack arguments
+/// are already contiguous), and the first argument passed on the stack. va_arg
+/// moves on to the stack arguments when the next one reaches the end.
+#[derive(Debug, Clone)]
+struct VarArgFrame {
+    /// Offset of the control block below RBP
+    control: usize,
+    /// Address of the first variable argument
+    first: String,
+    /// Address just past the saved argument registers
+    registers_end: Option<String>,
 }
 
 /// Represents a variable in the generated code
@@ -22,17 +117,89 @@ struct Variable {
 }
 
 impl CodeGenerator {
-    pub fn new() -> Self {
+    /// Create a code generator for the given target platform
+    pub fn new_with_target(target: Target) -> Self {
         Self {
-            output: String::new(),
+            output: Assembly::new(),
             label_count: 0,
             string_literals: Vec::new(),
             variables: HashMap::new(),
+            static_locals: HashMap::new(),
+            functions: HashMap::new(),
+            defined_functions: HashSet::new(),
+            called_functions: Vec::new(),
+            globals: HashMap::new(),
+            types: TypeMap::new(),
             current_function: None,
             stack_offset: 0,
+            frame_size: 0,
+            scratch_depth: 0,
+            saved_registers: Vec::new(),
+            var_args: None,
+            target,
+            debug_info: false,
+            debug_files: Vec::new(),
+            opt_level: 0,
+            trap_overflow: false,
+            overflow_trap_used: false,
+            common_values: Vec::new(),
+            jump_targets: Vec::new(),
         }
     }
 
+    /// Use the types computed by the type checker for the program being generated
+    pub fn with_types(mut self, types: TypeMap) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Emit line information for debuggers
+    pub fn with_debug_info(mut self, debug_info: bool) -> Self {
+        self.debug_info = debug_info;
+        self
+    }
+
+    /// Set the optimization level
+    pub fn with_opt_level(mut self, opt_level: u8) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
+    /// Abort the program when signed arithmetic overflows
+    pub fn with_trap_overflow(mut self, trap_overflow: bool) -> Self {
+        self.trap_overflow = trap_overflow;
+        self
+    }
+
+    /// Emit the routine signed arithmetic jumps to when it overflows. It may be
+    /// reached with the stack at any depth, so it aligns the stack, and leaves
+    /// Windows' shadow space, before calling abort, which does not return.
+    fn emit_overflow_trap(&mut self) {
+        writeln!(self.output, "{}:", OVERFLOW_TRAP).unwrap();
+        writeln!(self.output, "    and rsp, -16").unwrap();
+        writeln!(self.output, "    sub rsp, 32").unwrap();
+        let abort = self.function_symbol("abort");
+        writeln!(self.output, "    call {}", abort).unwrap();
+    }
+
+    /// Emit a `.loc` directive for the source line of a node, naming its file with
+    /// a `.file` directive the first time it appears
+    fn emit_location(&mut self, node: &Node) {
+        let Some(location) = node.location() else {
+            return;
+        };
+
+        let file = match self.debug_files.iter().position(|file| *file == location.file) {
+            Some(index) => index + 1,
+            None => {
+                self.debug_files.push(location.file.clone());
+                writeln!(self.output, "    .file {} {:?}", self.debug_files.len(), location.file).unwrap();
+                self.debug_files.len()
+            }
+        };
+        writeln!(self.output, "    .loc {} {} {}", file, location.line, location.column).unwrap();
+    }
+
     /// Generate a unique label
     fn generate_label(&mut self, prefix: &str) -> String {
         let label = format!(".{}{}", prefix, self.label_count);
@@ -44,13 +211,16 @@ impl CodeGenerator {
     fn size_of(&self, type_: &Type) -> usize {
         match type_ {
             Type::Void => 0,
-            Type::Char => 1,
-            Type::Int => 4,
-            Type::Long => 8,
+            Type::Bool | Type::Char | Type::UChar => 1,
+            Type::Int | Type::UInt => 4,
+            Type::Long | Type::ULong => 8,
+            Type::Float => 4,
+            Type::Double => 8,
             Type::Pointer(_) => 8,
             Type::Array(base, Some(size)) => self.size_of(base) * size,
             Type::Array(_, None) => panic!("Cannot determine size of array with unknown size"),
             Type::Function(_, _, _) => 8, // Function pointers are 8 bytes
+            Type::Const(inner) | Type::Volatile(inner) => self.size_of(inner),
             Type::Struct(_, members) => {
                 let mut size = 0;
                 for (_, member_type) in members {
@@ -58,6 +228,10 @@ impl CodeGenerator {
                 }
                 size
             }
+            // A union is as large as its largest member
+            Type::Union(_, members) => {
+                members.iter().map(|(_, member_type)| self.size_of(member_type)).max().unwrap_or(0)
+            }
         }
     }
 
@@ -66,21 +240,953 @@ impl CodeGenerator {
         (n + align - 1) & !(align - 1)
     }
 
+    /// Reserve a frame slot below those in use and return its offset from RBP. The
+    /// slot spans [rbp-offset, rbp-offset+size), so it ends where the previous one
+    /// begins at the latest; every slot takes at least a byte, so no two share an
+    /// address.
+    fn allocate_slot(&mut self, size: usize, align: usize) -> usize {
+        self.stack_offset = self.align_to(self.stack_offset + size.max(1), align);
+        self.frame_size = self.frame_size.max(self.stack_offset);
+        self.stack_offset
+    }
+
+    /// Get the type of an expression, as computed by the type checker.
+    /// Qualifiers do not affect code generation, so they are dropped.
+    fn expr_type(&self, node: &Node) -> Type {
+        match self.types.get(node) {
+            Some(type_) => type_.strip_qualifiers(),
+            None => self.infer_type(node),
+        }
+    }
+
+    /// Infer the type of an expression from the declarations seen so far, for
+    /// nodes the type checker has not annotated
+    fn infer_type(&self, node: &Node) -> Type {
+        match node {
+            Node::IntLiteral(value, _) => {
+                if i32::try_from(*value).is_ok() { Type::Int } else { Type::Long }
+            }
+            Node::UIntLiteral(value, _) => {
+                if u32::try_from(*value).is_ok() { Type::UInt } else { Type::ULong }
+            }
+            Node::LongLiteral(_, _) => Type::Long,
+            Node::ULongLiteral(_, _) => Type::ULong,
+            Node::FloatLiteral(_, _) => Type::Double,
+            Node::CharLiteral(_, _) => Type::Int,
+            Node::StringLiteral(_, _) => Type::Pointer(Box::new(Type::Char)),
+            Node::Identifier(name, _) => {
+                if let Some(var) = self.variables.get(name) {
+                    var.type_.clone()
+                } else {
+                    self.globals.get(&self.global_label(name)).cloned().unwrap_or(Type::Long)
+                }
+            }
+            Node::BinaryExpr { op, left, right, .. } => {
+                let left_type = self.expr_type(left);
+                let right_type = self.expr_type(right);
+                let is_pointer = |t: &Type| matches!(t, Type::Pointer(_) | Type::Array(_, _));
+
+                match op {
+                    BinaryOp::Add | BinaryOp::Subtract => {
+                        if is_pointer(&left_type) && is_pointer(&right_type) {
+                            Type::Long
+                        } else if is_pointer(&left_type) {
+                            left_type.decay()
+                        } else if is_pointer(&right_type) {
+                            right_type.decay()
+                        } else {
+                            Type::arithmetic_type(&left_type, &right_type)
+                        }
+                    }
+                    BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo |
+                    BinaryOp::BitwiseAnd | BinaryOp::BitwiseOr | BinaryOp::BitwiseXor => {
+                        Type::arithmetic_type(&left_type, &right_type)
+                    }
+                    BinaryOp::ShiftLeft | BinaryOp::ShiftRight => left_type.promote(),
+                    BinaryOp::Assign => left_type,
+                    _ => Type::Int,
+                }
+            }
+            Node::UnaryExpr { op, expr, .. } => {
+                let expr_type = self.expr_type(expr);
+                match op {
+                    UnaryOp::Negate | UnaryOp::BitwiseNot => expr_type.promote(),
+                    UnaryOp::LogicalNot => Type::Int,
+                    UnaryOp::Dereference => match expr_type {
+                        Type::Pointer(inner) | Type::Array(inner, _) => *inner,
+                        _ => Type::Long,
+                    },
+                    UnaryOp::AddressOf => Type::Pointer(Box::new(expr_type)),
+                }
+            }
+            Node::FunctionCall { name, .. } => match self.functions.get(name) {
+                Some(Type::Function(return_type, _, _)) => (**return_type).clone(),
+                _ => Type::Int,
+            },
+            Node::CommaExpr { right, .. } => self.expr_type(right).decay(),
+            Node::ConditionalExpr { then_expr, else_expr, .. } => {
+                let then_type = self.expr_type(then_expr).decay();
+                let else_type = self.expr_type(else_expr).decay();
+                if then_type.is_arithmetic() && else_type.is_arithmetic() {
+                    Type::arithmetic_type(&then_type, &else_type)
+                } else if matches!(then_type, Type::Pointer(_)) {
+                    then_type
+                } else {
+                    else_type
+                }
+            }
+            Node::MemberAccess { expr, member, .. } => self
+                .member_offset(&self.expr_type(expr), member)
+                .map(|(_, type_)| type_)
+                .unwrap_or(Type::Long),
+            Node::SizeofExpr(_, _) | Node::SizeofType(_, _) => Type::ULong,
+            Node::Cast { type_, .. } => type_.strip_qualifiers(),
+            Node::VaArg(_, type_, _) => type_.strip_qualifiers(),
+            _ => Type::Void,
+        }
+    }
+
+    /// Get the assembly label for a label written in the source of the current function
+    fn source_label(&self, name: &str) -> Result<String> {
+        match &self.current_function {
+            Some(func_name) => Ok(format!(".{}_label_{}", func_name, name)),
+            None => Err(codegen_error(None, "Label outside of function")),
+        }
+    }
+
+    /// Get the assembler operand holding the initial value of a global scalar.
+    /// Global initializers have already been folded to a single constant; pointers
+    /// may also start out with the address of a string literal or another global.
+    fn global_initializer(&mut self, name: &str, type_: &Type, initializer: Option<&Node>) -> Result<String> {
+        let location = initializer.and_then(Node::location);
+        let not_constant = || {
+            codegen_error(location, format!(
+                "Initializer of global variable '{}' is not a constant expression",
+                name
+            ))
+        };
+
+        // Floating globals hold the bits of their value, converted to the global's type
+        if type_.is_floating() {
+            let value = match initializer {
+                None => 0.0,
+                Some(Node::FloatLiteral(value, _)) => *value,
+                Some(Node::IntLiteral(value, _)) => *value as f64,
+                Some(Node::UIntLiteral(value, _) | Node::ULongLiteral(value, _)) => *value as f64,
+                Some(Node::LongLiteral(value, _)) => *value as f64,
+                Some(Node::CharLiteral(value, _)) => *value as u8 as i8 as f64,
+                Some(_) => return Err(not_constant()),
+            };
+            return Ok(match type_ {
+                Type::Float => (value as f32).to_bits().to_string(),
+                _ => value.to_bits().to_string(),
+            });
+        }
+
+        let value = match initializer {
+            None => 0,
+            Some(Node::IntLiteral(value, _) | Node::LongLiteral(value, _)) => *value,
+            Some(Node::FloatLiteral(value, _)) if *type_ == Type::Bool => (*value != 0.0) as i64,
+            Some(Node::FloatLiteral(value, _)) => *value as i64,
+            Some(Node::UIntLiteral(value, _) | Node::ULongLiteral(value, _)) => *value as i64,
+            Some(Node::CharLiteral(value, _)) => *value as u8 as i8 as i64,
+            Some(Node::StringLiteral(value, _)) if matches!(type_, Type::Pointer(_)) => {
+                let index = self.string_literals.len();
+                self.string_literals.push(value.clone());
+                return Ok(format!(".LC{}", index));
+            }
+            Some(Node::UnaryExpr { op: UnaryOp::AddressOf, expr, .. }) if matches!(type_, Type::Pointer(_)) => {
+                return match expr.as_ref() {
+                    Node::Identifier(target, _) if self.globals.contains_key(target) => Ok(self.symbol(target)),
+                    Node::Identifier(target, _) if self.functions.contains_key(target) => Ok(self.function_symbol(target)),
+                    _ => Err(not_constant()),
+                };
+            }
+            // A function pointer may start out pointing to a function
+            Some(Node::Identifier(target, _))
+                if type_.is_function_pointer() && self.functions.contains_key(target) && !self.globals.contains_key(target) =>
+            {
+                return Ok(self.function_symbol(target));
+            }
+            Some(_) => return Err(not_constant()),
+        };
+
+        if (matches!(type_, Type::Array(_, _)) || type_.is_record()) && initializer.is_some() {
+            return Err(codegen_error(location, format!(
+                "Initializers for global aggregate '{}' are not supported",
+                name
+            )));
+        }
+
+        // A _Bool holds 1 for every nonzero value
+        if *type_ == Type::Bool {
+            return Ok(((value != 0) as u8).to_string());
+        }
+
+        Ok(match self.size_of(type_) {
+            1 => (value as u8).to_string(),
+            4 => (value as u32).to_string(),
+            _ => value.to_string(),
+        })
+    }
+
+    /// Get the label of a variable that is not on the stack: a static local of the
+    /// current function, or else the global of that name
+    fn global_label(&self, name: &str) -> String {
+        self.static_locals.get(name).cloned().unwrap_or_else(|| name.to_string())
+    }
+
+    /// Get the assembler name of a label: global symbols follow the target's naming,
+    /// while local labels such as those of static locals start with a '.'
+    fn symbol(&self, label: &str) -> String {
+        if label.starts_with('.') {
+            label.to_string()
+        } else {
+            self.target.symbol(label)
+        }
+    }
+
+    /// Decide where each argument of a call with these types is passed, giving one
+    /// location for each 8-byte word of the arguments
+    fn arg_locations(&self, types: &[Type]) -> Vec<ArgLocation> {
+        let registers = self.target.arg_registers();
+        let (mut next_register, mut next_xmm) = (0, 0);
+        let mut locations = Vec::new();
+
+        for (i, type_) in types.iter().enumerate() {
+            // On Windows the position alone picks the register; System V hands out
+            // integer and XMM registers separately
+            if self.target.positional_args() {
+                (next_register, next_xmm) = (i, i);
+            }
+
+            if self.arg_words(type_) == 2 {
+                // A 16-byte struct goes in two registers, or all of it on the stack
+                if next_register + 2 <= registers.len() {
+                    locations.push(ArgLocation::Register(registers[next_register]));
+                    locations.push(ArgLocation::Register(registers[next_register + 1]));
+                    next_register += 2;
+                } else {
+                    locations.extend([ArgLocation::Stack, ArgLocation::Stack]);
+                }
+            } else if type_.is_floating() && next_xmm < self.target.float_arg_registers() {
+                next_xmm += 1;
+                locations.push(ArgLocation::Xmm(next_xmm - 1));
+            } else if !type_.is_floating() && next_register < registers.len() {
+                next_register += 1;
+                locations.push(ArgLocation::Register(registers[next_register - 1]));
+            } else {
+                locations.push(ArgLocation::Stack);
+            }
+        }
+
+        locations
+    }
+
+    /// Number of 8-byte words an argument of this type takes: two for a struct of
+    /// 9 to 16 bytes on System V, which is passed in two registers, and one for
+    /// anything else
+    fn arg_words(&self, type_: &Type) -> usize {
+        if type_.is_record() && !self.target.structs_by_reference() && self.size_of(type_) > 8 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Get the type of a global variable, which must have been declared
+    fn global_type(&self, name: &str) -> Result<Type> {
+        self.globals
+            .get(name)
+            .cloned()
+            .ok_or_else(|| codegen_error(None, format!("Undeclared global variable: {}", name)))
+    }
+
+    /// Check if a type is a pointer or an array (which behaves as a pointer in expressions)
+    fn is_pointer(&self, type_: &Type) -> bool {
+        matches!(type_, Type::Pointer(_) | Type::Array(_, _))
+    }
+
+    /// Get the size of the element a pointer or array type points to
+    fn pointee_size(&self, type_: &Type) -> Option<usize> {
+        match type_ {
+            // Arithmetic on void pointers moves by single bytes, as in GCC
+            Type::Pointer(inner) | Type::Array(inner, _) => Some(self.size_of(inner).max(1)),
+            _ => None,
+        }
+    }
+
+    /// Check whether an operation on the given type should use unsigned instructions.
+    /// Pointers compare as unsigned addresses.
+    fn is_unsigned(&self, type_: &Type) -> bool {
+        type_.is_unsigned() || self.is_pointer(type_)
+    }
+
+    /// Re-extend RAX after a 64-bit operation so it holds a valid value of the
+    /// given narrower result type, taken from the low bytes
+    fn extend_result(&mut self, type_: &Type) {
+        match type_ {
+            Type::Char => writeln!(self.output, "    movsx rax, al").unwrap(),
+            Type::Bool | Type::UChar => writeln!(self.output, "    movzx eax, al").unwrap(),
+            Type::Int => writeln!(self.output, "    movsxd rax, eax").unwrap(),
+            Type::UInt => self.output.emit("mov", &["eax", "eax"]),
+            _ => {}
+        }
+    }
+
+    /// Load a constant into a 64-bit register. `mov` only takes a sign-extended
+    /// 32-bit immediate, so a constant outside that range needs `movabs`.
+    fn emit_constant(&mut self, register: &str, value: i64) {
+        let mnemonic = if i32::try_from(value).is_ok() { "mov" } else { "movabs" };
+        writeln!(self.output, "    {} {}, {}", mnemonic, register, value).unwrap();
+    }
+
+    /// Load a value of the given type from memory into RAX, sign- or zero-extending
+    /// narrow types so the full register holds the value
+    fn emit_load(&mut self, type_: &Type, address: &str) {
+        self.emit_load_into("rax", type_, address);
+    }
+
+    /// Load a value of the given type from memory into a 64-bit register
+    fn emit_load_into(&mut self, register: &str, type_: &Type, address: &str) {
+        match type_ {
+            Type::Char => writeln!(self.output, "    movsx {}, byte ptr [{}]", register, address).unwrap(),
+            Type::Bool | Type::UChar => writeln!(self.output, "    movzx {}, byte ptr [{}]", register, address).unwrap(),
+            Type::Int => writeln!(self.output, "    movsxd {}, dword ptr [{}]", register, address).unwrap(),
+            // Writing a 32-bit register implicitly zeroes its upper half
+            Type::UInt => {
+                self.output.emit("mov", &[&sub_register(register, 4), &format!("dword ptr [{}]", address)])
+            }
+            // Floating values are computed as doubles, so a float is widened on load
+            Type::Float => {
+                writeln!(self.output, "    movss xmm0, dword ptr [{}]", address).unwrap();
+                writeln!(self.output, "    cvtss2sd xmm0, xmm0").unwrap();
+                writeln!(self.output, "    movq {}, xmm0", register).unwrap();
+            }
+            _ => self.output.emit("mov", &[register, &format!("qword ptr [{}]", address)]),
+        }
+    }
+
+    /// Store the part of a register that holds a value of the given type to memory
+    fn emit_store(&mut self, type_: &Type, address: &str, register: &str) {
+        // A float is narrowed from the double the register holds
+        if let Type::Float = type_ {
+            writeln!(self.output, "    movq xmm0, {}", register).unwrap();
+            writeln!(self.output, "    cvtsd2ss xmm0, xmm0").unwrap();
+            writeln!(self.output, "    movss dword ptr [{}], xmm0", address).unwrap();
+            return;
+        }
+
+        let size = match self.size_of(type_) {
+            1 => 1,
+            4 => 4,
+            _ => 8,
+        };
+        self.output.emit("mov", &[&format!("{} ptr [{}]", ptr_size(size), address), &sub_register(register, size)]);
+    }
+
+    /// Convert the value in RAX from one arithmetic type to another. Floating values
+    /// are held in RAX as the bits of a double, moving through XMM0 to be converted.
+    /// Unsigned longs above the signed range are not converted correctly yet.
+    fn emit_convert(&mut self, from: &Type, to: &Type) {
+        if *to == Type::Bool && *from != Type::Bool {
+            // Any nonzero scalar converts to 1. Doubling a double's bits drops its
+            // sign, so both zeros become 0 and every other value stays nonzero.
+            if from.is_floating() {
+                writeln!(self.output, "    add rax, rax").unwrap();
+            } else {
+                let register = sub_register("rax", self.size_of(from));
+                writeln!(self.output, "    test {0}, {0}", register).unwrap();
+            }
+            writeln!(self.output, "    setne al").unwrap();
+            writeln!(self.output, "    movzx eax, al").unwrap();
+        } else if from.is_integer() && to.is_floating() {
+            writeln!(self.output, "    cvtsi2sd xmm0, rax").unwrap();
+            writeln!(self.output, "    movq rax, xmm0").unwrap();
+        } else if from.is_floating() && to.is_integer() {
+            // Conversion to an integer truncates toward zero
+            writeln!(self.output, "    movq xmm0, rax").unwrap();
+            writeln!(self.output, "    cvttsd2si rax, xmm0").unwrap();
+            self.extend_result(to);
+        }
+    }
+
+    /// At -O1, compute the subexpressions a statement's expression evaluates more
+    /// than once, each into a temporary in the frame that its occurrences load
+    /// from instead. Returns the stack offset to restore when the statement is done.
+    fn compute_common_values(&mut self, expr: &Node) -> Result<usize> {
+        let stack_offset = self.stack_offset;
+        if self.opt_level == 0 {
+            return Ok(stack_offset);
+        }
+
+        // Smaller expressions come first, so larger ones reuse their values
+        for value in cse::common_subexpressions(expr, &self.types) {
+            self.generate_node(value)?;
+            let offset = self.allocate_slot(8, 8);
+            self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", offset), "rax"]);
+            self.common_values.push((value.clone(), offset));
+        }
+        Ok(stack_offset)
+    }
+
+    /// Forget the subexpressions computed for a statement and free their temporaries
+    fn release_common_values(&mut self, stack_offset: usize) {
+        self.common_values.clear();
+        self.stack_offset = stack_offset;
+    }
+
+    /// Evaluate an expression and convert its value to the given type, as happens
+    /// implicitly on assignment, initialization, argument passing and return
+    fn generate_converted(&mut self, node: &Node, type_: &Type) -> Result<()> {
+        self.generate_node(node)?;
+        let from = self.expr_type(node);
+        self.emit_convert(&from, &type_.strip_qualifiers());
+        Ok(())
+    }
+
+    /// Evaluate one arm of a conditional expression into RAX as a value of the
+    /// expression's type. Structs are left as their address, as any struct
+    /// expression is.
+    fn generate_conditional_arm(&mut self, arm: &Node, type_: &Type) -> Result<()> {
+        if !type_.is_arithmetic() {
+            return self.generate_node(arm);
+        }
+
+        self.generate_converted(arm, type_)?;
+        // An int arm of an unsigned int result keeps only its low 32 bits
+        if self.expr_type(arm) != *type_ {
+            self.extend_result(type_);
+        }
+        Ok(())
+    }
+
+    /// Evaluate a condition into RAX so that it is zero exactly when it is false.
+    /// A floating zero may be negative, so the sign bit is shifted out.
+    fn generate_condition(&mut self, condition: &Node) -> Result<()> {
+        self.generate_node(condition)?;
+        if self.expr_type(condition).is_floating() {
+            writeln!(self.output, "    shl rax, 1").unwrap();
+        }
+        Ok(())
+    }
+
+    /// Generate an arithmetic operation or comparison on floating operands, both
+    /// converted to the double the operation is carried out in. Float operands are
+    /// computed in double precision too and only rounded when they are stored.
+    fn generate_floating_binary(&mut self, op: &BinaryOp, left: &Node, right: &Node) -> Result<()> {
+        self.generate_converted(left, &Type::Double)?;
+        self.save_operand();
+        self.generate_converted(right, &Type::Double)?;
+        let saved = self.restore_operand();
+
+        writeln!(self.output, "    movq xmm0, {}", saved).unwrap();
+        writeln!(self.output, "    movq xmm1, rax").unwrap();
+
+        let instruction = match op {
+            BinaryOp::Add => "addsd",
+            BinaryOp::Subtract => "subsd",
+            BinaryOp::Multiply => "mulsd",
+            BinaryOp::Divide => "divsd",
+            _ => {
+                // comisd sets ZF, PF and CF when either operand is NaN, so only the
+                // above conditions are false for unordered operands; less-than
+                // comparisons swap the operands to use them too
+                match op {
+                    BinaryOp::Less | BinaryOp::LessEqual => {
+                        writeln!(self.output, "    comisd xmm1, xmm0").unwrap();
+                    }
+                    _ => writeln!(self.output, "    comisd xmm0, xmm1").unwrap(),
+                }
+                match op {
+                    BinaryOp::Equal => {
+                        writeln!(self.output, "    sete al").unwrap();
+                        writeln!(self.output, "    setnp cl").unwrap();
+                        writeln!(self.output, "    and al, cl").unwrap();
+                    }
+                    BinaryOp::NotEqual => {
+                        writeln!(self.output, "    setne al").unwrap();
+                        writeln!(self.output, "    setp cl").unwrap();
+                        writeln!(self.output, "    or al, cl").unwrap();
+                    }
+                    BinaryOp::Less | BinaryOp::Greater => writeln!(self.output, "    seta al").unwrap(),
+                    _ => writeln!(self.output, "    setae al").unwrap(),
+                }
+                writeln!(self.output, "    movzx rax, al").unwrap();
+                return Ok(());
+            }
+        };
+
+        writeln!(self.output, "    {} xmm0, xmm1", instruction).unwrap();
+        writeln!(self.output, "    movq rax, xmm0").unwrap();
+        Ok(())
+    }
+
+    /// Copy a struct of the given size between two addresses, using RDX for the data
+    fn emit_copy(&mut self, destination: &str, source: &str, size: usize) {
+        for (offset, chunk) in chunks(size) {
+            let data = sub_register("rdx", chunk);
+            self.output.emit("mov", &[&data, &format!("{} ptr [{}+{}]", ptr_size(chunk), source, offset)]);
+            self.output.emit("mov", &[&format!("{} ptr [{}+{}]", ptr_size(chunk), destination, offset), &data]);
+        }
+    }
+
+    /// Load the bytes of a small struct whose address is in RAX into RAX and, past
+    /// the first 8 bytes, RDX. Only the struct's own bytes are read; each 8-byte half
+    /// is assembled from its pieces with R8 holding the address and R9 the piece.
+    fn emit_load_struct(&mut self, size: usize) {
+        self.output.emit("mov", &["r8", "rax"]);
+
+        for (register, start) in [("rdx", 8), ("rax", 0)] {
+            if size <= start {
+                continue;
+            }
+
+            // Load the highest piece first, then shift each lower one in below it
+            let pieces = chunks((size - start).min(8));
+            for (i, (offset, chunk)) in pieces.iter().rev().enumerate() {
+                let target = if i == 0 { register } else { "r9" };
+                let address = format!("r8+{}", start + offset);
+                match chunk {
+                    4 => self.output.emit("mov", &[&sub_register(target, 4), &format!("dword ptr [{}]", address)]),
+                    8 => self.output.emit("mov", &[target, &format!("qword ptr [{}]", address)]),
+                    _ => writeln!(self.output, "    movzx {}, {} ptr [{}]", target, ptr_size(*chunk), address).unwrap(),
+                }
+                if i > 0 {
+                    writeln!(self.output, "    shl {}, {}", register, chunk * 8).unwrap();
+                    writeln!(self.output, "    or {}, r9", register).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Store a small struct held in RAX and RDX, as left by `emit_load_struct` or a
+    /// call, to memory, writing only the struct's own bytes
+    fn emit_store_struct(&mut self, address: &str, size: usize) {
+        for (register, start) in [("rax", 0), ("rdx", 8)] {
+            if size <= start {
+                continue;
+            }
+
+            let pieces = chunks((size - start).min(8));
+            for (i, (offset, chunk)) in pieces.iter().enumerate() {
+                self.output.emit("mov", &[&format!("{} ptr [{}+{}]", ptr_size(*chunk), address, start + offset), &sub_register(register, *chunk)]);
+                // Move the next piece down into the low bytes
+                if i + 1 < pieces.len() {
+                    writeln!(self.output, "    shr {}, {}", register, chunk * 8).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Get the byte offset and type of a struct or union member. Struct members are
+    /// laid out one after another in declaration order, matching `size_of`; union
+    /// members all start at offset 0.
+    fn member_offset(&self, struct_type: &Type, member: &str) -> Result<(usize, Type)> {
+        if let Type::Struct(_, members) | Type::Union(_, members) = struct_type {
+            let mut offset = 0;
+            for (name, type_) in members {
+                if name == member {
+                    return Ok((offset, type_.strip_qualifiers()));
+                }
+                if let Type::Struct(_, _) = struct_type {
+                    offset += self.size_of(type_);
+                }
+            }
+        }
+        Err(codegen_error(None, format!("No member named {} in {:?}", member, struct_type)))
+    }
+
+    /// Leave the address of a struct member in RAX
+    fn generate_member_address(&mut self, expr: &Node, member: &str) -> Result<()> {
+        // A struct returned by a call only exists in registers
+        if let Node::FunctionCall { .. } | Node::IndirectCall { .. } = expr {
+            return Err(codegen_error(
+                expr.location(),
+                "Member access on a struct returned by value is not supported yet",
+            ));
+        }
+
+        // Struct-typed lvalues evaluate to their address
+        let (offset, _) = self.member_offset(&self.expr_type(expr), member)?;
+        self.generate_node(expr)?;
+        if offset > 0 {
+            writeln!(self.output, "    add rax, {}", offset).unwrap();
+        }
+        Ok(())
+    }
+
+    /// Call a function with the target's calling convention, leaving its result in RAX
+    fn generate_call(&mut self, callee: Callee, args: &[Node]) -> Result<()> {
+        if let Callee::Named(name) = callee {
+            if !self.called_functions.iter().any(|called| called == name) {
+                self.called_functions.push(name.to_string());
+            }
+        }
+
+        // Scratch registers holding operands of an enclosing expression are
+        // volatile and would not survive the call, so save the ones in use
+        let live_registers: Vec<&str> = SCRATCH_REGISTERS
+            .iter()
+            .take(self.scratch_depth)
+            .filter(|register| !CALLEE_SAVED_REGISTERS.contains(register))
+            .copied()
+            .collect();
+        for register in &live_registers {
+            self.output.emit("push", &[register]);
+        }
+
+        let func_type = match callee {
+            Callee::Named(name) => self.functions.get(name).cloned(),
+            Callee::Pointer(pointer) => match self.expr_type(pointer) {
+                Type::Pointer(func_type) => Some(*func_type),
+                _ => None,
+            },
+        };
+        let (return_type, param_types, is_variadic) = match func_type {
+            Some(Type::Function(return_type, param_types, is_variadic)) => (*return_type, param_types, is_variadic),
+            _ => (Type::Int, Vec::new(), true),
+        };
+
+        // Arguments past the fixed parameters keep their own type
+        let arg_types: Vec<Type> = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| param_types.get(i).cloned().unwrap_or_else(|| self.expr_type(arg)))
+            .collect();
+        let words: usize = arg_types.iter().map(|type_| self.arg_words(type_)).sum();
+
+        // Reserve one 8-byte slot per argument word, and at least the shadow space
+        // (32 bytes on Windows) the callee may spill its register arguments into.
+        // On Windows, arguments beyond the first 4 then sit right above the shadow
+        // space, where the callee expects them. The frame leaves RSP 16-byte
+        // aligned, so the space is padded to keep it aligned at the call despite
+        // any pushes
+        let pushed = live_registers.len() + self.scratch_depth.saturating_sub(SCRATCH_REGISTERS.len());
+        let shadow_space = self.target.shadow_space();
+        let arg_space = self.align_to(shadow_space.max(words * 8) + pushed * 8, 16) - pushed * 8;
+        writeln!(self.output, "    sub rsp, {}", arg_space).unwrap();
+
+        // Copies of structs passed by reference live in the frame until the call
+        // returns
+        let stack_offset = self.stack_offset;
+
+        // Evaluate the arguments left to right into their slots. Nested calls
+        // and spills leave RSP where it was, so the slots stay put, and nothing
+        // is kept in a register while the next argument is evaluated
+        let mut word = 0;
+        for (arg, arg_type) in args.iter().zip(&arg_types) {
+            let slot = word * 8;
+            word += self.arg_words(arg_type);
+
+            if self.expr_type(arg).is_record() {
+                let size = self.size_of(&self.expr_type(arg));
+                if size <= 8 {
+                    // A small struct is passed by value in a single word
+                    self.generate_struct_value(arg, size)?;
+                } else if self.target.structs_by_reference() {
+                    // A larger one is copied, and the copy's address passed instead
+                    let copy = self.allocate_slot(size, 8);
+                    writeln!(self.output, "    lea rax, [rbp-{}]", copy).unwrap();
+                    self.generate_struct_store(arg, size)?;
+                } else if size <= 16 {
+                    // System V splits it across two words
+                    self.generate_struct_value(arg, size)?;
+                    self.output.emit("mov", &[&format!("qword ptr [rsp+{}]", slot + 8), "rdx"]);
+                } else {
+                    return Err(codegen_error(
+                        arg.location(),
+                        "Passing structs larger than 16 bytes by value is not supported yet",
+                    ));
+                }
+            } else {
+                self.generate_converted(arg, arg_type)?;
+                if let Type::Float = arg_type {
+                    // A float parameter is passed in single precision
+                    writeln!(self.output, "    movq xmm0, rax").unwrap();
+                    writeln!(self.output, "    cvtsd2ss xmm0, xmm0").unwrap();
+                    writeln!(self.output, "    movd eax, xmm0").unwrap();
+                }
+            }
+            self.output.emit("mov", &[&format!("qword ptr [rsp+{}]", slot), "rax"]);
+        }
+
+        // The address of a function called through a pointer waits in R11, which
+        // passes no arguments, while they are loaded
+        if let Callee::Pointer(pointer) = callee {
+            self.generate_node(pointer)?;
+            self.output.emit("mov", &["r11", "rax"]);
+        }
+
+        // Load the register arguments right before the call. On Windows a floating
+        // argument also goes in the integer register of its position, for
+        // variadic callees, which may expect either
+        let locations = self.arg_locations(&arg_types);
+        let integer_registers = self.target.arg_registers();
+        for (i, location) in locations.iter().enumerate() {
+            match location {
+                ArgLocation::Register(register) => {
+                    self.output.emit("mov", &[register, &format!("qword ptr [rsp+{}]", i * 8)]);
+                }
+                ArgLocation::Xmm(xmm) => {
+                    writeln!(self.output, "    movq xmm{}, qword ptr [rsp+{}]", xmm, i * 8).unwrap();
+                    if self.target.positional_args() {
+                        self.output.emit("mov", &[integer_registers[i], &format!("qword ptr [rsp+{}]", i * 8)]);
+                    }
+                }
+                ArgLocation::Stack => {}
+            }
+        }
+
+        // System V passes the remaining arguments in order at the bottom of the
+        // argument space, so move them down over the slots already loaded
+        if !self.target.positional_args() {
+            let stack_args = locations.iter().enumerate().filter(|(_, location)| **location == ArgLocation::Stack);
+            for (slot, (i, _)) in stack_args.enumerate() {
+                if slot != i {
+                    self.output.emit("mov", &["rax", &format!("qword ptr [rsp+{}]", i * 8)]);
+                    self.output.emit("mov", &[&format!("qword ptr [rsp+{}]", slot * 8), "rax"]);
+                }
+            }
+
+            // A variadic callee learns from AL how many XMM registers hold arguments
+            if is_variadic {
+                let xmm_count = locations.iter().filter(|location| matches!(location, ArgLocation::Xmm(_))).count();
+                self.output.emit("mov", &["eax", &xmm_count.to_string()]);
+            }
+        }
+
+        // Call the function by name, or at its address
+        // This will jump to the function and save the return address
+        match callee {
+            Callee::Named(name) => writeln!(self.output, "    call {}", self.symbol(name)).unwrap(),
+            Callee::Pointer(_) => writeln!(self.output, "    call r11").unwrap(),
+        }
+
+        // Release the argument space and copies, and restore the saved registers
+        writeln!(self.output, "    add rsp, {}", arg_space).unwrap();
+        self.stack_offset = stack_offset;
+        for register in live_registers.iter().rev() {
+            self.output.emit("pop", &[register]);
+        }
+
+        // Function return value is already in RAX per calling convention,
+        // except for floating values, which come back in XMM0. A narrow value
+        // only fills the low bytes, so the rest of RAX is extended from it, as
+        // an int such as getchar's EOF must be -1 in all 64 bits
+        match return_type.strip_qualifiers() {
+            Type::Float => {
+                writeln!(self.output, "    cvtss2sd xmm0, xmm0").unwrap();
+                writeln!(self.output, "    movq rax, xmm0").unwrap();
+            }
+            Type::Double => writeln!(self.output, "    movq rax, xmm0").unwrap(),
+            other => self.extend_result(&other),
+        }
+        Ok(())
+    }
+
+    /// Check if a name refers to a local or global variable in the current scope,
+    /// rather than a function
+    fn is_variable(&self, name: &str) -> bool {
+        self.variables.contains_key(name) || self.globals.contains_key(&self.global_label(name))
+    }
+
+    /// Get the symbol of a function whose address is stored in data, declaring it
+    /// if it is defined elsewhere
+    fn function_symbol(&mut self, name: &str) -> String {
+        if !self.called_functions.iter().any(|called| called == name) {
+            self.called_functions.push(name.to_string());
+        }
+        self.symbol(name)
+    }
+
+    /// Leave the address of a function in RAX. A function defined elsewhere may be in
+    /// a shared library, so where executables are position-independent its address
+    /// is read from the global offset table.
+    fn emit_function_address(&mut self, name: &str) {
+        let symbol = self.function_symbol(name);
+        if self.defined_functions.contains(name) || !self.target.position_independent() {
+            writeln!(self.output, "    lea rax, [rip + {}]", symbol).unwrap();
+        } else {
+            self.output.emit("mov", &["rax", &format!("qword ptr [rip + {}@GOTPCREL]", symbol)]);
+        }
+    }
+
+    /// Leave the address of an lvalue in RAX: a variable, a dereference (which
+    /// includes array subscripts) or a struct member
+    fn generate_address(&mut self, node: &Node) -> Result<()> {
+        match node {
+            // A function's name already stands for its address
+            Node::Identifier(name, _) if !self.is_variable(name) && self.functions.contains_key(name) => {
+                self.emit_function_address(name);
+                Ok(())
+            }
+            Node::Identifier(name, _) => {
+                if let Some(var) = self.variables.get(name) {
+                    // For local variables, calculate address relative to RBP
+                    // lea (Load Effective Address) calculates the address without dereferencing
+                    writeln!(self.output, "    lea rax, [rbp-{}]", var.offset).unwrap();
+                } else {
+                    // For global variables, get the address of the global label
+                    writeln!(self.output, "    lea rax, [rip + {}]", self.symbol(&self.global_label(name))).unwrap();
+                }
+                Ok(())
+            }
+            // The address of *p is the value of p, so the final load is skipped
+            Node::UnaryExpr { op: UnaryOp::Dereference, expr, .. } => self.generate_node(expr),
+            Node::MemberAccess { expr, member, .. } => self.generate_member_address(expr, member),
+            _ => Err(codegen_error(node.location(), "Cannot take address of non-lvalue")),
+        }
+    }
+
+    /// Evaluate a small struct into RAX and RDX. Calls already return it there, any
+    /// other struct expression evaluates to its address and is loaded from memory.
+    fn generate_struct_value(&mut self, node: &Node, size: usize) -> Result<()> {
+        self.generate_node(node)?;
+        if !matches!(node, Node::FunctionCall { .. } | Node::IndirectCall { .. }) {
+            self.emit_load_struct(size);
+        }
+        Ok(())
+    }
+
+    /// Copy a struct into the memory at the address in RAX, whether it comes from
+    /// another struct in memory or from a call. RAX keeps the destination address.
+    fn generate_struct_store(&mut self, value: &Node, size: usize) -> Result<()> {
+        self.save_operand();
+
+        self.generate_node(value)?;
+        let destination = self.restore_operand();
+        if let Node::FunctionCall { .. } | Node::IndirectCall { .. } = value {
+            self.emit_store_struct(destination, size);
+        } else {
+            self.emit_copy(destination, "rax", size);
+        }
+
+        self.output.emit("mov", &["rax", destination]);
+        Ok(())
+    }
+
+    /// Set aside the value in RAX while another operand is evaluated. It is kept in
+    /// the next free scratch register, or spilled to the stack once all are taken.
+    fn save_operand(&mut self) {
+        match SCRATCH_REGISTERS.get(self.scratch_depth) {
+            Some(&register) => {
+                self.output.emit("mov", &[register, "rax"]);
+                if CALLEE_SAVED_REGISTERS.contains(&register) && !self.saved_registers.contains(&register) {
+                    self.saved_registers.push(register);
+                }
+            }
+            None => self.output.emit("push", &["rax"]),
+        }
+        self.scratch_depth += 1;
+    }
+
+    /// Release the operand set aside by the matching `save_operand` and return the
+    /// register that now holds it
+    fn restore_operand(&mut self) -> &'static str {
+        self.scratch_depth -= 1;
+        match SCRATCH_REGISTERS.get(self.scratch_depth) {
+            Some(&register) => register,
+            None => {
+                self.output.emit("pop", &["rcx"]);
+                "rcx"
+            }
+        }
+    }
+
+    /// Evaluate the operands of a binary operation, leaving the left value in RAX and
+    /// returning where the right value is: a small constant becomes an immediate and a
+    /// scalar local is loaded straight into RCX, so neither needs the left value set
+    /// aside. Otherwise the left value waits in a scratch register while the right
+    /// operand is evaluated.
+    fn generate_operands(&mut self, op: &BinaryOp, left: &Node, right: &Node, commutative: bool) -> Result<String> {
+        self.generate_node(left)?;
+
+        if let Some(value) = self.immediate_value(right) {
+            let takes_immediate = match op {
+                BinaryOp::ShiftLeft | BinaryOp::ShiftRight => (0..64).contains(&value),
+                BinaryOp::Divide | BinaryOp::Modulo => false,
+                _ => i32::try_from(value).is_ok(),
+            };
+            if takes_immediate {
+                return Ok(value.to_string());
+            }
+            self.emit_constant("rcx", value);
+            return Ok("rcx".to_string());
+        }
+
+        if let Node::Identifier(name, _) = right {
+            if let Some(var) = self.variables.get(name) {
+                if var.type_.is_integer() || matches!(var.type_, Type::Pointer(_)) {
+                    let (type_, address) = (var.type_.clone(), format!("rbp-{}", var.offset));
+                    self.emit_load_into("rcx", &type_, &address);
+                    return Ok("rcx".to_string());
+                }
+            }
+        }
+
+        self.save_operand();
+        self.generate_node(right)?;
+        let saved = self.restore_operand();
+
+        // The left value is now in the scratch register and the right one in RAX;
+        // swap them back unless their order does not matter
+        if !commutative {
+            writeln!(self.output, "    xchg rax, {}", saved).unwrap();
+        }
+        Ok(saved.to_string())
+    }
+
+    /// Get the value of a constant operand that can be used without evaluating it
+    fn immediate_value(&self, node: &Node) -> Option<i64> {
+        match node {
+            Node::IntLiteral(value, _) | Node::LongLiteral(value, _) => Some(*value),
+            Node::UIntLiteral(value, _) | Node::ULongLiteral(value, _) => i64::try_from(*value).ok(),
+            Node::CharLiteral(value, _) => Some(*value as u8 as i8 as i64),
+            _ => None,
+        }
+    }
+
+    /// Multiply an integer operand by an element size for pointer arithmetic,
+    /// returning the operand that holds the scaled value
+    fn scale_operand(&mut self, operand: String, size: usize) -> String {
+        if let Ok(value) = operand.parse::<i64>() {
+            let scaled = value * size as i64;
+            if i32::try_from(scaled).is_ok() {
+                return scaled.to_string();
+            }
+            self.emit_constant("rcx", scaled);
+            return "rcx".to_string();
+        }
+
+        writeln!(self.output, "    imul {0}, {0}, {1}", operand, size).unwrap();
+        operand
+    }
+
     /// Generate code for a program
     pub fn generate(&mut self, program: &Node) -> Result<String> {
         self.emit_header();
+        let externs_at = self.output.instructions.len();
 
         match program {
             Node::Program(declarations) => {
-                // First pass: collect all function declarations
-                for decl in declarations {
+                // First pass: collect all function declarations, and pick the declaration
+                // that defines each global: the one with an initializer, or else the first
+                // that is not extern
+                let mut definitions: HashMap<&str, usize> = HashMap::new();
+                for (i, decl) in declarations.iter().enumerate() {
                     if let Node::FunctionDecl {
                         name,
                         return_type,
                         params,
+                        body,
                         ..
                     } = decl
                     {
+                        if body.is_some() {
+                            self.defined_functions.insert(name.clone());
+                        }
+
                         // Check if the function is variadic (has ... in parameters)
                         let is_variadic = params.iter().any(|(name, _)| name == "...");
 
@@ -91,36 +1197,68 @@ impl CodeGenerator {
                             .collect();
 
                         let func_type = Type::Function(Box::new(return_type.clone()), param_types, is_variadic);
-                        self.variables.insert(name.clone(), Variable {
-                            offset: 0,
-                            type_: func_type,
-                        });
+                        self.functions.insert(name.clone(), func_type.strip_qualifiers());
+                    } else if let Node::VarDecl { name, type_, storage, initializer, .. } = decl {
+                        self.globals.insert(name.clone(), type_.strip_qualifiers());
+                        if initializer.is_some() {
+                            definitions.insert(name, i);
+                        } else if *storage != StorageClass::Extern {
+                            definitions.entry(name).or_insert(i);
+                        }
                     }
                 }
 
-                // Second pass: generate code for all declarations
-                for decl in declarations {
+                // Second pass: generate code for all declarations, emitting each global once
+                for (i, decl) in declarations.iter().enumerate() {
+                    if let Node::VarDecl { name, .. } = decl {
+                        if definitions.get(name.as_str()) != Some(&i) {
+                            continue;
+                        }
+                    }
                     self.generate_declaration(decl)?;
                 }
 
+                // Signed arithmetic that overflowed under -ftrap-overflow ends up here
+                if self.overflow_trap_used {
+                    self.emit_overflow_trap();
+                }
+
+                // Declare the functions that are called but defined elsewhere
+                let externs: Vec<Instruction> = self
+                    .called_functions
+                    .iter()
+                    .filter(|name| !self.defined_functions.contains(name.as_str()))
+                    .map(|name| Instruction::Directive(format!(".extern {}", self.symbol(name))))
+                    .collect();
+                self.output.instructions.splice(externs_at..externs_at, externs);
+
                 // Generate string literals
                 if !self.string_literals.is_empty() {
-                    writeln!(self.output, "\n.section .rodata").unwrap();
+                    writeln!(self.output, "\n{}", self.target.rodata_section()).unwrap();
                     for (i, s) in self.string_literals.iter().enumerate() {
                         writeln!(self.output, ".LC{}:", i).unwrap();
 
-                        // Replace newlines with explicit escape sequences
-                        let escaped = s.replace("\n", "\\n");
-
-                        // Split the string into multiple .ascii directives to avoid newline issues
-                        writeln!(self.output, "    .ascii \"{}\"", escaped).unwrap();
+                        // Each character is a byte; quotes, backslashes and control
+                        // characters are escaped so the assembler reads them back as is
+                        let bytes: Vec<u8> = literal_bytes(s).collect();
+                        writeln!(self.output, "    .ascii \"{}\"", escape_ascii(&bytes)).unwrap();
                         writeln!(self.output, "    .byte 0").unwrap(); // Null terminator
                     }
                 }
 
-                Ok(self.output.clone())
+                if let Some(section) = self.target.stack_note_section() {
+                    writeln!(self.output, "    {}", section).unwrap();
+                }
+
+                // Clean up redundant stack traffic before producing the final text
+                if self.opt_level >= 1 {
+                    peephole::remove_unreachable(&mut self.output.instructions);
+                }
+                peephole::optimize(&mut self.output.instructions);
+
+                Ok(self.output.to_string())
             }
-            _ => Err(codegen_error("Expected program node")),
+            _ => Err(codegen_error(program.location(), "Expected program node")),
         }
     }
 
@@ -128,15 +1266,69 @@ impl CodeGenerator {
     fn emit_header(&mut self) {
         writeln!(self.output, "    .intel_syntax noprefix").unwrap();
         writeln!(self.output, "    .text").unwrap();
-        writeln!(self.output, "    .globl main").unwrap();
+        writeln!(self.output, "    .globl {}", self.symbol("main")).unwrap();
+    }
+
+    /// Emit the data of a variable with static storage under the given label, switching
+    /// to the data section and back so it can also appear inside a function
+    fn emit_static_variable(&mut self, label: &str, name: &str, type_: &Type, initializer: Option<&Node>, exported: bool, location: &Location) -> Result<()> {
+        let type_ = &type_.strip_qualifiers();
+
+        // A char array initialized with a string holds its characters, then zeros
+        if let (Type::Array(_, Some(size)), Some(Node::StringLiteral(value, _))) = (type_, initializer) {
+            let bytes = string_bytes(value, *size);
+            let length = value.chars().count().min(*size);
+
+            writeln!(self.output, "    .data").unwrap();
+            let label = self.symbol(label);
+            if exported {
+                writeln!(self.output, "    .globl {}", label).unwrap();
+            }
+            writeln!(self.output, "{}:", label).unwrap();
+            writeln!(self.output, "    .ascii \"{}\"", escape_ascii(&bytes[..length])).unwrap();
+            if length < *size {
+                writeln!(self.output, "    .zero {}", size - length).unwrap();
+            }
+            writeln!(self.output, "    .text").unwrap();
+            return Ok(());
+        }
+        let initial = self.global_initializer(name, type_, initializer)?;
+
+        // The directive that emits a scalar's initial value; aggregates have none
+        // because they cannot have initializers yet
+        let directive = match type_ {
+            Type::Bool | Type::Char | Type::UChar => Some(".byte"),
+            Type::Int | Type::UInt | Type::Float => Some(".long"),
+            Type::Long | Type::ULong | Type::Double | Type::Pointer(_) => Some(".quad"),
+            Type::Array(_, Some(_)) | Type::Struct(_, _) | Type::Union(_, _) => None,
+            _ => {
+                return Err(codegen_error(Some(location), format!(
+                    "Unsupported global variable type: {:?}",
+                    type_
+                )));
+            }
+        };
 
-        // Declare external functions
-        writeln!(self.output, "    .extern puts").unwrap();
-        writeln!(self.output, "    .extern printf").unwrap();
-        writeln!(self.output, "    .extern scanf").unwrap();
-        writeln!(self.output, "    .extern putchar").unwrap();
-        writeln!(self.output, "    .extern getchar").unwrap();
-        writeln!(self.output, "    .extern atoi").unwrap();
+        // Variables that start out all zero go in .bss, which takes no space in
+        // the object file
+        let directive = directive.filter(|_| initial != "0");
+        let section = if directive.is_some() { ".data" } else { ".bss" };
+
+        writeln!(self.output, "    {}", section).unwrap();
+        let label = self.symbol(label);
+        if exported {
+            writeln!(self.output, "    .globl {}", label).unwrap();
+        }
+        writeln!(self.output, "{}:", label).unwrap();
+
+        match directive {
+            Some(directive) => writeln!(self.output, "    {} {}", directive, initial).unwrap(),
+            None => writeln!(self.output, "    .zero {}", self.size_of(type_)).unwrap(),
+        }
+
+        writeln!(self.output, "    .text").unwrap();
+
+        Ok(())
     }
 
     /// Generate code for a declaration
@@ -145,39 +1337,18 @@ impl CodeGenerator {
             Node::VarDecl {
                 name,
                 type_,
-                initializer: _,
-                ..
+                storage,
+                initializer,
+                location,
             } => {
-                // Global variable
-                writeln!(self.output, "    .data").unwrap();
-                writeln!(self.output, "    .globl {}", name).unwrap();
-                writeln!(self.output, "{}:", name).unwrap();
-
-                match type_ {
-                    Type::Char => {
-                        writeln!(self.output, "    .byte 0").unwrap();
-                    }
-                    Type::Int => {
-                        writeln!(self.output, "    .long 0").unwrap();
-                    }
-                    Type::Long => {
-                        writeln!(self.output, "    .quad 0").unwrap();
-                    }
-                    Type::Array(base, Some(size)) => {
-                        let elem_size = self.size_of(base);
-                        writeln!(self.output, "    .zero {}", elem_size * size).unwrap();
-                    }
-                    _ => {
-                        return Err(codegen_error(format!(
-                            "Unsupported global variable type: {:?}",
-                            type_
-                        )));
-                    }
+                // An extern declaration only names a variable defined elsewhere
+                if *storage == StorageClass::Extern && initializer.is_none() {
+                    return Ok(());
                 }
 
-                writeln!(self.output, "    .text").unwrap();
-
-                Ok(())
+                // A static global is not visible outside this file
+                let exported = *storage != StorageClass::Static;
+                self.emit_static_variable(name, name, type_, initializer.as_deref(), exported, location)
             }
             Node::FunctionDecl {
                 name,
@@ -188,43 +1359,137 @@ impl CodeGenerator {
                 if let Some(body) = body {
                     self.current_function = Some(name.clone());
                     self.variables.clear();
+                    self.static_locals.clear();
                     self.stack_offset = 0;
+                    self.frame_size = 0;
+                    self.saved_registers.clear();
 
                     // Function prologue
-                    writeln!(self.output, "{}:", name).unwrap();
-                    writeln!(self.output, "    push rbp").unwrap();
-                    writeln!(self.output, "    mov rbp, rsp").unwrap();
-
-                    // Allocate space for parameters
-                    // Windows x64 calling convention uses rcx, rdx, r8, r9 for the first 4 parameters
-                    let param_registers = ["rcx", "rdx", "r8", "r9"];
-                    for (i, (param_name, param_type)) in params.iter().enumerate() {
-                        self.stack_offset += 8; // All parameters take 8 bytes on the stack
+                    writeln!(self.output, "{}:", self.symbol(name)).unwrap();
+                    if self.debug_info {
+                        self.emit_location(node);
+                    }
+                    self.output.emit("push", &["rbp"]);
+                    self.output.emit("mov", &["rbp", "rsp"]);
+
+                    // The whole frame is allocated here once the body has been generated
+                    // and its size is known; locals then sit at fixed [rbp-offset] slots
+                    let frame_setup = self.output.instructions.len();
+
+                    // Store the parameters in the frame
+                    // They arrive in the registers the target's calling convention assigns.
+                    // On Windows the caller's shadow space for them starts at [rbp+16], above
+                    // the return address; parameters are saved in this frame instead, so it
+                    // stays unused
+                    let is_variadic = params.iter().any(|(name, _)| name == "...");
+                    let params: Vec<&(String, Type)> = params.iter().filter(|(name, _)| name != "...").collect();
+                    let param_types: Vec<Type> = params.iter().map(|(_, type_)| type_.clone()).collect();
+                    let locations = self.arg_locations(&param_types);
+                    let mut words = locations.iter();
+                    let mut copies = Vec::new();
+                    for &(param_name, param_type) in &params {
+                        let param_words: Vec<ArgLocation> = words.by_ref().take(self.arg_words(param_type)).copied().collect();
+
+                        // All parameters take 8 bytes in the frame for each word they
+                        // are passed in, whatever their type. A struct parameter arrives
+                        // in the low bytes of its registers, so storing the registers
+                        // lays it out in memory as it should be
+                        let saved = self.allocate_slot(8 * param_words.len(), 8);
+                        let mut offset = saved;
+                        if param_type.is_record() {
+                            let size = self.size_of(param_type);
+                            if self.target.structs_by_reference() && size > 8 {
+                                // A larger struct arrives as the address of the caller's
+                                // copy, which is copied into the frame once all the
+                                // registers are saved
+                                offset = self.allocate_slot(size, 8);
+                                copies.push((offset, saved, size));
+                            } else if size > 16 {
+                                return Err(codegen_error(
+                                    node.location(),
+                                    "Passing structs larger than 16 bytes by value is not supported yet",
+                                ));
+                            }
+                        }
                         self.variables.insert(
                             param_name.clone(),
                             Variable {
-                                offset: self.stack_offset,
-                                type_: param_type.clone(),
+                                offset,
+                                type_: param_type.strip_qualifiers(),
                             },
                         );
 
-                        if i < param_registers.len() {
-                            // Parameter is passed in a register
-                            writeln!(self.output, "    push {}", param_registers[i]).unwrap();
-                        } else {
-                            // Parameter is passed on the stack
-                            // TODO: Implement stack parameters
-                            return Err(codegen_error("Stack parameters not implemented yet"));
+                        for (i, location) in param_words.into_iter().enumerate() {
+                            let slot = saved - 8 * i;
+                            match location {
+                                ArgLocation::Xmm(xmm) => {
+                                    // Floating parameters arrive in an XMM register
+                                    writeln!(self.output, "    movsd qword ptr [rbp-{}], xmm{}", slot, xmm).unwrap();
+                                }
+                                ArgLocation::Register(register) => {
+                                    // Parameter is passed in a register
+                                    self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", slot), register]);
+                                }
+                                ArgLocation::Stack => {
+                                    // Parameter is passed on the stack
+                                    // TODO: Implement stack parameters
+                                    return Err(codegen_error(node.location(), "Stack parameters not implemented yet"));
+                                }
+                            }
                         }
                     }
 
+                    self.var_args = None;
+                    if is_variadic {
+                        self.spill_var_args(&locations);
+                    }
+
+                    for (offset, pointer, size) in copies {
+                        self.output.emit("mov", &["rax", &format!("qword ptr [rbp-{}]", pointer)]);
+                        self.emit_copy(&format!("rbp-{}", offset), "rax", size);
+                    }
+
                     // Generate code for the function body
                     self.generate_node(body)?;
 
+                    // main returns 0 when control falls off the end of its body
+                    let ends_in_return = matches!(
+                        body.as_ref(),
+                        Node::BlockStmt(statements, _) if matches!(statements.last(), Some(Node::ReturnStmt(_, _)))
+                    );
+                    if name == "main" && !ends_in_return {
+                        writeln!(self.output, "    xor eax, eax").unwrap();
+                    }
+
+                    // Callee-saved scratch registers are kept in slots below all the others,
+                    // inside the frame, so the arguments above RBP stay where the calling
+                    // convention puts them
+                    self.stack_offset = self.frame_size;
+                    let mut saved_slots = Vec::new();
+                    for register in self.saved_registers.clone() {
+                        let offset = self.allocate_slot(8, 8);
+                        saved_slots.push((register, format!("qword ptr [rbp-{}]", offset)));
+                    }
+
                     // Function epilogue
                     writeln!(self.output, ".{}ret:", name).unwrap();
-                    writeln!(self.output, "    mov rsp, rbp").unwrap();
-                    writeln!(self.output, "    pop rbp").unwrap();
+                    for (register, slot) in &saved_slots {
+                        self.output.emit("mov", &[register, slot]);
+                    }
+                    self.output.emit("mov", &["rsp", "rbp"]);
+                    self.output.emit("pop", &["rbp"]);
+
+                    // Allocate the frame, keeping RSP 16-byte aligned for calls. On entry RSP
+                    // is 8 past a multiple of 16, so after the push of RBP it is aligned
+                    let frame_size = self.align_to(self.frame_size, 16);
+                    let mut setup = Vec::new();
+                    if frame_size > 0 {
+                        setup.push(Instruction::op("sub", &["rsp", &frame_size.to_string()]));
+                    }
+                    for (register, slot) in &saved_slots {
+                        setup.push(Instruction::op("mov", &[slot, register]));
+                    }
+                    self.output.instructions.splice(frame_setup..frame_setup, setup);
                     writeln!(self.output, "    ret").unwrap();
 
                     self.current_function = None;
@@ -232,10 +1497,42 @@ impl CodeGenerator {
 
                 Ok(())
             }
-            _ => Err(codegen_error("Expected declaration")),
+            _ => Err(codegen_error(node.location(), "Expected declaration")),
         }
     }
 
+    /// Save the argument registers a variadic function's named parameters leave
+    /// unused, so va_arg can read the variable arguments from memory
+    fn spill_var_args(&mut self, named: &[ArgLocation]) {
+        let registers = self.target.arg_registers();
+
+        let (first, registers_end) = if self.target.positional_args() {
+            // Every argument has a slot in the shadow space or above it, so once the
+            // registers are spilled into the shadow space all arguments are contiguous
+            let used = named.len();
+            for (i, register) in registers.iter().enumerate().skip(used) {
+                self.output.emit("mov", &[&format!("qword ptr [rbp+{}]", 16 + 8 * i), register]);
+            }
+            (format!("rbp+{}", 16 + 8 * used), None)
+        } else {
+            // The registers are saved in the frame, apart from the stack arguments
+            let used = named.iter().filter(|location| matches!(location, ArgLocation::Register(_))).count();
+            let save_area = self.allocate_slot(8 * registers.len(), 8);
+            for (i, register) in registers.iter().enumerate().skip(used) {
+                self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", save_area - 8 * i), register]);
+            }
+            let end = format!("rbp-{}", save_area - 8 * registers.len());
+            (format!("rbp-{}", save_area - 8 * used), Some(end))
+        };
+
+        let control = self.allocate_slot(24, 8);
+        self.var_args = Some(VarArgFrame {
+            control,
+            first,
+            registers_end,
+        });
+    }
+
     /// Generate x86-64 assembly code for an AST node
     ///
     /// This is the core code generation function that recursively traverses the AST
@@ -257,17 +1554,44 @@ impl CodeGenerator {
     /// # Returns
     /// * `Result<()>` - Success or an error if code generation fails
     fn generate_node(&mut self, node: &Node) -> Result<()> {
+        // Each statement starts a new source line for debuggers
+        let is_statement = matches!(
+            node,
+            Node::ExpressionStmt(_) | Node::ReturnStmt(_, _) | Node::IfStmt { .. } |
+            Node::WhileStmt { .. } | Node::ForStmt { .. } | Node::GotoStmt(_, _) |
+            Node::BreakStmt(_) | Node::ContinueStmt(_) | Node::VarDecl { initializer: Some(_), .. }
+        );
+        if self.debug_info && is_statement {
+            self.emit_location(node);
+        }
+
+        // A subexpression computed once for the statement is loaded from its temporary
+        if let Some((_, offset)) = self.common_values.iter().find(|(value, _)| cse::same_expression(value, node)) {
+            self.output.emit("mov", &["rax", &format!("qword ptr [rbp-{}]", offset)]);
+            return Ok(());
+        }
+
         match node {
-            Node::IntLiteral(value, _) => {
+            Node::IntLiteral(value, _) | Node::LongLiteral(value, _) => {
                 // Load the integer literal value directly into RAX register
                 // This makes the value available for subsequent operations
-                writeln!(self.output, "    mov rax, {}", value).unwrap();
+                self.emit_constant("rax", *value);
+                Ok(())
+            }
+            Node::UIntLiteral(value, _) | Node::ULongLiteral(value, _) => {
+                // Unsigned literals are loaded the same way, as the same bits
+                self.emit_constant("rax", *value as i64);
+                Ok(())
+            }
+            Node::FloatLiteral(value, _) => {
+                // Floating values are held in RAX as the bits of a double
+                self.emit_constant("rax", value.to_bits() as i64);
                 Ok(())
             }
             Node::CharLiteral(value, _) => {
-                // Convert character to its ASCII/UTF-8 value and load into RAX
-                // Characters are treated as 8-bit values but stored in 64-bit register
-                writeln!(self.output, "    mov rax, {}", *value as u8).unwrap();
+                // Convert character to its byte value and load into RAX
+                // Characters are signed 8-bit values, sign-extended to the 64-bit register
+                self.output.emit("mov", &["rax", &(*value as u8 as i8).to_string()]);
                 Ok(())
             }
             Node::StringLiteral(value, _) => {
@@ -281,35 +1605,48 @@ impl CodeGenerator {
                 writeln!(self.output, "    lea rax, [rip + .LC{}]", index).unwrap();
                 Ok(())
             }
+            Node::Identifier(name, _) if !self.is_variable(name) && self.functions.contains_key(name) => {
+                self.emit_function_address(name);
+                Ok(())
+            }
             Node::Identifier(name, _location) => {
                 if let Some(var) = self.variables.get(name) {
-                    match var.type_ {
-                        Type::Char | Type::Int | Type::Long => {
-                            // For scalar types, load the value from the stack into RAX
+                    let offset = var.offset;
+                    let type_ = var.type_.clone();
+                    match type_ {
+                        Type::Bool | Type::Char | Type::Int | Type::Long |
+                        Type::UChar | Type::UInt | Type::ULong |
+                        Type::Float | Type::Double | Type::Pointer(_) => {
+                            // For scalar types and pointers, load the value from the stack into RAX
                             // rbp is the base pointer, and var.offset is the variable's position on the stack
-                            writeln!(self.output, "    mov rax, [rbp-{}]", var.offset).unwrap();
-                        }
-                        Type::Pointer(_) => {
-                            // For pointers, load the value of the pointer (which is an address)
-                            writeln!(self.output, "    mov rax, [rbp-{}]", var.offset).unwrap();
+                            // Only the variable's own bytes are read, then extended to 64 bits
+                            self.emit_load(&type_, &format!("rbp-{}", offset));
                         }
-                        Type::Array(_, _) => {
-                            // For arrays, load the address of the array
+                        Type::Array(_, _) | Type::Struct(_, _) | Type::Union(_, _) => {
+                            // For arrays, structs and unions, load the address of the object
                             // lea (Load Effective Address) calculates the address without dereferencing
-                            writeln!(self.output, "    lea rax, [rbp-{}]", var.offset).unwrap();
+                            writeln!(self.output, "    lea rax, [rbp-{}]", offset).unwrap();
                         }
                         _ => {
-                            return Err(codegen_error(format!(
+                            return Err(codegen_error(node.location(), format!(
                                 "Unsupported variable type: {:?}",
-                                var.type_
+                                type_
                             )));
                         }
                     }
                     Ok(())
                 } else {
                     // For global variables, load the value from the global memory location
-                    // The name directly references a label in the data section
-                    writeln!(self.output, "    mov rax, [{}]", name).unwrap();
+                    // The label references the data section relative to RIP, so the
+                    // code stays position-independent
+                    let label = self.global_label(name);
+                    let type_ = self.global_type(&label)?;
+                    let address = format!("rip + {}", self.symbol(&label));
+                    if let Type::Array(_, _) | Type::Struct(_, _) | Type::Union(_, _) = type_ {
+                        writeln!(self.output, "    lea rax, [{}]", address).unwrap();
+                    } else {
+                        self.emit_load(&type_, &address);
+                    }
                     Ok(())
                 }
             }
@@ -322,197 +1659,299 @@ impl CodeGenerator {
                 match op {
                     BinaryOp::Assign => {
                         // Assignment operator requires special handling
-                        match &**left {
-                            Node::Identifier(name, _) => {
-                                // First, evaluate the right-hand side expression
-                                // This will put the result in RAX
-                                self.generate_node(right)?;
-
-                                // Then store the value from RAX into the variable's memory location
-                                if let Some(var) = self.variables.get(name) {
-                                    // For local variables, store at [rbp-offset]
-                                    // This writes the 64-bit RAX value to the stack location of the variable
-                                    writeln!(self.output, "    mov [rbp-{}], rax", var.offset).unwrap();
-                                } else {
-                                    // For global variables, store at the global label
-                                    writeln!(self.output, "    mov [{}], rax", name).unwrap();
-                                }
-                            },
-                            Node::UnaryExpr { op: UnaryOp::Dereference, expr, .. } => {
-                                // For pointer dereference (*p = value), we need to:
-                                // 1. Evaluate the right-hand side and save it
-                                self.generate_node(right)?;
-                                writeln!(self.output, "    push rax").unwrap();  // Save the value to assign
-
-                                // 2. Evaluate the pointer expression to get the address
-                                self.generate_node(expr)?;
-                                // Now RAX contains the address to store to
-
-                                // 3. Pop the value and store it at the address
-                                writeln!(self.output, "    pop rcx").unwrap();  // Get the value to assign
-                                writeln!(self.output, "    mov [rax], rcx").unwrap();  // Store the value at the address
-                            },
-                            _ => {
-                                return Err(codegen_error("Left operand of assignment must be an identifier or dereferenced pointer"));
+                        if self.expr_type(left).is_record() {
+                            // Struct assignment copies the whole struct into the
+                            // address of the left side
+                            let size = self.size_of(&self.expr_type(left));
+                            self.generate_address(left)?;
+                            return self.generate_struct_store(right, size);
+                        }
+
+                        // The assigned value is converted to the type of the target
+                        let target_type = self.expr_type(left);
+
+                        // First, evaluate the right-hand side expression
+                        // This will put the result in RAX
+                        self.generate_converted(right, &target_type)?;
+
+                        if let Node::Identifier(name, _) = &**left {
+                            // A variable is stored to directly, without computing its address
+                            // Only as many bytes as the variable's type occupies are written
+                            if let Some(var) = self.variables.get(name) {
+                                // For local variables, store at [rbp-offset]
+                                let address = format!("rbp-{}", var.offset);
+                                let type_ = var.type_.clone();
+                                self.emit_store(&type_, &address, "rax");
+                            } else {
+                                // For global variables, store at the global label
+                                let label = self.global_label(name);
+                                let type_ = self.global_type(&label)?;
+                                self.emit_store(&type_, &format!("rip + {}", self.symbol(&label)), "rax");
                             }
+                        } else {
+                            // Any other lvalue (*p, arr[i], s.m) is stored through its address
+                            self.save_operand();  // Save the value to assign
+                            self.generate_address(left)?;
+
+                            // Store the saved value at the address, writing only as many
+                            // bytes as the target type occupies
+                            let value = self.restore_operand();
+                            self.emit_store(&target_type, "rax", value);
+                            self.output.emit("mov", &["rax", value]);  // The assigned value is the result
                         }
                     }
+                    BinaryOp::LogicalAnd | BinaryOp::LogicalOr => {
+                        // The right operand is only evaluated when the left one does not
+                        // decide the result: a false left side of && or a true left side
+                        // of || jumps straight past it
+                        let (prefix, jump, result) = match op {
+                            BinaryOp::LogicalAnd => ("land", "je", 0),
+                            _ => ("lor", "jne", 1),
+                        };
+                        let short_label = self.generate_label(prefix);
+                        let end_label = self.generate_label(prefix);
+
+                        self.generate_condition(left)?;
+                        writeln!(self.output, "    cmp rax, 0").unwrap();
+                        writeln!(self.output, "    {} {}", jump, short_label).unwrap();
+
+                        // The left operand did not decide, so the right one is the result
+                        self.generate_condition(right)?;
+                        writeln!(self.output, "    cmp rax, 0").unwrap();
+                        writeln!(self.output, "    setne al").unwrap();
+                        writeln!(self.output, "    movzx rax, al").unwrap();
+                        self.output.emit("jmp", &[&end_label]);
+
+                        writeln!(self.output, "{}:", short_label).unwrap();
+                        self.output.emit("mov", &["rax", &result.to_string()]);
+                        writeln!(self.output, "{}:", end_label).unwrap();
+                    }
                     _ => {
                         // For all other binary operations, we need both operands' values
+                        // The operand types decide between signed and unsigned instructions
+                        let result_type = self.expr_type(node);
+                        let left_type = self.expr_type(left);
+                        let right_type = self.expr_type(right);
+
+                        // Arithmetic and comparisons with a floating operand use SSE
+                        let floating_op = matches!(
+                            op,
+                            BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide |
+                            BinaryOp::Equal | BinaryOp::NotEqual | BinaryOp::Less | BinaryOp::LessEqual |
+                            BinaryOp::Greater | BinaryOp::GreaterEqual
+                        );
+                        if floating_op && (left_type.is_floating() || right_type.is_floating()) {
+                            return self.generate_floating_binary(op, left, right);
+                        }
 
-                        // First, evaluate the left operand and save its value on the stack
-                        // This frees up RAX for evaluating the right operand
-                        self.generate_node(left)?;
-                        writeln!(self.output, "    push rax").unwrap();  // Save left operand value
-
-                        // Then, evaluate the right operand (result will be in RAX)
-                        self.generate_node(right)?;
+                        let unsigned = match op {
+                            BinaryOp::ShiftRight => self.is_unsigned(&result_type),
+                            _ => {
+                                self.is_unsigned(&Type::arithmetic_type(&left_type, &right_type))
+                                    || self.is_pointer(&left_type)
+                                    || self.is_pointer(&right_type)
+                            }
+                        };
 
-                        // Pop the left operand value into RCX
-                        // Now: left value in RCX, right value in RAX
-                        writeln!(self.output, "    pop rcx").unwrap();
+                        // The operands of these operations can be evaluated in either order
+                        let commutative = match op {
+                            BinaryOp::Add => {
+                                !self.is_pointer(&left_type) && !self.is_pointer(&right_type)
+                            }
+                            BinaryOp::Multiply | BinaryOp::Equal | BinaryOp::NotEqual |
+                            BinaryOp::BitwiseAnd | BinaryOp::BitwiseOr | BinaryOp::BitwiseXor => true,
+                            _ => false,
+                        };
+
+                        // Evaluate the operands: the left value ends up in RAX and the
+                        // right one in `r`, a register or a small immediate
+                        let mut r = self.generate_operands(op, left, right, commutative)?;
+
+                        // With -ftrap-overflow, signed integer arithmetic is followed by a
+                        // check of the overflow flag, so an int is computed in the 32-bit
+                        // registers for the flag to tell whether the int overflowed
+                        let trap = self.trap_overflow
+                            && matches!(op, BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply)
+                            && matches!(result_type, Type::Int | Type::Long)
+                            && !self.is_pointer(&left_type)
+                            && !self.is_pointer(&right_type);
+                        let narrow = trap && result_type == Type::Int;
+                        if narrow && !is_immediate(&r) {
+                            r = sub_register(&r, 4);
+                        }
+                        let accumulator = if narrow { "eax" } else { "rax" };
 
                         // Generate the specific operation based on the operator type
                         match op {
                             BinaryOp::Add => {
-                                // Addition: RAX = RCX + RAX
-                                // Adds the value in RCX (left operand) to RAX (right operand)
-                                writeln!(self.output, "    add rax, rcx").unwrap();
+                                // Pointer arithmetic: the integer operand counts elements,
+                                // so scale it by the size of the pointed-to type
+                                if let Some(elem_size) = self.pointee_size(&left_type) {
+                                    if !self.is_pointer(&right_type) && elem_size > 1 {
+                                        r = self.scale_operand(r, elem_size);
+                                    }
+                                } else if let Some(elem_size) = self.pointee_size(&right_type) {
+                                    if elem_size > 1 {
+                                        writeln!(self.output, "    imul rax, rax, {}", elem_size).unwrap();
+                                    }
+                                }
+
+                                // Addition: RAX = RAX + right
+                                writeln!(self.output, "    add {}, {}", accumulator, r).unwrap();
                             }
                             BinaryOp::Subtract => {
-                                // Subtraction: RAX = RCX - RAX
-                                // Note the order: left operand (RCX) - right operand (RAX)
-                                writeln!(self.output, "    sub rcx, rax").unwrap();
-                                writeln!(self.output, "    mov rax, rcx").unwrap();  // Move result to RAX
+                                // Pointer minus integer moves back by whole elements
+                                let elem_size = self.pointee_size(&left_type);
+                                let is_pointer_difference = self.is_pointer(&right_type);
+                                if let Some(elem_size) = elem_size {
+                                    if !is_pointer_difference && elem_size > 1 {
+                                        r = self.scale_operand(r, elem_size);
+                                    }
+                                }
+
+                                // Subtraction: RAX = RAX - right
+                                writeln!(self.output, "    sub {}, {}", accumulator, r).unwrap();
+
+                                // The difference of two pointers is a count of elements,
+                                // so divide the byte distance by the element size
+                                if let Some(elem_size) = elem_size {
+                                    if is_pointer_difference && elem_size > 1 {
+                                        self.output.emit("mov", &["rcx", &elem_size.to_string()]);
+                                        writeln!(self.output, "    cqo").unwrap();
+                                        writeln!(self.output, "    idiv rcx").unwrap();
+                                    }
+                                }
                             }
                             BinaryOp::Multiply => {
-                                // Signed multiplication: RAX = RAX * RCX
+                                // Signed multiplication: RAX = RAX * right
                                 // imul performs signed integer multiplication
-                                writeln!(self.output, "    imul rax, rcx").unwrap();
+                                if is_immediate(&r) {
+                                    writeln!(self.output, "    imul {0}, {0}, {1}", accumulator, r).unwrap();
+                                } else {
+                                    writeln!(self.output, "    imul {}, {}", accumulator, r).unwrap();
+                                }
                             }
-                            BinaryOp::Divide => {
-                                // Division: RAX = RCX / RAX
-                                // x86 div instruction requires special setup:
-                                writeln!(self.output, "    mov rdx, 0").unwrap();    // Clear RDX (will hold remainder)
-                                writeln!(self.output, "    mov rax, rcx").unwrap();  // Move dividend to RAX
-                                writeln!(self.output, "    div rax").unwrap();       // Divide RAX by original RAX value
+                            BinaryOp::Divide | BinaryOp::Modulo => {
+                                // Division: RAX = RAX / right, modulo: RAX = RAX % right
+                                // x86 division divides RDX:RAX by the operand, or EDX:EAX
+                                // when the operands are promoted to a 32-bit type
+                                let size = self.size_of(&Type::arithmetic_type(&left_type, &right_type));
+                                let (dividend, remainder, divisor) = if size == 4 {
+                                    ("eax", "edx", sub_register(&r, 4))
+                                } else {
+                                    ("rax", "rdx", r.clone())
+                                };
+
+                                // The most negative value divided by -1 overflows, which
+                                // faults in idiv, so a divisor of -1 negates instead,
+                                // wrapping around, and leaves no remainder
+                                let guard = !unsigned
+                                    && !matches!(self.immediate_value(right), Some(value) if value != -1);
+                                let end_label = if guard {
+                                    let divide_label = self.generate_label("div");
+                                    let end_label = self.generate_label("div");
+                                    writeln!(self.output, "    cmp {}, -1", divisor).unwrap();
+                                    writeln!(self.output, "    jne {}", divide_label).unwrap();
+                                    match op {
+                                        BinaryOp::Divide => writeln!(self.output, "    neg {}", dividend).unwrap(),
+                                        _ => writeln!(self.output, "    xor eax, eax").unwrap(),
+                                    }
+                                    self.output.emit("jmp", &[&end_label]);
+                                    writeln!(self.output, "{}:", divide_label).unwrap();
+                                    Some(end_label)
+                                } else {
+                                    None
+                                };
+
+                                if unsigned {
+                                    writeln!(self.output, "    xor edx, edx").unwrap();       // Zero-extend the dividend into RDX
+                                    writeln!(self.output, "    div {}", divisor).unwrap();    // Unsigned divide
+                                } else {
+                                    // Sign-extend the dividend into EDX or RDX
+                                    writeln!(self.output, "    {}", if size == 4 { "cdq" } else { "cqo" }).unwrap();
+                                    writeln!(self.output, "    idiv {}", divisor).unwrap();   // Signed divide
+                                }
                                 // Result is stored in RAX (quotient) and RDX (remainder)
+                                if let BinaryOp::Modulo = op {
+                                    self.output.emit("mov", &[dividend, remainder]);
+                                }
+
+                                if let Some(end_label) = end_label {
+                                    writeln!(self.output, "{}:", end_label).unwrap();
+                                }
                             }
-                            BinaryOp::Modulo => {
-                                // Modulo: RAX = RCX % RAX
-                                // Uses the same div instruction as division but returns the remainder
-                                writeln!(self.output, "    mov rdx, 0").unwrap();    // Clear RDX
-                                writeln!(self.output, "    mov rax, rcx").unwrap();  // Move dividend to RAX
-                                writeln!(self.output, "    div rax").unwrap();       // Divide RAX by original RAX value
-                                writeln!(self.output, "    mov rax, rdx").unwrap();  // Move remainder from RDX to RAX
-                            }
-                            BinaryOp::Equal => {
-                                // Equality comparison: RAX = (RCX == RAX) ? 1 : 0
-                                writeln!(self.output, "    cmp rcx, rax").unwrap();   // Compare left and right operands
-                                writeln!(self.output, "    sete al").unwrap();       // Set AL to 1 if equal, 0 if not
+                            BinaryOp::Equal | BinaryOp::NotEqual |
+                            BinaryOp::Less | BinaryOp::LessEqual |
+                            BinaryOp::Greater | BinaryOp::GreaterEqual => {
+                                // Comparison: RAX = (RAX op right) ? 1 : 0
+                                // Unsigned operands use the below/above condition codes
+                                let set = match (op, unsigned) {
+                                    (BinaryOp::Equal, _) => "sete",
+                                    (BinaryOp::NotEqual, _) => "setne",
+                                    (BinaryOp::Less, true) => "setb",
+                                    (BinaryOp::Less, false) => "setl",
+                                    (BinaryOp::LessEqual, true) => "setbe",
+                                    (BinaryOp::LessEqual, false) => "setle",
+                                    (BinaryOp::Greater, true) => "seta",
+                                    (BinaryOp::Greater, false) => "setg",
+                                    (_, true) => "setae",
+                                    (_, false) => "setge",
+                                };
+                                // Operands are compared at the width of their common type:
+                                // an int converted to unsigned int is only its low 32 bits
+                                let common_type = Type::arithmetic_type(&left_type, &right_type);
+                                let narrow = !self.is_pointer(&left_type)
+                                    && !self.is_pointer(&right_type)
+                                    && self.size_of(&common_type) == 4;
+                                if narrow && !is_immediate(&r) {
+                                    r = sub_register(&r, 4);
+                                }
+                                let left_register = if narrow { "eax" } else { "rax" };
+                                writeln!(self.output, "    cmp {}, {}", left_register, r).unwrap();   // Compare left and right operands
+                                writeln!(self.output, "    {} al", set).unwrap();       // Set AL to 1 if the comparison holds
                                 writeln!(self.output, "    movzx rax, al").unwrap(); // Zero-extend AL to RAX (clears upper bits)
                             }
-                            BinaryOp::NotEqual => {
-                                // Inequality comparison: RAX = (RCX != RAX) ? 1 : 0
-                                writeln!(self.output, "    cmp rcx, rax").unwrap();   // Compare left and right operands
-                                writeln!(self.output, "    setne al").unwrap();      // Set AL to 1 if not equal, 0 if equal
-                                writeln!(self.output, "    movzx rax, al").unwrap(); // Zero-extend AL to RAX
-                            }
-                            BinaryOp::Less => {
-                                // Less than comparison: RAX = (RCX < RAX) ? 1 : 0
-                                writeln!(self.output, "    cmp rcx, rax").unwrap();   // Compare left and right operands
-                                writeln!(self.output, "    setl al").unwrap();       // Set AL to 1 if less, 0 if not
-                                writeln!(self.output, "    movzx rax, al").unwrap(); // Zero-extend AL to RAX
-                            }
-                            BinaryOp::LessEqual => {
-                                // Less than or equal comparison: RAX = (RCX <= RAX) ? 1 : 0
-                                writeln!(self.output, "    cmp rcx, rax").unwrap();   // Compare left and right operands
-                                writeln!(self.output, "    setle al").unwrap();      // Set AL to 1 if less or equal, 0 if not
-                                writeln!(self.output, "    movzx rax, al").unwrap(); // Zero-extend AL to RAX
-                            }
-                            BinaryOp::Greater => {
-                                // Greater than comparison: RAX = (RCX > RAX) ? 1 : 0
-                                writeln!(self.output, "    cmp rcx, rax").unwrap();   // Compare left and right operands
-                                writeln!(self.output, "    setg al").unwrap();       // Set AL to 1 if greater, 0 if not
-                                writeln!(self.output, "    movzx rax, al").unwrap(); // Zero-extend AL to RAX
-                            }
-                            BinaryOp::GreaterEqual => {
-                                // Greater than or equal comparison: RAX = (RCX >= RAX) ? 1 : 0
-                                writeln!(self.output, "    cmp rcx, rax").unwrap();   // Compare left and right operands
-                                writeln!(self.output, "    setge al").unwrap();      // Set AL to 1 if greater or equal, 0 if not
-                                writeln!(self.output, "    movzx rax, al").unwrap(); // Zero-extend AL to RAX
-                            }
-                            BinaryOp::LogicalAnd => {
-                                // Logical AND with short-circuit evaluation
-                                // If left operand is false (0), result is false without evaluating right operand
-                                let end_label = self.generate_label("land");
-
-                                // Check if left operand (RCX) is false (0)
-                                writeln!(self.output, "    cmp rcx, 0").unwrap();
-                                // If left is false, jump to end and result will be 0
-                                writeln!(self.output, "    je {}", end_label).unwrap();
-
-                                // Left is true, so result depends on right operand (RAX)
-                                writeln!(self.output, "    cmp rax, 0").unwrap();
-                                // Set AL to 1 if right is non-zero (true)
-                                writeln!(self.output, "    setne al").unwrap();
-                                // Zero-extend AL to RAX for the final result
-                                writeln!(self.output, "    movzx rax, al").unwrap();
-
-                                // Target for the short-circuit jump
-                                writeln!(self.output, "{}:", end_label).unwrap();
-                            }
-                            BinaryOp::LogicalOr => {
-                                // Logical OR with short-circuit evaluation
-                                // If left operand is true (non-0), result is true without evaluating right operand
-                                let end_label = self.generate_label("lor");
-
-                                // Check if left operand (RCX) is true (non-0)
-                                writeln!(self.output, "    cmp rcx, 0").unwrap();
-                                // If left is true, jump to end and result will be 1
-                                writeln!(self.output, "    jne {}", end_label).unwrap();
-
-                                // Left is false, so result depends on right operand (RAX)
-                                writeln!(self.output, "    cmp rax, 0").unwrap();
-                                // Set AL to 1 if right is non-zero (true)
-                                writeln!(self.output, "    setne al").unwrap();
-                                // Zero-extend AL to RAX for the final result
-                                writeln!(self.output, "    movzx rax, al").unwrap();
-
-                                // Target for the short-circuit jump
-                                writeln!(self.output, "{}:", end_label).unwrap();
-                            }
                             BinaryOp::BitwiseAnd => {
-                                // Bitwise AND: RAX = RAX & RCX
-                                // Performs bitwise AND between left and right operands
-                                writeln!(self.output, "    and rax, rcx").unwrap();
+                                // Bitwise AND: RAX = RAX & right
+                                writeln!(self.output, "    and rax, {}", r).unwrap();
                             }
                             BinaryOp::BitwiseOr => {
-                                // Bitwise OR: RAX = RAX | RCX
-                                // Performs bitwise OR between left and right operands
-                                writeln!(self.output, "    or rax, rcx").unwrap();
+                                // Bitwise OR: RAX = RAX | right
+                                writeln!(self.output, "    or rax, {}", r).unwrap();
                             }
                             BinaryOp::BitwiseXor => {
-                                // Bitwise XOR: RAX = RAX ^ RCX
+                                // Bitwise XOR: RAX = RAX ^ right
                                 // Performs bitwise exclusive OR between left and right operands
-                                writeln!(self.output, "    xor rax, rcx").unwrap();
+                                writeln!(self.output, "    xor rax, {}", r).unwrap();
                             }
-                            BinaryOp::ShiftLeft => {
-                                // Shift left: RAX = RCX << (RAX & 0x3F)
-                                // x86 shift instructions use CL (lowest byte of RCX) for shift count
-                                writeln!(self.output, "    mov rcx, rax").unwrap();  // Move shift count to RCX
-                                writeln!(self.output, "    mov rax, rcx").unwrap();  // Move value to shift into RAX
-                                writeln!(self.output, "    shl rax, cl").unwrap();   // Shift RAX left by CL bits
-                            }
-                            BinaryOp::ShiftRight => {
-                                // Shift right: RAX = RCX >> (RAX & 0x3F)
-                                // Logical shift right (zeros are shifted in from the left)
-                                writeln!(self.output, "    mov rcx, rax").unwrap();  // Move shift count to RCX
-                                writeln!(self.output, "    mov rax, rcx").unwrap();  // Move value to shift into RAX
-                                writeln!(self.output, "    shr rax, cl").unwrap();   // Shift RAX right by CL bits
+                            BinaryOp::ShiftLeft | BinaryOp::ShiftRight => {
+                                // Shift: RAX = RAX << right or RAX >> right
+                                // Unsigned values shift in zeros (shr), signed values copy the sign bit (sar)
+                                let shift = match op {
+                                    BinaryOp::ShiftLeft => "shl",
+                                    _ if unsigned => "shr",
+                                    _ => "sar",
+                                };
+                                if is_immediate(&r) {
+                                    writeln!(self.output, "    {} rax, {}", shift, r).unwrap();
+                                } else {
+                                    // x86 shift instructions take a variable count in CL
+                                    if r != "rcx" {
+                                        self.output.emit("mov", &["rcx", &r]);
+                                    }
+                                    writeln!(self.output, "    {} rax, cl", shift).unwrap();
+                                }
                             }
-                            BinaryOp::Assign => unreachable!(),
+                            BinaryOp::Assign | BinaryOp::LogicalAnd | BinaryOp::LogicalOr => unreachable!(),
+                        }
+
+                        if trap {
+                            writeln!(self.output, "    jo {}", OVERFLOW_TRAP).unwrap();
+                            self.overflow_trap_used = true;
                         }
+
+                        // Keep 32-bit results correctly extended in the full register
+                        self.extend_result(&result_type);
                     }
                 }
 
@@ -526,22 +1965,8 @@ impl CodeGenerator {
                 // Special case for address-of operator
                 if let UnaryOp::AddressOf = op {
                     // Address-of operator: RAX = &expr
-                    // Special case: we need the address, not the value
-                    if let Node::Identifier(name, _) = &**expr {
-                        if let Some(var) = self.variables.get(name) {
-                            // For local variables, calculate address relative to RBP
-                            // lea (Load Effective Address) calculates the address without dereferencing
-                            // We don't need to load the value first, just the address
-                            writeln!(self.output, "    lea rax, [rbp-{}]", var.offset).unwrap();
-
-                        } else {
-                            // For global variables, get the address of the global label
-                            writeln!(self.output, "    lea rax, [{}]", name).unwrap();
-                        }
-                    } else {
-                        return Err(codegen_error("Cannot take address of non-lvalue"));
-                    }
-                    return Ok(());
+                    // We need the address of the operand, not its value
+                    return self.generate_address(expr);
                 }
 
                 // For other unary operators, first evaluate the expression to get its value in RAX
@@ -549,12 +1974,23 @@ impl CodeGenerator {
 
                 match op {
                     UnaryOp::Negate => {
-                        // Arithmetic negation: RAX = -RAX
-                        // Negates the value in RAX (two's complement)
-                        writeln!(self.output, "    neg rax").unwrap();
+                        let result_type = self.expr_type(node);
+                        if result_type.is_floating() {
+                            // A floating value is negated by flipping its sign bit
+                            writeln!(self.output, "    btc rax, 63").unwrap();
+                        } else {
+                            // Arithmetic negation: RAX = -RAX
+                            // Negates the value in RAX (two's complement)
+                            writeln!(self.output, "    neg rax").unwrap();
+                            self.extend_result(&result_type);
+                        }
                     }
                     UnaryOp::LogicalNot => {
                         // Logical NOT: RAX = !RAX (0 becomes 1, non-0 becomes 0)
+                        // Both floating zeros count as 0, whatever their sign
+                        if self.expr_type(expr).is_floating() {
+                            writeln!(self.output, "    shl rax, 1").unwrap();
+                        }
                         writeln!(self.output, "    cmp rax, 0").unwrap();      // Compare RAX with 0
                         writeln!(self.output, "    sete al").unwrap();        // Set AL to 1 if RAX is 0, otherwise 0
                         writeln!(self.output, "    movzx rax, al").unwrap();  // Zero-extend AL to RAX
@@ -562,12 +1998,21 @@ impl CodeGenerator {
                     UnaryOp::BitwiseNot => {
                         // Bitwise NOT: RAX = ~RAX (flips all bits)
                         writeln!(self.output, "    not rax").unwrap();
+                        let result_type = self.expr_type(node);
+                        self.extend_result(&result_type);
                     }
                     UnaryOp::Dereference => {
                         // Dereference: RAX = *RAX (load value from address in RAX)
-                        // Treats RAX as a pointer and loads the value it points to
-                        // For pointers to integers, we need to load the value at the address
-                        writeln!(self.output, "    mov rax, [rax]").unwrap();
+                        // Treats RAX as a pointer and loads the value it points to,
+                        // reading exactly the width of the pointed-to type
+                        let pointee_type = self.expr_type(node);
+                        match pointee_type {
+                            // The function a pointer points to stands for the pointer itself
+                            _ if self.expr_type(expr).is_function_pointer() => {}
+                            // An array or struct designates memory; its address is its value
+                            Type::Array(_, _) | Type::Struct(_, _) | Type::Union(_, _) | Type::Function(_, _, _) => {}
+                            _ => self.emit_load(&pointee_type, "rax"),
+                        }
                     }
                     UnaryOp::AddressOf => {
                         // This case is handled separately above
@@ -577,97 +2022,229 @@ impl CodeGenerator {
 
                 Ok(())
             }
-            Node::FunctionCall {
-                name,
-                args,
-                location: _,
-            } => {
-                // Function call using Windows x64 calling convention
-
-                // Save all volatile registers that might be modified by the callee
-                // This preserves their values across the function call
-                writeln!(self.output, "    push rbx").unwrap();  // Non-volatile register
-                writeln!(self.output, "    push rsi").unwrap();  // Non-volatile register
-                writeln!(self.output, "    push rdi").unwrap();  // Non-volatile register
-                writeln!(self.output, "    push rcx").unwrap();  // Volatile register (1st param)
-                writeln!(self.output, "    push rdx").unwrap();  // Volatile register (2nd param)
-                writeln!(self.output, "    push r8").unwrap();   // Volatile register (3rd param)
-                writeln!(self.output, "    push r9").unwrap();   // Volatile register (4th param)
-                writeln!(self.output, "    push r10").unwrap();  // Volatile register
-                writeln!(self.output, "    push r11").unwrap();  // Volatile register
-
-                // Prepare arguments according to Windows x64 calling convention
-                // First 4 args go in registers: RCX, RDX, R8, R9
-                // Additional args are pushed on the stack in reverse order
-                let arg_registers = ["rcx", "rdx", "r8", "r9"];
-                for (i, arg) in args.iter().enumerate() {
-                    // Evaluate the argument expression (result in RAX)
-                    self.generate_node(arg)?;
-
-                    if i < arg_registers.len() {
-                        // For the first 4 arguments, move from RAX to the appropriate register
-                        writeln!(self.output, "    mov {}, rax", arg_registers[i]).unwrap();
-                    } else {
-                        // For additional arguments, push onto the stack
-                        writeln!(self.output, "    push rax").unwrap();
+            Node::FunctionCall { name, args, location } => {
+                // A variable with the name is a function pointer to call through.
+                // Anything else must be a declared function, or the call could only
+                // fail when linking or running
+                if self.is_variable(name) {
+                    let callee = Node::Identifier(name.clone(), location.clone());
+                    if !self.expr_type(&callee).is_function_pointer() {
+                        return Err(codegen_error(
+                            Some(location),
+                            format!("Called object {} is not a function or function pointer", name),
+                        ));
                     }
+                    self.generate_call(Callee::Pointer(&callee), args)
+                } else if self.functions.contains_key(name) {
+                    self.generate_call(Callee::Named(name), args)
+                } else {
+                    Err(codegen_error(Some(location), format!("Call to undeclared function {}", name)))
                 }
-
-                // Call the function by name
-                // This will jump to the function and save the return address
-                writeln!(self.output, "    call {}", name).unwrap();
-
-                // Clean up stack space used for arguments beyond the first 4
-                // Each argument takes 8 bytes on the stack
-                if args.len() > arg_registers.len() {
-                    let stack_args = args.len() - arg_registers.len();
-                    writeln!(self.output, "    add rsp, {}", stack_args * 8).unwrap();
+            }
+            Node::IndirectCall { callee, args, .. } => self.generate_call(Callee::Pointer(callee), args),
+            Node::MemberAccess { expr, member, .. } => {
+                // Member access: compute the member's address from the struct's,
+                // then read the member unless it is itself an array or struct
+                self.generate_member_address(expr, member)?;
+                match self.expr_type(node) {
+                    Type::Array(_, _) | Type::Struct(_, _) | Type::Union(_, _) => {}
+                    member_type => self.emit_load(&member_type, "rax"),
+                }
+                Ok(())
+            }
+            Node::SizeofExpr(expr, _) => {
+                // The operand is not evaluated, only its type is used
+                let size = self.size_of(&self.expr_type(expr));
+                self.output.emit("mov", &["rax", &size.to_string()]);
+                Ok(())
+            }
+            Node::SizeofType(type_, _) => {
+                self.output.emit("mov", &["rax", &self.size_of(type_).to_string()]);
+                Ok(())
+            }
+            Node::Cast { type_, expr, .. } => {
+                let to = type_.strip_qualifiers();
+                if to == Type::Void {
+                    // The value is computed for its side effects and discarded
+                    return self.generate_node(expr);
                 }
 
-                // Restore all saved registers in reverse order
-                // This ensures the register state is the same as before the call
-                writeln!(self.output, "    pop r11").unwrap();
-                writeln!(self.output, "    pop r10").unwrap();
-                writeln!(self.output, "    pop r9").unwrap();
-                writeln!(self.output, "    pop r8").unwrap();
-                writeln!(self.output, "    pop rdx").unwrap();
-                writeln!(self.output, "    pop rcx").unwrap();
-                writeln!(self.output, "    pop rdi").unwrap();
-                writeln!(self.output, "    pop rsi").unwrap();
-                writeln!(self.output, "    pop rbx").unwrap();
+                let from = self.expr_type(expr);
+                self.generate_converted(expr, &to)?;
+                if to == Type::Float && from != Type::Float {
+                    // A float is held as a double, so the value is rounded to float
+                    // precision and back
+                    writeln!(self.output, "    movq xmm0, rax").unwrap();
+                    writeln!(self.output, "    cvtsd2ss xmm0, xmm0").unwrap();
+                    writeln!(self.output, "    cvtss2sd xmm0, xmm0").unwrap();
+                    writeln!(self.output, "    movq rax, xmm0").unwrap();
+                } else if to.is_integer() && to != Type::Bool && !from.is_floating() {
+                    // An integer is narrowed to the low bytes of the new type and
+                    // extended from them; a floating value already was when converted
+                    self.extend_result(&to);
+                }
+                Ok(())
+            }
+            Node::VaStart(list, _, location) => {
+                let Some(var_args) = self.var_args.clone() else {
+                    return Err(codegen_error(Some(location), "va_start used in a function with fixed arguments"));
+                };
+                let control = var_args.control;
+
+                // Fill in the control block and point the va_list at it
+                self.generate_address(list)?;
+                self.output.emit("mov", &["rcx", "rax"]);
+                writeln!(self.output, "    lea rax, [{}]", var_args.first).unwrap();
+                self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", control), "rax"]);
+                match &var_args.registers_end {
+                    Some(end) => writeln!(self.output, "    lea rax, [{}]", end).unwrap(),
+                    None => writeln!(self.output, "    xor eax, eax").unwrap(),
+                }
+                self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", control - 8), "rax"]);
+                writeln!(self.output, "    lea rax, [rbp+16]").unwrap();
+                self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", control - 16), "rax"]);
+                writeln!(self.output, "    lea rax, [rbp-{}]", control).unwrap();
+                self.output.emit("mov", &["qword ptr [rcx]", "rax"]);
+                Ok(())
+            }
+            Node::VaArg(list, type_, _) => {
+                let next_label = self.generate_label("vaarg");
+
+                // Take the next argument, moving on to the stack arguments once the
+                // saved registers run out, and step past it
+                self.generate_node(list)?;
+                self.output.emit("mov", &["rcx", "rax"]);
+                self.output.emit("mov", &["rax", "qword ptr [rcx]"]);
+                writeln!(self.output, "    cmp rax, qword ptr [rcx+8]").unwrap();
+                writeln!(self.output, "    jne {}", next_label).unwrap();
+                self.output.emit("mov", &["rax", "qword ptr [rcx+16]"]);
+                writeln!(self.output, "{}:", next_label).unwrap();
+                writeln!(self.output, "    lea rdx, [rax+8]").unwrap();
+                self.output.emit("mov", &["qword ptr [rcx]", "rdx"]);
+                self.emit_load(&type_.strip_qualifiers(), "rax");
+                Ok(())
+            }
+            // Nothing needs releasing when the arguments have been read
+            Node::VaEnd(list, _) => self.generate_node(list),
+            Node::CommaExpr { left, right, .. } => {
+                // Comma operator - evaluate the left operand and discard its value,
+                // then evaluate the right operand, leaving its value in RAX
+                self.generate_node(left)?;
+                self.generate_node(right)?;
+                Ok(())
+            }
+            Node::ConditionalExpr { condition, then_expr, else_expr, .. } => {
+                // Only the chosen arm is evaluated, and its value is converted to the
+                // type of the result, so both arms leave it at the same width
+                let result_type = self.expr_type(node);
+                let else_label = self.generate_label("condelse");
+                let end_label = self.generate_label("endcond");
+
+                self.generate_condition(condition)?;
+                writeln!(self.output, "    cmp rax, 0").unwrap();
+                writeln!(self.output, "    je {}", else_label).unwrap();
+                self.generate_conditional_arm(then_expr, &result_type)?;
+                self.output.emit("jmp", &[&end_label]);
 
-                // Function return value is already in RAX per calling convention
+                writeln!(self.output, "{}:", else_label).unwrap();
+                self.generate_conditional_arm(else_expr, &result_type)?;
+                writeln!(self.output, "{}:", end_label).unwrap();
                 Ok(())
             }
             Node::ExpressionStmt(expr) => {
                 // Expression statement - evaluate the expression but discard the result
                 // The value is left in RAX but not used by the caller
+                let stack_offset = self.compute_common_values(expr)?;
                 self.generate_node(expr)?;
+                self.release_common_values(stack_offset);
+                Ok(())
+            }
+            Node::EmptyStmt(_) => {
+                // Nothing to do, so no code at all
                 Ok(())
             }
-            Node::ReturnStmt(value, _) => {
+            Node::ReturnStmt(value, location) => {
                 // Return statement - evaluate the expression and jump to function epilogue
 
                 // If there's a return value, evaluate it (result will be in RAX)
                 if let Some(expr) = value {
-                    self.generate_node(expr)?;
-                    // The result is already in RAX, which is the return value register
+                    if self.expr_type(expr).is_record() {
+                        // Structs of up to 16 bytes are returned in RAX and RDX
+                        let size = self.size_of(&self.expr_type(expr));
+                        if size > 16 {
+                            return Err(codegen_error(Some(location), "Returning structs larger than 16 bytes is not supported yet"));
+                        }
+                        self.generate_struct_value(expr, size)?;
+                    } else {
+                        // The value is converted to the function's return type
+                        let return_type = match self.current_function.as_ref().and_then(|name| self.functions.get(name)) {
+                            Some(Type::Function(return_type, _, _)) => (**return_type).clone(),
+                            _ => self.expr_type(expr),
+                        };
+                        let stack_offset = self.compute_common_values(expr)?;
+                        self.generate_converted(expr, &return_type)?;
+                        self.release_common_values(stack_offset);
+
+                        // A value of another type is narrowed or widened to the
+                        // declared width, so all of RAX holds the returned value
+                        let return_type = return_type.strip_qualifiers();
+                        if self.expr_type(expr).strip_qualifiers() != return_type {
+                            self.extend_result(&return_type);
+                        }
+
+                        // The result is already in RAX, which is the return value register;
+                        // floating values are returned in XMM0
+                        match return_type {
+                            Type::Float => {
+                                writeln!(self.output, "    movq xmm0, rax").unwrap();
+                                writeln!(self.output, "    cvtsd2ss xmm0, xmm0").unwrap();
+                            }
+                            Type::Double => writeln!(self.output, "    movq xmm0, rax").unwrap(),
+                            _ => {}
+                        }
+                    }
                 }
 
                 if let Some(func_name) = &self.current_function {
                     // Jump to the function's epilogue (return label)
                     // This skips any remaining code in the function
-                    writeln!(self.output, "    jmp .{}ret", func_name).unwrap();
+                    self.output.emit("jmp", &[&format!(".{}ret", func_name)]);
                 } else {
-                    return Err(codegen_error("Return statement outside of function"));
+                    return Err(codegen_error(Some(location), "Return statement outside of function"));
                 }
 
                 Ok(())
             }
+            Node::LabelStmt(name, statement, _) => {
+                // Labels are namespaced by function so that each function can reuse names
+                writeln!(self.output, "{}:", self.source_label(name)?).unwrap();
+                self.generate_node(statement)
+            }
+            Node::GotoStmt(name, _) => {
+                self.output.emit("jmp", &[&self.source_label(name)?]);
+                Ok(())
+            }
+            Node::BreakStmt(location) => {
+                // Leave the innermost loop or switch
+                let Some(targets) = self.jump_targets.last() else {
+                    return Err(codegen_error(Some(location), "break statement not within a loop"));
+                };
+                self.output.emit("jmp", &[&targets.break_label]);
+                Ok(())
+            }
+            Node::ContinueStmt(location) => {
+                // Go on with the innermost loop, passing over any switch inside it
+                let Some(label) = self.jump_targets.iter().rev().find_map(|targets| targets.continue_label.as_ref()) else {
+                    return Err(codegen_error(Some(location), "continue statement not within a loop"));
+                };
+                self.output.emit("jmp", &[label]);
+                Ok(())
+            }
             Node::IfStmt {
                 condition,
                 then_branch,
                 else_branch,
+                parenthesized: _,
                 location: _,
             } => {
                 // If-else statement with conditional branching
@@ -677,7 +2254,7 @@ impl CodeGenerator {
 
                 // Generate code for the condition expression
                 // Result will be in RAX
-                self.generate_node(condition)?;
+                self.generate_condition(condition)?;
 
                 // Compare the condition result with 0 (false)
                 writeln!(self.output, "    cmp rax, 0").unwrap();
@@ -687,7 +2264,7 @@ impl CodeGenerator {
                 // Generate code for the 'then' branch (executed if condition is true)
                 self.generate_node(then_branch)?;
                 // After executing 'then' branch, skip the 'else' branch
-                writeln!(self.output, "    jmp {}", end_label).unwrap();
+                self.output.emit("jmp", &[&end_label]);
 
                 // Else branch starts here
                 writeln!(self.output, "{}:", else_label).unwrap();
@@ -704,6 +2281,7 @@ impl CodeGenerator {
             Node::WhileStmt {
                 condition,
                 body,
+                parenthesized: _,
                 location: _,
             } => {
                 // While loop implementation
@@ -715,16 +2293,21 @@ impl CodeGenerator {
                 writeln!(self.output, "{}:", start_label).unwrap();
 
                 // Generate code for the condition expression
-                self.generate_node(condition)?;
+                self.generate_condition(condition)?;
                 // Compare condition result with 0 (false)
                 writeln!(self.output, "    cmp rax, 0").unwrap();
                 // If condition is false, exit the loop
                 writeln!(self.output, "    je {}", end_label).unwrap();
 
-                // Generate code for the loop body
+                // Generate code for the loop body, where continue checks the condition again
+                self.jump_targets.push(JumpTargets {
+                    break_label: end_label.clone(),
+                    continue_label: Some(start_label.clone()),
+                });
                 self.generate_node(body)?;
+                self.jump_targets.pop();
                 // After executing the body, jump back to check the condition again
-                writeln!(self.output, "    jmp {}", start_label).unwrap();
+                self.output.emit("jmp", &[&start_label]);
 
                 // Loop end label - execution continues here when the condition becomes false
                 writeln!(self.output, "{}:", end_label).unwrap();
@@ -755,13 +2338,19 @@ impl CodeGenerator {
                 // 2. Condition check - if false, exit the loop
                 // If no condition is provided, the loop runs indefinitely (until break)
                 if let Some(condition) = condition {
-                    self.generate_node(condition)?;
+                    self.generate_condition(condition)?;
                     writeln!(self.output, "    cmp rax, 0").unwrap();  // Compare with false (0)
                     writeln!(self.output, "    je {}", end_label).unwrap();  // Exit if condition is false
                 }
 
-                // 3. Loop body - the main code to execute in each iteration
+                // 3. Loop body - the main code to execute in each iteration; continue
+                // goes on with the increment
+                self.jump_targets.push(JumpTargets {
+                    break_label: end_label.clone(),
+                    continue_label: Some(inc_label.clone()),
+                });
                 self.generate_node(body)?;
+                self.jump_targets.pop();
 
                 // 4. Increment section - executed after each iteration
                 writeln!(self.output, "{}:", inc_label).unwrap();
@@ -770,7 +2359,7 @@ impl CodeGenerator {
                 }
 
                 // Jump back to the condition check for the next iteration
-                writeln!(self.output, "    jmp {}", start_label).unwrap();
+                self.output.emit("jmp", &[&start_label]);
 
                 // Loop end label - execution continues here when the loop exits
                 writeln!(self.output, "{}:", end_label).unwrap();
@@ -779,59 +2368,124 @@ impl CodeGenerator {
             }
             Node::BlockStmt(statements, _) => {
                 // Block statement - a sequence of statements executed in order
-                // No special assembly setup is needed for blocks - just generate code for each statement
+                // Variables declared in the block go out of scope at its end, uncovering
+                // any they shadowed, and their frame slots are reused by later blocks
+                let variables = self.variables.clone();
+                let static_locals = self.static_locals.clone();
+                let stack_offset = self.stack_offset;
+
+                // At -O1, statements after a return, goto, break or continue are left
+                // out until one a goto can jump to. Declarations are kept for the
+                // statements after such a label; their initializers are removed later.
+                let mut reachable = true;
                 for stmt in statements {
+                    let labeled = has_label(stmt);
+                    if !reachable && !labeled && self.opt_level >= 1 && !matches!(stmt, Node::VarDecl { .. }) {
+                        continue;
+                    }
                     self.generate_node(stmt)?;
+                    reachable = (reachable || labeled) && !always_jumps(stmt);
                 }
 
+                self.variables = variables;
+                self.static_locals = static_locals;
+                self.stack_offset = stack_offset;
+
                 Ok(())
             }
             Node::VarDecl {
                 name,
                 type_,
+                storage,
                 initializer,
-                location: _,
+                location,
             } => {
+                match storage {
+                    StorageClass::Extern => {
+                        // A block-scope extern refers to the global of that name
+                        self.variables.remove(name);
+                        self.static_locals.remove(name);
+                        self.globals.insert(name.clone(), type_.strip_qualifiers());
+                        return Ok(());
+                    }
+                    StorageClass::Static => {
+                        // A static local lives in a single fixed slot under a label of its
+                        // own, initialized once when the program is loaded
+                        let function = self.current_function.clone().unwrap_or_default();
+                        let label = self.generate_label(&format!("{}_{}_", function, name));
+                        self.emit_static_variable(&label, name, type_, initializer.as_deref(), false, location)?;
+
+                        self.variables.remove(name);
+                        self.static_locals.insert(name.clone(), label.clone());
+                        self.globals.insert(label, type_.strip_qualifiers());
+                        return Ok(());
+                    }
+                    StorageClass::Auto => {}
+                }
+
                 // Local variable declaration with optional initialization
+                let type_ = &type_.strip_qualifiers();
 
                 // Calculate the size of the variable based on its type
                 let size = self.size_of(type_);
 
                 // Determine the alignment requirement for the variable type
                 let align = match type_ {
-                    Type::Char => 1,                // 1-byte alignment for char
-                    Type::Int => 4,                // 4-byte alignment for int
-                    Type::Long => 8,               // 8-byte alignment for long
+                    Type::Bool | Type::Char | Type::UChar => 1, // 1-byte alignment for char
+                    Type::Int | Type::UInt => 4,   // 4-byte alignment for int
+                    Type::Long | Type::ULong => 8, // 8-byte alignment for long
                     Type::Pointer(_) => 8,         // 8-byte alignment for pointers
                     Type::Array(_, _) => 8,        // 8-byte alignment for arrays
                     _ => 8,                        // Default to 8-byte alignment
                 };
 
-                // Adjust the stack offset to maintain proper alignment
-                // This ensures all variables are properly aligned in memory
-                self.stack_offset = self.align_to(self.stack_offset + size, align);
+                // Reserve an aligned slot before anything is stored in it
+                let offset = self.allocate_slot(size, align);
 
                 // Register the variable in our symbol table with its stack offset
                 self.variables.insert(
                     name.clone(),
                     Variable {
-                        offset: self.stack_offset,  // Distance from base pointer
+                        offset,                    // Distance from base pointer
                         type_: type_.clone(),      // Type information for later use
                     },
                 );
 
-                // Allocate space on the stack for the variable
-                // This decreases RSP to make room for the variable
-                writeln!(self.output, "    sub rsp, {}", size).unwrap();
-
                 // If there's an initializer, evaluate it and store the result
                 if let Some(init) = initializer {
-                    // Evaluate the initializer expression (result in RAX)
-                    self.generate_node(init)?;
+                    if let (Type::Array(_, Some(size)), Node::StringLiteral(value, _)) = (type_, init.as_ref()) {
+                        // Copy the string into the array, zeroing the rest of it
+                        let bytes = string_bytes(value, *size);
+                        for (chunk_offset, chunk) in chunks(*size) {
+                            let value = bytes[chunk_offset..chunk_offset + chunk]
+                                .iter()
+                                .rev()
+                                .fold(0u64, |value, byte| value << 8 | *byte as u64);
+                            let address = offset - chunk_offset;
+                            if chunk == 8 {
+                                self.emit_constant("rax", value as i64);
+                                self.output.emit("mov", &[&format!("qword ptr [rbp-{}]", address), "rax"]);
+                            } else {
+                                self.output.emit("mov", &[&format!("{} ptr [rbp-{}]", ptr_size(chunk), address), &value.to_string()]);
+                            }
+                        }
+                        return Ok(());
+                    }
+
+                    if type_.is_record() {
+                        // Copy the initializing struct into the variable
+                        writeln!(self.output, "    lea rax, [rbp-{}]", offset).unwrap();
+                        return self.generate_struct_store(init, size);
+                    }
+
+                    // Evaluate the initializer expression (result in RAX),
+                    // converted to the type of the variable
+                    self.generate_converted(init, type_)?;
 
                     // Store the value from RAX into the variable's stack location
                     // For pointers, we need to store the address
-                    writeln!(self.output, "    mov [rbp-{}], rax", self.stack_offset).unwrap();
+                    let address = format!("rbp-{}", offset);
+                    self.emit_store(type_, &address, "rax");
                 }
 
                 Ok(())
@@ -850,3 +2504,106 @@ impl CodeGenerator {
         }
     }
 }
+
+/// The bytes a string initializer stores into a char array of the given size: its
+/// characters, cut off or padded with zeros to fill the array
+fn string_bytes(value: &str, size: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = literal_bytes(value).collect();
+    bytes.resize(size, 0);
+    bytes
+}
+
+/// Write bytes for an `.ascii` directive, escaping quotes, backslashes and
+/// anything that is not printable as octal escapes
+fn escape_ascii(bytes: &[u8]) -> String {
+    let mut escaped = String::new();
+    for &byte in bytes {
+        match byte {
+            b'"' | b'\\' => {
+                escaped.push('\\');
+                escaped.push(byte as char);
+            }
+            0x20..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    escaped
+}
+
+/// Get the name of the low 1, 2, 4 or 8 bytes of a 64-bit register
+fn sub_register(register: &str, size: usize) -> String {
+    // r8-r15 name their low parts with a b/w/d suffix
+    if register[1..].parse::<u8>().is_ok() {
+        return match size {
+            1 => format!("{}b", register),
+            2 => format!("{}w", register),
+            4 => format!("{}d", register),
+            _ => register.to_string(),
+        };
+    }
+
+    let letter = match register {
+        "rax" => "a",
+        "rbx" => "b",
+        "rcx" => "c",
+        "rdx" => "d",
+        _ => unreachable!("Unsupported register: {}", register),
+    };
+    match size {
+        1 => format!("{}l", letter),
+        2 => format!("{}x", letter),
+        4 => format!("e{}x", letter),
+        _ => register.to_string(),
+    }
+}
+
+/// Split `size` bytes into the 8-, 4-, 2- and 1-byte pieces a copy moves at once,
+/// as (offset, size) pairs in increasing address order
+fn chunks(size: usize) -> Vec<(usize, usize)> {
+    let mut chunks = Vec::new();
+    let mut offset = 0;
+    while offset < size {
+        let chunk = [8, 4, 2, 1].into_iter().find(|&chunk| chunk <= size - offset).unwrap();
+        chunks.push((offset, chunk));
+        offset += chunk;
+    }
+    chunks
+}
+
+/// Get the size keyword of a memory operand of the given width
+fn ptr_size(size: usize) -> &'static str {
+    match size {
+        1 => "byte",
+        2 => "word",
+        4 => "dword",
+        _ => "qword",
+    }
+}
+
+/// Check if an operand is an immediate value rather than a register
+fn is_immediate(operand: &str) -> bool {
+    operand.parse::<i64>().is_ok()
+}
+
+/// Check if a statement defines a label, so a goto can reach it from elsewhere
+fn has_label(node: &Node) -> bool {
+    match node {
+        Node::LabelStmt(_, _, _) => true,
+        Node::BlockStmt(statements, _) => statements.iter().any(has_label),
+        Node::IfStmt { then_branch, else_branch, .. } => {
+            has_label(then_branch) || else_branch.as_deref().is_some_and(has_label)
+        }
+        Node::WhileStmt { body, .. } | Node::ForStmt { body, .. } => has_label(body),
+        _ => false,
+    }
+}
+
+/// Check if control never continues past a statement: it ends in a jump
+fn always_jumps(node: &Node) -> bool {
+    match node {
+        Node::ReturnStmt(_, _) | Node::GotoStmt(_, _) | Node::BreakStmt(_) | Node::ContinueStmt(_) => true,
+        Node::LabelStmt(_, statement, _) => always_jumps(statement),
+        Node::BlockStmt(statements, _) => statements.last().is_some_and(always_jumps),
+        _ => false,
+    }
+}
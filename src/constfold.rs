@@ -0,0 +1,187 @@
+use crate::ast::{BinaryOp, Location, Node, Type, UnaryOp};
+use crate::error::{semantic_error, Result};
+
+/// An integer constant together with its C type
+struct Constant {
+    value: i64,
+    type_: Type,
+}
+
+impl Constant {
+    /// Read the constant value of a literal node
+    fn from_node(node: &Node) -> Option<Self> {
+        match node {
+            Node::IntLiteral(value, _) => Some(Self::new(*value, literal_type(*value))),
+            Node::UIntLiteral(value, _) => {
+                let type_ = if u32::try_from(*value).is_ok() { Type::UInt } else { Type::ULong };
+                Some(Self::new(*value as i64, type_))
+            }
+            Node::LongLiteral(value, _) => Some(Self::new(*value, Type::Long)),
+            Node::ULongLiteral(value, _) => Some(Self::new(*value as i64, Type::ULong)),
+            Node::CharLiteral(value, _) => Some(Self::new(*value as u8 as i8 as i64, Type::Int)),
+            _ => None,
+        }
+    }
+
+    /// Create a constant, wrapping the value to the width of its type
+    fn new(value: i64, type_: Type) -> Self {
+        let value = match type_ {
+            Type::Int => value as i32 as i64,
+            Type::UInt => value as u32 as i64,
+            _ => value,
+        };
+        Self { value, type_ }
+    }
+
+    /// Turn the constant back into a literal node
+    fn into_node(self, location: Location) -> Node {
+        match self.type_ {
+            Type::Long => Node::LongLiteral(self.value, location),
+            Type::ULong => Node::ULongLiteral(self.value as u64, location),
+            Type::UInt => Node::UIntLiteral(self.value as u64, location),
+            _ => Node::IntLiteral(self.value, location),
+        }
+    }
+}
+
+/// The type of an unsuffixed integer literal
+fn literal_type(value: i64) -> Type {
+    if i32::try_from(value).is_ok() {
+        Type::Int
+    } else {
+        Type::Long
+    }
+}
+
+/// Fold the constant expressions of a whole program
+pub fn fold_program(program: Node) -> Result<Node> {
+    fold(program)
+}
+
+/// Evaluate an expression that must be an integer constant, if it is one
+pub fn eval_constant(node: Node) -> Result<Option<i64>> {
+    Ok(Constant::from_node(&fold(node)?).map(|c| c.value))
+}
+
+/// Recursively replace operations on integer literals with their result
+fn fold(node: Node) -> Result<Node> {
+    // The operand of sizeof is never evaluated, so it is left as written
+    if matches!(node, Node::SizeofExpr(_, _)) {
+        return Ok(node);
+    }
+
+    Ok(match node.try_map_children(&mut fold)? {
+        Node::BinaryExpr { op, left, right, location } => {
+            // A constant zero divisor would trap at runtime, whatever the dividend
+            if matches!(op, BinaryOp::Divide | BinaryOp::Modulo)
+                && Constant::from_node(&right).is_some_and(|divisor| divisor.value == 0)
+            {
+                return Err(semantic_error(&location, "Division by zero"));
+            }
+
+            match fold_binary(&op, &left, &right) {
+                Some(constant) => constant.into_node(location),
+                None => Node::BinaryExpr { op, left, right, location },
+            }
+        }
+        Node::UnaryExpr { op, expr, location } => {
+            // Negative floating literals are folded too, so they can initialize globals
+            if let (UnaryOp::Negate, Node::FloatLiteral(value, _)) = (&op, expr.as_ref()) {
+                return Ok(Node::FloatLiteral(-value, location));
+            }
+
+            match fold_unary(&op, &expr) {
+                Some(constant) => constant.into_node(location),
+                None => Node::UnaryExpr { op, expr, location },
+            }
+        }
+        Node::ConditionalExpr { condition, then_expr, else_expr, location } => {
+            // With constant operands throughout, the result is the chosen one
+            // converted to the type of both
+            match (
+                Constant::from_node(&condition),
+                Constant::from_node(&then_expr),
+                Constant::from_node(&else_expr),
+            ) {
+                (Some(condition), Some(then_value), Some(else_value)) => {
+                    let type_ = Type::arithmetic_type(&then_value.type_, &else_value.type_);
+                    let chosen = if condition.value != 0 { then_value } else { else_value };
+                    Constant::new(chosen.value, type_).into_node(location)
+                }
+                _ => Node::ConditionalExpr { condition, then_expr, else_expr, location },
+            }
+        }
+        other => other,
+    })
+}
+
+/// Evaluate a binary operation on two constants
+fn fold_binary(op: &BinaryOp, left: &Node, right: &Node) -> Option<Constant> {
+    let (left, right) = match (Constant::from_node(left), Constant::from_node(right)) {
+        (Some(left), Some(right)) => (left, right),
+        _ => return None,
+    };
+
+    let type_ = Type::arithmetic_type(&left.type_, &right.type_);
+    let unsigned = type_.is_unsigned();
+    let (l, r) = (left.value, right.value);
+    let truth = |b: bool| Constant::new(b as i64, Type::Int);
+
+    let constant = match op {
+        BinaryOp::Add => Constant::new(l.wrapping_add(r), type_),
+        BinaryOp::Subtract => Constant::new(l.wrapping_sub(r), type_),
+        BinaryOp::Multiply => Constant::new(l.wrapping_mul(r), type_),
+        BinaryOp::Divide | BinaryOp::Modulo => {
+            // A zero divisor has already been rejected by `fold`
+            let value = match (op, unsigned) {
+                (BinaryOp::Divide, true) => ((l as u64) / (r as u64)) as i64,
+                (BinaryOp::Divide, false) => l.wrapping_div(r),
+                (_, true) => ((l as u64) % (r as u64)) as i64,
+                (_, false) => l.wrapping_rem(r),
+            };
+            Constant::new(value, type_)
+        }
+        BinaryOp::Equal => truth(l == r),
+        BinaryOp::NotEqual => truth(l != r),
+        BinaryOp::Less if unsigned => truth((l as u64) < (r as u64)),
+        BinaryOp::LessEqual if unsigned => truth((l as u64) <= (r as u64)),
+        BinaryOp::Greater if unsigned => truth((l as u64) > (r as u64)),
+        BinaryOp::GreaterEqual if unsigned => truth((l as u64) >= (r as u64)),
+        BinaryOp::Less => truth(l < r),
+        BinaryOp::LessEqual => truth(l <= r),
+        BinaryOp::Greater => truth(l > r),
+        BinaryOp::GreaterEqual => truth(l >= r),
+        BinaryOp::LogicalAnd => truth(l != 0 && r != 0),
+        BinaryOp::LogicalOr => truth(l != 0 || r != 0),
+        BinaryOp::BitwiseAnd => Constant::new(l & r, type_),
+        BinaryOp::BitwiseOr => Constant::new(l | r, type_),
+        BinaryOp::BitwiseXor => Constant::new(l ^ r, type_),
+        BinaryOp::ShiftLeft | BinaryOp::ShiftRight => {
+            // Shifts take the promoted type of the left operand
+            let type_ = left.type_.promote();
+            let count = (r & 63) as u32;
+            let value = match (op, type_.is_unsigned()) {
+                (BinaryOp::ShiftLeft, _) => l.wrapping_shl(count),
+                (_, true) => ((l as u64).wrapping_shr(count)) as i64,
+                (_, false) => l.wrapping_shr(count),
+            };
+            Constant::new(value, type_)
+        }
+        BinaryOp::Assign => return None,
+    };
+
+    Some(constant)
+}
+
+/// Evaluate a unary operation on a constant
+fn fold_unary(op: &UnaryOp, expr: &Node) -> Option<Constant> {
+    let operand = Constant::from_node(expr)?;
+    let type_ = operand.type_.promote();
+
+    match op {
+        UnaryOp::Negate => Some(Constant::new(operand.value.wrapping_neg(), type_)),
+        UnaryOp::BitwiseNot => Some(Constant::new(!operand.value, type_)),
+        UnaryOp::LogicalNot => Some(Constant::new((operand.value == 0) as i64, Type::Int)),
+        UnaryOp::Dereference | UnaryOp::AddressOf => None,
+    }
+}
@@ -0,0 +1,239 @@
+//! Common subexpression elimination within a single statement. A pure
+//! subexpression evaluated more than once, like `a[i]` in `a[i] * a[i]`, is found
+//! here so the code generator can compute it once and reuse the value.
+
+use std::collections::HashSet;
+
+use crate::ast::{BinaryOp, Node, Type, TypeMap};
+
+/// An occurrence of a subexpression, and whether it is only evaluated depending
+/// on another operand, as the right side of `&&` is
+type Occurrence<'a> = (&'a Node, bool);
+
+/// Find the subexpressions of a statement's expression worth computing once: pure,
+/// scalar, more than a variable or constant, and evaluated more than once, at least
+/// once unconditionally. Smaller ones come first, so a larger one can be computed
+/// from the values of those inside it.
+///
+/// Nothing is found if the expression calls a function or assigns anywhere but at
+/// its top, as either could change a value between two evaluations.
+pub fn common_subexpressions<'a>(expr: &'a Node, types: &TypeMap) -> Vec<&'a Node> {
+    let mut occurrences = Vec::new();
+    match expr {
+        Node::BinaryExpr { op: BinaryOp::Assign, left, right, .. } => {
+            if has_effects(left) || has_effects(right) {
+                return Vec::new();
+            }
+            // The assigned object's address is computed, but not its value
+            collect_address(left, types, &mut occurrences);
+            collect(right, false, types, &mut occurrences);
+        }
+        _ => {
+            if has_effects(expr) {
+                return Vec::new();
+            }
+            collect(expr, false, types, &mut occurrences);
+        }
+    }
+
+    // Take the largest repeated expression first. Its other occurrences are not
+    // evaluated at all, so what they contain no longer counts as repeated.
+    occurrences.sort_by_key(|(node, _)| std::cmp::Reverse(size(node)));
+    let mut replaced = HashSet::new();
+    let mut common: Vec<&Node> = Vec::new();
+    for (node, _) in &occurrences {
+        if replaced.contains(&(*node as *const Node)) || common.iter().any(|chosen| same_expression(chosen, node)) {
+            continue;
+        }
+        let same: Vec<&Occurrence> = occurrences
+            .iter()
+            .filter(|(other, _)| !replaced.contains(&(*other as *const Node)) && same_expression(node, other))
+            .collect();
+        let Some(&&(first, _)) = same.iter().find(|(_, conditional)| !conditional) else {
+            continue;
+        };
+        if same.len() < 2 {
+            continue;
+        }
+
+        for (other, _) in same {
+            if !std::ptr::eq(*other, first) {
+                mark_replaced(other, &mut replaced);
+            }
+        }
+        common.push(first);
+    }
+
+    common.reverse();
+    common
+}
+
+/// Check if two expressions are written the same way, so that, being pure, they
+/// have the same value within one statement
+pub fn same_expression(left: &Node, right: &Node) -> bool {
+    match (left, right) {
+        (Node::IntLiteral(l, _), Node::IntLiteral(r, _)) => l == r,
+        (Node::UIntLiteral(l, _), Node::UIntLiteral(r, _)) => l == r,
+        (Node::LongLiteral(l, _), Node::LongLiteral(r, _)) => l == r,
+        (Node::ULongLiteral(l, _), Node::ULongLiteral(r, _)) => l == r,
+        (Node::FloatLiteral(l, _), Node::FloatLiteral(r, _)) => l.to_bits() == r.to_bits(),
+        (Node::CharLiteral(l, _), Node::CharLiteral(r, _)) => l == r,
+        (Node::StringLiteral(l, _), Node::StringLiteral(r, _)) => l == r,
+        (Node::Identifier(l, _), Node::Identifier(r, _)) => l == r,
+        (
+            Node::BinaryExpr { op: l_op, left: l_left, right: l_right, .. },
+            Node::BinaryExpr { op: r_op, left: r_left, right: r_right, .. },
+        ) => l_op == r_op && same_expression(l_left, r_left) && same_expression(l_right, r_right),
+        (Node::UnaryExpr { op: l_op, expr: l_expr, .. }, Node::UnaryExpr { op: r_op, expr: r_expr, .. }) => {
+            l_op == r_op && same_expression(l_expr, r_expr)
+        }
+        (Node::CommaExpr { left: l_left, right: l_right, .. }, Node::CommaExpr { left: r_left, right: r_right, .. }) => {
+            same_expression(l_left, r_left) && same_expression(l_right, r_right)
+        }
+        (
+            Node::ConditionalExpr { condition: l_condition, then_expr: l_then, else_expr: l_else, .. },
+            Node::ConditionalExpr { condition: r_condition, then_expr: r_then, else_expr: r_else, .. },
+        ) => same_expression(l_condition, r_condition) && same_expression(l_then, r_then) && same_expression(l_else, r_else),
+        (
+            Node::MemberAccess { expr: l_expr, member: l_member, .. },
+            Node::MemberAccess { expr: r_expr, member: r_member, .. },
+        ) => l_member == r_member && same_expression(l_expr, r_expr),
+        (Node::SizeofExpr(l, _), Node::SizeofExpr(r, _)) => same_expression(l, r),
+        (Node::SizeofType(l, _), Node::SizeofType(r, _)) => l == r,
+        (Node::Cast { type_: l_type, expr: l_expr, .. }, Node::Cast { type_: r_type, expr: r_expr, .. }) => {
+            l_type == r_type && same_expression(l_expr, r_expr)
+        }
+        _ => false,
+    }
+}
+
+/// Check if evaluating an expression may change the value of another one: it calls
+/// a function, assigns or steps through variable arguments
+fn has_effects(node: &Node) -> bool {
+    match node {
+        Node::IntLiteral(_, _)
+        | Node::UIntLiteral(_, _)
+        | Node::LongLiteral(_, _)
+        | Node::ULongLiteral(_, _)
+        | Node::FloatLiteral(_, _)
+        | Node::CharLiteral(_, _)
+        | Node::StringLiteral(_, _)
+        | Node::Identifier(_, _)
+        | Node::SizeofExpr(_, _)
+        | Node::SizeofType(_, _) => false,
+        Node::BinaryExpr { op, left, right, .. } => *op == BinaryOp::Assign || has_effects(left) || has_effects(right),
+        Node::CommaExpr { left, right, .. } => has_effects(left) || has_effects(right),
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => {
+            has_effects(condition) || has_effects(then_expr) || has_effects(else_expr)
+        }
+        Node::UnaryExpr { expr, .. } | Node::MemberAccess { expr, .. } | Node::Cast { expr, .. } => has_effects(expr),
+        _ => true,
+    }
+}
+
+/// Check if an expression is worth computing once rather than each time: it does
+/// some work, has a scalar value that fits a temporary, and reads no volatile object
+fn is_candidate(node: &Node, types: &TypeMap) -> bool {
+    let worth_it = matches!(
+        node,
+        Node::BinaryExpr { .. }
+            | Node::UnaryExpr { .. }
+            | Node::MemberAccess { .. }
+            | Node::Cast { .. }
+            | Node::ConditionalExpr { .. }
+    );
+    let scalar = types
+        .get(node)
+        .is_some_and(|type_| type_.is_arithmetic() || matches!(type_.unqualified(), Type::Pointer(_)));
+    worth_it && scalar && !reads_volatile(node, types)
+}
+
+/// Check if an expression reads a volatile object, which must be read each time
+fn reads_volatile(node: &Node, types: &TypeMap) -> bool {
+    types.get(node).is_some_and(|type_| type_.is_volatile())
+        || match node {
+            Node::BinaryExpr { left, right, .. } | Node::CommaExpr { left, right, .. } => {
+                reads_volatile(left, types) || reads_volatile(right, types)
+            }
+            Node::ConditionalExpr { condition, then_expr, else_expr, .. } => {
+                reads_volatile(condition, types) || reads_volatile(then_expr, types) || reads_volatile(else_expr, types)
+            }
+            Node::UnaryExpr { expr, .. } | Node::MemberAccess { expr, .. } | Node::Cast { expr, .. } => {
+                reads_volatile(expr, types)
+            }
+            _ => false,
+        }
+}
+
+/// Collect the candidate subexpressions that are evaluated with an expression
+fn collect<'a>(node: &'a Node, conditional: bool, types: &TypeMap, occurrences: &mut Vec<Occurrence<'a>>) {
+    if is_candidate(node, types) {
+        occurrences.push((node, conditional));
+    }
+    match node {
+        // The right operand of && and || is not always evaluated
+        Node::BinaryExpr { op: BinaryOp::LogicalAnd | BinaryOp::LogicalOr, left, right, .. } => {
+            collect(left, conditional, types, occurrences);
+            collect(right, true, types, occurrences);
+        }
+        Node::BinaryExpr { left, right, .. } | Node::CommaExpr { left, right, .. } => {
+            collect(left, conditional, types, occurrences);
+            collect(right, conditional, types, occurrences);
+        }
+        // Only one of the arms of ?: is evaluated
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => {
+            collect(condition, conditional, types, occurrences);
+            collect(then_expr, true, types, occurrences);
+            collect(else_expr, true, types, occurrences);
+        }
+        Node::UnaryExpr { expr, .. } | Node::MemberAccess { expr, .. } | Node::Cast { expr, .. } => {
+            collect(expr, conditional, types, occurrences)
+        }
+        // The operand of sizeof is never evaluated
+        _ => {}
+    }
+}
+
+/// Collect the candidate subexpressions evaluated to find the address of an
+/// assigned object
+fn collect_address<'a>(node: &'a Node, types: &TypeMap, occurrences: &mut Vec<Occurrence<'a>>) {
+    match node {
+        Node::UnaryExpr { expr, .. } => collect(expr, false, types, occurrences),
+        Node::MemberAccess { expr, .. } => collect_address(expr, types, occurrences),
+        _ => {}
+    }
+}
+
+/// Record an occurrence and everything inside it as no longer evaluated
+fn mark_replaced(node: &Node, replaced: &mut HashSet<*const Node>) {
+    replaced.insert(node as *const Node);
+    match node {
+        Node::BinaryExpr { left, right, .. } | Node::CommaExpr { left, right, .. } => {
+            mark_replaced(left, replaced);
+            mark_replaced(right, replaced);
+        }
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => {
+            mark_replaced(condition, replaced);
+            mark_replaced(then_expr, replaced);
+            mark_replaced(else_expr, replaced);
+        }
+        Node::UnaryExpr { expr, .. }
+        | Node::MemberAccess { expr, .. }
+        | Node::SizeofExpr(expr, _)
+        | Node::Cast { expr, .. } => mark_replaced(expr, replaced),
+        _ => {}
+    }
+}
+
+/// The number of nodes in an expression
+fn size(node: &Node) -> usize {
+    1 + match node {
+        Node::BinaryExpr { left, right, .. } | Node::CommaExpr { left, right, .. } => size(left) + size(right),
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => size(condition) + size(then_expr) + size(else_expr),
+        Node::UnaryExpr { expr, .. }
+        | Node::MemberAccess { expr, .. }
+        | Node::SizeofExpr(expr, _)
+        | Node::Cast { expr, .. } => size(expr),
+        _ => 0,
+    }
+}
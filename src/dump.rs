@@ -0,0 +1,511 @@
+use std::fmt::Write;
+
+use crate::ast::{Location, Node, StorageClass, Type};
+use crate::lexer::Token;
+use crate::typechecker::Symbol;
+
+/// Render the token stream one token per line, with its position and length
+pub fn tokens(tokens: &[Token]) -> String {
+    let mut output = String::new();
+    for token in tokens {
+        writeln!(
+            output,
+            "Token: {:?} at {}:{}, length {}",
+            token.kind, token.location.line, token.location.column, token.length
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Render the names the type checker defined one per line, with their type and
+/// the depth of the scope they were defined in
+pub fn symbols(symbols: &[Symbol]) -> String {
+    let mut output = String::new();
+    for symbol in symbols {
+        writeln!(output, "Symbol: {}: {} at depth {}", symbol.name, symbol.type_, symbol.depth).unwrap();
+    }
+    output
+}
+
+/// Render an AST as an indented tree, one node per line with its children below it
+pub fn ast(node: &Node) -> String {
+    let mut output = String::new();
+    write_node(&mut output, node, 0);
+    output
+}
+
+/// Write a node and its children at the given depth
+fn write_node(output: &mut String, node: &Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+
+    match node {
+        Node::IntLiteral(value, _) => writeln!(output, "{}IntLiteral {}", indent, value).unwrap(),
+        Node::UIntLiteral(value, _) => writeln!(output, "{}UIntLiteral {}", indent, value).unwrap(),
+        Node::LongLiteral(value, _) => writeln!(output, "{}LongLiteral {}", indent, value).unwrap(),
+        Node::ULongLiteral(value, _) => writeln!(output, "{}ULongLiteral {}", indent, value).unwrap(),
+        Node::FloatLiteral(value, _) => writeln!(output, "{}FloatLiteral {:?}", indent, value).unwrap(),
+        Node::CharLiteral(value, _) => writeln!(output, "{}CharLiteral {:?}", indent, value).unwrap(),
+        Node::StringLiteral(value, _) => writeln!(output, "{}StringLiteral {:?}", indent, value).unwrap(),
+        Node::Identifier(name, _) => writeln!(output, "{}Identifier {}", indent, name).unwrap(),
+        Node::BinaryExpr { op, left, right, .. } => {
+            writeln!(output, "{}BinaryExpr {}", indent, op).unwrap();
+            write_node(output, left, depth + 1);
+            write_node(output, right, depth + 1);
+        }
+        Node::UnaryExpr { op, expr, .. } => {
+            writeln!(output, "{}UnaryExpr {}", indent, op).unwrap();
+            write_node(output, expr, depth + 1);
+        }
+        Node::FunctionCall { name, args, .. } => {
+            writeln!(output, "{}FunctionCall {}", indent, name).unwrap();
+            for arg in args {
+                write_node(output, arg, depth + 1);
+            }
+        }
+        Node::IndirectCall { callee, args, .. } => {
+            writeln!(output, "{}IndirectCall", indent).unwrap();
+            write_labeled(output, "callee", callee, depth + 1);
+            for arg in args {
+                write_node(output, arg, depth + 1);
+            }
+        }
+        Node::CommaExpr { left, right, .. } => {
+            writeln!(output, "{}CommaExpr", indent).unwrap();
+            write_node(output, left, depth + 1);
+            write_node(output, right, depth + 1);
+        }
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => {
+            writeln!(output, "{}ConditionalExpr", indent).unwrap();
+            write_labeled(output, "condition", condition, depth + 1);
+            write_labeled(output, "then", then_expr, depth + 1);
+            write_labeled(output, "else", else_expr, depth + 1);
+        }
+        Node::MemberAccess { expr, member, .. } => {
+            writeln!(output, "{}MemberAccess .{}", indent, member).unwrap();
+            write_node(output, expr, depth + 1);
+        }
+        Node::SizeofExpr(expr, _) => {
+            writeln!(output, "{}SizeofExpr", indent).unwrap();
+            write_node(output, expr, depth + 1);
+        }
+        Node::SizeofType(type_, _) => writeln!(output, "{}SizeofType {}", indent, type_).unwrap(),
+        Node::Cast { type_, expr, .. } => {
+            writeln!(output, "{}Cast {}", indent, type_).unwrap();
+            write_node(output, expr, depth + 1);
+        }
+        Node::VaStart(list, last, _) => {
+            writeln!(output, "{}VaStart {}", indent, last).unwrap();
+            write_node(output, list, depth + 1);
+        }
+        Node::VaArg(list, type_, _) => {
+            writeln!(output, "{}VaArg {}", indent, type_).unwrap();
+            write_node(output, list, depth + 1);
+        }
+        Node::VaEnd(list, _) => {
+            writeln!(output, "{}VaEnd", indent).unwrap();
+            write_node(output, list, depth + 1);
+        }
+        Node::ExpressionStmt(expr) => {
+            writeln!(output, "{}ExpressionStmt", indent).unwrap();
+            write_node(output, expr, depth + 1);
+        }
+        Node::EmptyStmt(_) => writeln!(output, "{}EmptyStmt", indent).unwrap(),
+        Node::ReturnStmt(value, _) => {
+            writeln!(output, "{}ReturnStmt", indent).unwrap();
+            if let Some(value) = value {
+                write_node(output, value, depth + 1);
+            }
+        }
+        Node::IfStmt { condition, then_branch, else_branch, .. } => {
+            writeln!(output, "{}IfStmt", indent).unwrap();
+            write_labeled(output, "condition", condition, depth + 1);
+            write_labeled(output, "then", then_branch, depth + 1);
+            if let Some(else_branch) = else_branch {
+                write_labeled(output, "else", else_branch, depth + 1);
+            }
+        }
+        Node::WhileStmt { condition, body, .. } => {
+            writeln!(output, "{}WhileStmt", indent).unwrap();
+            write_labeled(output, "condition", condition, depth + 1);
+            write_labeled(output, "body", body, depth + 1);
+        }
+        Node::ForStmt { init, condition, increment, body, .. } => {
+            writeln!(output, "{}ForStmt", indent).unwrap();
+            if let Some(init) = init {
+                write_labeled(output, "init", init, depth + 1);
+            }
+            if let Some(condition) = condition {
+                write_labeled(output, "condition", condition, depth + 1);
+            }
+            if let Some(increment) = increment {
+                write_labeled(output, "increment", increment, depth + 1);
+            }
+            write_labeled(output, "body", body, depth + 1);
+        }
+        Node::BlockStmt(statements, _) => {
+            writeln!(output, "{}BlockStmt", indent).unwrap();
+            for statement in statements {
+                write_node(output, statement, depth + 1);
+            }
+        }
+        Node::LabelStmt(name, statement, _) => {
+            writeln!(output, "{}LabelStmt {}", indent, name).unwrap();
+            write_node(output, statement, depth + 1);
+        }
+        Node::GotoStmt(name, _) => writeln!(output, "{}GotoStmt {}", indent, name).unwrap(),
+        Node::BreakStmt(_) => writeln!(output, "{}BreakStmt", indent).unwrap(),
+        Node::ContinueStmt(_) => writeln!(output, "{}ContinueStmt", indent).unwrap(),
+        Node::VarDecl { name, type_, storage, initializer, .. } => {
+            match storage {
+                StorageClass::Auto => writeln!(output, "{}VarDecl {}: {}", indent, name, type_).unwrap(),
+                _ => writeln!(output, "{}VarDecl {}: {} {}", indent, name, storage, type_).unwrap(),
+            }
+            if let Some(initializer) = initializer {
+                write_node(output, initializer, depth + 1);
+            }
+        }
+        Node::FunctionDecl { name, return_type, params, body, .. } => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(name, type_)| if name == "..." { name.clone() } else { format!("{}: {}", name, type_) })
+                .collect();
+            writeln!(output, "{}FunctionDecl {}({}) -> {}", indent, name, params.join(", "), return_type).unwrap();
+            if let Some(body) = body {
+                write_node(output, body, depth + 1);
+            }
+        }
+        Node::Program(declarations) => {
+            writeln!(output, "{}Program", indent).unwrap();
+            for declaration in declarations {
+                write_node(output, declaration, depth + 1);
+            }
+        }
+    }
+}
+
+/// Write a child under a label naming its role, e.g. the condition of an if
+fn write_labeled(output: &mut String, label: &str, node: &Node, depth: usize) {
+    writeln!(output, "{}{}:", "  ".repeat(depth), label).unwrap();
+    write_node(output, node, depth + 1);
+}
+
+/// Render an AST as a Graphviz DOT graph, for `dot -Tpng`. Each node is a box
+/// labeled with its kind and any name, value or operator, with an edge to each
+/// child labeled by the child's role, e.g. the condition of an if.
+pub fn ast_dot(node: &Node) -> String {
+    let mut output = String::from("digraph AST {\n    node [shape=box];\n");
+    node_dot(&mut output, node, &mut 0);
+    output.push_str("}\n");
+    output
+}
+
+/// Write a node and its children as DOT statements, numbering nodes in the order
+/// they are written, and return the node's number
+fn node_dot(output: &mut String, node: &Node, next_id: &mut usize) -> usize {
+    let (label, children): (String, Vec<(&str, &Node)>) = match node {
+        Node::IntLiteral(value, _) => (format!("IntLiteral {}", value), vec![]),
+        Node::UIntLiteral(value, _) => (format!("UIntLiteral {}", value), vec![]),
+        Node::LongLiteral(value, _) => (format!("LongLiteral {}", value), vec![]),
+        Node::ULongLiteral(value, _) => (format!("ULongLiteral {}", value), vec![]),
+        Node::FloatLiteral(value, _) => (format!("FloatLiteral {:?}", value), vec![]),
+        Node::CharLiteral(value, _) => (format!("CharLiteral {:?}", value), vec![]),
+        Node::StringLiteral(value, _) => (format!("StringLiteral {:?}", value), vec![]),
+        Node::Identifier(name, _) => (format!("Identifier {}", name), vec![]),
+        Node::BinaryExpr { op, left, right, .. } => {
+            (format!("BinaryExpr {}", op), vec![("left", left.as_ref()), ("right", right.as_ref())])
+        }
+        Node::UnaryExpr { op, expr, .. } => (format!("UnaryExpr {}", op), vec![("expr", expr.as_ref())]),
+        Node::FunctionCall { name, args, .. } => {
+            (format!("FunctionCall {}", name), args.iter().map(|arg| ("arg", arg)).collect())
+        }
+        Node::IndirectCall { callee, args, .. } => {
+            let mut children = vec![("callee", callee.as_ref())];
+            children.extend(args.iter().map(|arg| ("arg", arg)));
+            ("IndirectCall".to_string(), children)
+        }
+        Node::CommaExpr { left, right, .. } => {
+            ("CommaExpr".to_string(), vec![("left", left.as_ref()), ("right", right.as_ref())])
+        }
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => (
+            "ConditionalExpr".to_string(),
+            vec![("condition", condition.as_ref()), ("then", then_expr.as_ref()), ("else", else_expr.as_ref())],
+        ),
+        Node::MemberAccess { expr, member, .. } => (format!("MemberAccess .{}", member), vec![("expr", expr.as_ref())]),
+        Node::SizeofExpr(expr, _) => ("SizeofExpr".to_string(), vec![("expr", expr.as_ref())]),
+        Node::SizeofType(type_, _) => (format!("SizeofType {}", type_), vec![]),
+        Node::Cast { type_, expr, .. } => (format!("Cast {}", type_), vec![("expr", expr.as_ref())]),
+        Node::VaStart(list, last, _) => (format!("VaStart {}", last), vec![("list", list.as_ref())]),
+        Node::VaArg(list, type_, _) => (format!("VaArg {}", type_), vec![("list", list.as_ref())]),
+        Node::VaEnd(list, _) => ("VaEnd".to_string(), vec![("list", list.as_ref())]),
+        Node::ExpressionStmt(expr) => ("ExpressionStmt".to_string(), vec![("expr", expr.as_ref())]),
+        Node::EmptyStmt(_) => ("EmptyStmt".to_string(), vec![]),
+        Node::ReturnStmt(value, _) => ("ReturnStmt".to_string(), value.iter().map(|value| ("value", value.as_ref())).collect()),
+        Node::IfStmt { condition, then_branch, else_branch, .. } => {
+            let mut children = vec![("condition", condition.as_ref()), ("then", then_branch.as_ref())];
+            children.extend(else_branch.iter().map(|else_branch| ("else", else_branch.as_ref())));
+            ("IfStmt".to_string(), children)
+        }
+        Node::WhileStmt { condition, body, .. } => {
+            ("WhileStmt".to_string(), vec![("condition", condition.as_ref()), ("body", body.as_ref())])
+        }
+        Node::ForStmt { init, condition, increment, body, .. } => {
+            let mut children = Vec::new();
+            children.extend(init.iter().map(|init| ("init", init.as_ref())));
+            children.extend(condition.iter().map(|condition| ("condition", condition.as_ref())));
+            children.extend(increment.iter().map(|increment| ("increment", increment.as_ref())));
+            children.push(("body", body.as_ref()));
+            ("ForStmt".to_string(), children)
+        }
+        Node::BlockStmt(statements, _) => {
+            ("BlockStmt".to_string(), statements.iter().map(|statement| ("statement", statement)).collect())
+        }
+        Node::LabelStmt(name, statement, _) => (format!("LabelStmt {}", name), vec![("statement", statement.as_ref())]),
+        Node::GotoStmt(name, _) => (format!("GotoStmt {}", name), vec![]),
+        Node::BreakStmt(_) => ("BreakStmt".to_string(), vec![]),
+        Node::ContinueStmt(_) => ("ContinueStmt".to_string(), vec![]),
+        Node::VarDecl { name, type_, storage, initializer, .. } => {
+            let label = match storage {
+                StorageClass::Auto => format!("VarDecl {}: {}", name, type_),
+                _ => format!("VarDecl {}: {} {}", name, storage, type_),
+            };
+            (label, initializer.iter().map(|initializer| ("initializer", initializer.as_ref())).collect())
+        }
+        Node::FunctionDecl { name, return_type, params, body, .. } => {
+            let params: Vec<String> = params
+                .iter()
+                .map(|(name, type_)| if name == "..." { name.clone() } else { format!("{}: {}", name, type_) })
+                .collect();
+            let label = format!("FunctionDecl {}({}) -> {}", name, params.join(", "), return_type);
+            (label, body.iter().map(|body| ("body", body.as_ref())).collect())
+        }
+        Node::Program(declarations) => {
+            ("Program".to_string(), declarations.iter().map(|declaration| ("declaration", declaration)).collect())
+        }
+    };
+
+    let id = *next_id;
+    *next_id += 1;
+    writeln!(output, "    n{} [label={}];", id, string_dot(&label)).unwrap();
+    for (role, child) in children {
+        let child_id = node_dot(output, child, next_id);
+        writeln!(output, "    n{} -> n{} [label={}];", id, child_id, string_dot(role)).unwrap();
+    }
+    id
+}
+
+/// Quote a string as a DOT identifier, escaping quotes and backslashes so a string
+/// literal's escapes are shown rather than interpreted
+fn string_dot(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render an AST as JSON for editors and other tools. Every node is an object with
+/// its `kind`, its `location` (null for the program) and its fields by name; types
+/// are objects with a `kind` too, and operators are their C spelling.
+pub fn ast_json(node: &Node) -> String {
+    let mut output = node_json(node);
+    output.push('\n');
+    output
+}
+
+/// Serialize a node and its children
+fn node_json(node: &Node) -> String {
+    let (kind, fields): (&str, Vec<(&str, String)>) = match node {
+        Node::IntLiteral(value, _) => ("IntLiteral", vec![("value", value.to_string())]),
+        Node::UIntLiteral(value, _) => ("UIntLiteral", vec![("value", value.to_string())]),
+        Node::LongLiteral(value, _) => ("LongLiteral", vec![("value", value.to_string())]),
+        Node::ULongLiteral(value, _) => ("ULongLiteral", vec![("value", value.to_string())]),
+        Node::FloatLiteral(value, _) => ("FloatLiteral", vec![("value", float_json(*value))]),
+        Node::CharLiteral(value, _) => ("CharLiteral", vec![("value", string_json(&value.to_string()))]),
+        Node::StringLiteral(value, _) => ("StringLiteral", vec![("value", string_json(value))]),
+        Node::Identifier(name, _) => ("Identifier", vec![("name", string_json(name))]),
+        Node::BinaryExpr { op, left, right, .. } => (
+            "BinaryExpr",
+            vec![("op", string_json(&op.to_string())), ("left", node_json(left)), ("right", node_json(right))],
+        ),
+        Node::UnaryExpr { op, expr, .. } => {
+            ("UnaryExpr", vec![("op", string_json(&op.to_string())), ("expr", node_json(expr))])
+        }
+        Node::FunctionCall { name, args, .. } => {
+            ("FunctionCall", vec![("name", string_json(name)), ("args", nodes_json(args))])
+        }
+        Node::IndirectCall { callee, args, .. } => {
+            ("IndirectCall", vec![("callee", node_json(callee)), ("args", nodes_json(args))])
+        }
+        Node::CommaExpr { left, right, .. } => {
+            ("CommaExpr", vec![("left", node_json(left)), ("right", node_json(right))])
+        }
+        Node::ConditionalExpr { condition, then_expr, else_expr, .. } => (
+            "ConditionalExpr",
+            vec![("condition", node_json(condition)), ("then", node_json(then_expr)), ("else", node_json(else_expr))],
+        ),
+        Node::MemberAccess { expr, member, .. } => {
+            ("MemberAccess", vec![("expr", node_json(expr)), ("member", string_json(member))])
+        }
+        Node::SizeofExpr(expr, _) => ("SizeofExpr", vec![("expr", node_json(expr))]),
+        Node::SizeofType(type_, _) => ("SizeofType", vec![("type", type_json(type_))]),
+        Node::Cast { type_, expr, .. } => ("Cast", vec![("type", type_json(type_)), ("expr", node_json(expr))]),
+        Node::VaStart(list, last, _) => ("VaStart", vec![("list", node_json(list)), ("last", string_json(last))]),
+        Node::VaArg(list, type_, _) => ("VaArg", vec![("list", node_json(list)), ("type", type_json(type_))]),
+        Node::VaEnd(list, _) => ("VaEnd", vec![("list", node_json(list))]),
+        Node::ExpressionStmt(expr) => ("ExpressionStmt", vec![("expr", node_json(expr))]),
+        Node::EmptyStmt(_) => ("EmptyStmt", vec![]),
+        Node::ReturnStmt(value, _) => ("ReturnStmt", vec![("value", optional_node_json(value.as_deref()))]),
+        Node::IfStmt { condition, then_branch, else_branch, .. } => (
+            "IfStmt",
+            vec![
+                ("condition", node_json(condition)),
+                ("then", node_json(then_branch)),
+                ("else", optional_node_json(else_branch.as_deref())),
+            ],
+        ),
+        Node::WhileStmt { condition, body, .. } => {
+            ("WhileStmt", vec![("condition", node_json(condition)), ("body", node_json(body))])
+        }
+        Node::ForStmt { init, condition, increment, body, .. } => (
+            "ForStmt",
+            vec![
+                ("init", optional_node_json(init.as_deref())),
+                ("condition", optional_node_json(condition.as_deref())),
+                ("increment", optional_node_json(increment.as_deref())),
+                ("body", node_json(body)),
+            ],
+        ),
+        Node::BlockStmt(statements, _) => ("BlockStmt", vec![("statements", nodes_json(statements))]),
+        Node::LabelStmt(name, statement, _) => {
+            ("LabelStmt", vec![("name", string_json(name)), ("statement", node_json(statement))])
+        }
+        Node::GotoStmt(name, _) => ("GotoStmt", vec![("name", string_json(name))]),
+        Node::BreakStmt(_) => ("BreakStmt", vec![]),
+        Node::ContinueStmt(_) => ("ContinueStmt", vec![]),
+        Node::VarDecl { name, type_, storage, initializer, .. } => (
+            "VarDecl",
+            vec![
+                ("name", string_json(name)),
+                ("type", type_json(type_)),
+                ("storage", string_json(&storage.to_string())),
+                ("initializer", optional_node_json(initializer.as_deref())),
+            ],
+        ),
+        Node::FunctionDecl { name, return_type, params, body, .. } => {
+            // The "..." of a variadic function is a flag rather than a parameter
+            let variadic = params.iter().any(|(name, _)| name == "...");
+            let params: Vec<String> = params
+                .iter()
+                .filter(|(name, _)| name != "...")
+                .map(|(name, type_)| object_json(&[("name", string_json(name)), ("type", type_json(type_))]))
+                .collect();
+            (
+                "FunctionDecl",
+                vec![
+                    ("name", string_json(name)),
+                    ("return_type", type_json(return_type)),
+                    ("params", format!("[{}]", params.join(", "))),
+                    ("variadic", variadic.to_string()),
+                    ("body", optional_node_json(body.as_deref())),
+                ],
+            )
+        }
+        Node::Program(declarations) => ("Program", vec![("declarations", nodes_json(declarations))]),
+    };
+
+    let location = match node.location() {
+        Some(location) => location_json(location),
+        None => "null".to_string(),
+    };
+    let mut object = vec![("kind", string_json(kind)), ("location", location)];
+    object.extend(fields);
+    object_json(&object)
+}
+
+/// Serialize a list of nodes as an array
+fn nodes_json(nodes: &[Node]) -> String {
+    let nodes: Vec<String> = nodes.iter().map(node_json).collect();
+    format!("[{}]", nodes.join(", "))
+}
+
+/// Serialize a child that may be absent, such as an else branch, as null when it is
+fn optional_node_json(node: Option<&Node>) -> String {
+    node.map_or_else(|| "null".to_string(), node_json)
+}
+
+/// Serialize a source location
+fn location_json(location: &Location) -> String {
+    object_json(&[
+        ("file", string_json(&location.file)),
+        ("line", location.line.to_string()),
+        ("column", location.column.to_string()),
+    ])
+}
+
+/// Serialize a type. Basic types are just their C name, e.g. `{"kind": "unsigned int"}`;
+/// derived types also hold the types they are made from.
+fn type_json(type_: &Type) -> String {
+    match type_ {
+        Type::Pointer(inner) => object_json(&[("kind", string_json("pointer")), ("to", type_json(inner))]),
+        Type::Array(element, size) => object_json(&[
+            ("kind", string_json("array")),
+            ("element", type_json(element)),
+            ("size", size.map_or_else(|| "null".to_string(), |size| size.to_string())),
+        ]),
+        Type::Function(return_type, params, is_variadic) => {
+            let params: Vec<String> = params.iter().map(type_json).collect();
+            object_json(&[
+                ("kind", string_json("function")),
+                ("return_type", type_json(return_type)),
+                ("params", format!("[{}]", params.join(", "))),
+                ("variadic", is_variadic.to_string()),
+            ])
+        }
+        Type::Struct(name, members) | Type::Union(name, members) => {
+            let kind = if matches!(type_, Type::Struct(_, _)) { "struct" } else { "union" };
+            let members: Vec<String> = members
+                .iter()
+                .map(|(name, type_)| object_json(&[("name", string_json(name)), ("type", type_json(type_))]))
+                .collect();
+            object_json(&[
+                ("kind", string_json(kind)),
+                ("name", string_json(name)),
+                ("members", format!("[{}]", members.join(", "))),
+            ])
+        }
+        Type::Const(inner) => object_json(&[("kind", string_json("const")), ("type", type_json(inner))]),
+        Type::Volatile(inner) => object_json(&[("kind", string_json("volatile")), ("type", type_json(inner))]),
+        basic => object_json(&[("kind", string_json(&basic.to_string()))]),
+    }
+}
+
+/// Serialize fields, already serialized themselves, as an object
+fn object_json(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields.iter().map(|(name, value)| format!("{}: {}", string_json(name), value)).collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+/// Quote a string, escaping what JSON requires
+fn string_json(value: &str) -> String {
+    let mut output = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            c if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Write a float so it reads back as the same number; JSON has no infinity, so a
+/// literal too large for a double is null
+fn float_json(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:?}", value)
+    } else {
+        "null".to_string()
+    }
+}
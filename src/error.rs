@@ -1,8 +1,11 @@
+use std::fmt;
+
 use thiserror::Error;
 
 use crate::ast::Location;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum CompilerError {
     #[error("Lexical error at {location}: {message}")]
     LexicalError { location: Location, message: String },
@@ -16,14 +19,30 @@ pub enum CompilerError {
     #[error("Semantic error at {location}: {message}")]
     SemanticError { location: Location, message: String },
 
-    #[error("Code generation error: {message}")]
-    CodeGenError { message: String },
+    #[error("Code generation error{}: {message}", location.as_ref().map(|l| format!(" at {}", l)).unwrap_or_default())]
+    CodeGenError { location: Option<Location>, message: String },
 
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
     #[error("Preprocessor error at {location}: {message}")]
     PreprocessorError { location: Location, message: String },
+
+    #[error("{count} warning(s) treated as errors")]
+    WarningsAsErrors { count: usize },
+}
+
+/// A problem that does not stop compilation, unless warnings are treated as errors
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Warning at {}: {}", self.location, self.message)
+    }
 }
 
 pub type Result<T> = std::result::Result<T, CompilerError>;
@@ -60,9 +79,11 @@ pub fn semantic_error(location: &Location, message: impl Into<String>) -> Compil
     }
 }
 
-/// Helper function to create a code generation error
-pub fn codegen_error(message: impl Into<String>) -> CompilerError {
+/// Helper function to create a code generation error, at the construct it is
+/// about when there is one
+pub fn codegen_error(location: Option<&Location>, message: impl Into<String>) -> CompilerError {
     CompilerError::CodeGenError {
+        location: location.cloned(),
         message: message.into(),
     }
 }
@@ -74,3 +95,11 @@ pub fn preprocessor_error(location: &Location, message: impl Into<String>) -> Co
         message: message.into(),
     }
 }
+
+/// Helper function to create a warning
+pub fn warning(location: &Location, message: impl Into<String>) -> Warning {
+    Warning {
+        location: location.clone(),
+        message: message.into(),
+    }
+}
@@ -0,0 +1,65 @@
+//! Calls to library functions whose result is known at compile time are replaced
+//! with that result. Each intrinsic is a library function name and a rule that folds
+//! a call from its arguments, when they allow it.
+
+use crate::ast::{literal_bytes, Location, Node};
+
+/// Folds a call to a library function from its arguments, or leaves it as a call
+type Rule = fn(&[Node], &Location) -> Option<Node>;
+
+/// The library functions that are folded, and how
+const INTRINSICS: &[(&str, Rule)] = &[("strlen", fold_strlen)];
+
+/// `strlen` of a string literal is the number of bytes before its first NUL.
+/// The length is an unsigned long constant, the type `strlen` returns, so the
+/// arithmetic around it is the same whether the call is folded or not.
+fn fold_strlen(args: &[Node], location: &Location) -> Option<Node> {
+    match args {
+        [Node::StringLiteral(value, _)] => {
+            let length = literal_bytes(value).take_while(|&b| b != 0).count();
+            Some(Node::ULongLiteral(length as u64, location.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Fold the intrinsic calls of a whole program. A function the program defines
+/// itself is its own, so calls to it are left alone.
+pub fn fold_program(program: Node) -> Node {
+    let defined: Vec<&str> = match &program {
+        Node::Program(declarations) => declarations
+            .iter()
+            .filter_map(|declaration| match declaration {
+                Node::FunctionDecl { name, body: Some(_), .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let rules: Vec<(&str, Rule)> = INTRINSICS
+        .iter()
+        .filter(|(name, _)| !defined.contains(name))
+        .copied()
+        .collect();
+
+    fold(program, &rules)
+}
+
+/// Recursively replace intrinsic calls with their result
+fn fold(node: Node, rules: &[(&str, Rule)]) -> Node {
+    // A call under sizeof is left alone, as folding it would change its type
+    if matches!(node, Node::SizeofExpr(_, _)) {
+        return node;
+    }
+
+    match node.map_children(|child| fold(child, rules)) {
+        Node::FunctionCall { name, args, location } => {
+            let folded = rules
+                .iter()
+                .find(|(intrinsic, _)| *intrinsic == name)
+                .and_then(|(_, rule)| rule(&args, &location));
+            folded.unwrap_or(Node::FunctionCall { name, args, location })
+        }
+        other => other,
+    }
+}
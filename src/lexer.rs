@@ -1,16 +1,19 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::iter::Peekable;
+use std::num::IntErrorKind;
 use std::str::Chars;
 use lazy_static::lazy_static;
 
 use crate::ast::Location;
-use crate::error::{lexical_error, Result};
+use crate::error::{lexical_error, warning, Result, Warning};
 
 /// Represents a token in the C language
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenKind {
     // Keywords
     Auto,
+    Bool,
     Break,
     Case,
     Char,
@@ -46,6 +49,11 @@ pub enum TokenKind {
     // Identifiers and literals
     Identifier(String),
     IntLiteral(i64),
+    UIntLiteral(u64),
+    /// An integer literal with an `l` or `L` suffix, which is at least a long
+    LongLiteral(i64),
+    ULongLiteral(u64),
+    FloatLiteral(f64),
     CharLiteral(char),
     StringLiteral(String),
 
@@ -114,6 +122,8 @@ pub struct Token {
     pub location: Location,
     pub filename: String,
     pub at_bol: bool,  // Beginning of line
+    /// Number of source characters the token spans
+    pub length: usize,
 }
 
 impl Token {
@@ -123,6 +133,7 @@ impl Token {
             location: location.clone(),
             filename: location.file.clone(),
             at_bol: false,
+            length: 0,
         }
     }
 
@@ -130,11 +141,122 @@ impl Token {
         self.at_bol = at_bol;
         self
     }
+
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+}
+
+/// Format the digits of an integer literal with the prefix of its radix
+fn number_text(digits: &str, radix: u32) -> String {
+    match radix {
+        16 => format!("0x{}", digits),
+        2 => format!("0b{}", digits),
+        _ => digits.to_string(),
+    }
+}
+
+/// Write a character the way it would appear inside a quoted literal
+fn write_escaped(f: &mut fmt::Formatter<'_>, c: char, quote: char) -> fmt::Result {
+    match c {
+        '\n' => write!(f, "\\n"),
+        '\t' => write!(f, "\\t"),
+        '\r' => write!(f, "\\r"),
+        '\\' => write!(f, "\\\\"),
+        c if c == quote => write!(f, "\\{}", c),
+        c if (c as u32) < 0x20 || (c as u32) >= 0x7f => write!(f, "\\{:03o}", c as u32 & 0xff),
+        c => write!(f, "{}", c),
+    }
+}
+
+impl fmt::Display for TokenKind {
+    /// Write the token as it would be spelled in source code
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            TokenKind::Identifier(name) => return write!(f, "{}", name),
+            TokenKind::IntLiteral(value) => return write!(f, "{}", value),
+            TokenKind::UIntLiteral(value) => return write!(f, "{}u", value),
+            TokenKind::LongLiteral(value) => return write!(f, "{}L", value),
+            TokenKind::ULongLiteral(value) => return write!(f, "{}UL", value),
+            // Debug formatting always keeps a '.' or exponent, so it reads back as a float
+            TokenKind::FloatLiteral(value) => return write!(f, "{:?}", value),
+            TokenKind::CharLiteral(c) => {
+                write!(f, "'")?;
+                write_escaped(f, *c, '\'')?;
+                return write!(f, "'");
+            }
+            TokenKind::StringLiteral(s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    write_escaped(f, c, '"')?;
+                }
+                return write!(f, "\"");
+            }
+            TokenKind::Plus => "+",
+            TokenKind::Minus => "-",
+            TokenKind::Asterisk => "*",
+            TokenKind::Slash => "/",
+            TokenKind::Percent => "%",
+            TokenKind::Increment => "++",
+            TokenKind::Decrement => "--",
+            TokenKind::Equal => "==",
+            TokenKind::NotEqual => "!=",
+            TokenKind::LessThan => "<",
+            TokenKind::LessThanEqual => "<=",
+            TokenKind::GreaterThan => ">",
+            TokenKind::GreaterThanEqual => ">=",
+            TokenKind::LogicalAnd => "&&",
+            TokenKind::LogicalOr => "||",
+            TokenKind::LogicalNot => "!",
+            TokenKind::BitwiseAnd => "&",
+            TokenKind::BitwiseOr => "|",
+            TokenKind::BitwiseXor => "^",
+            TokenKind::BitwiseNot => "~",
+            TokenKind::ShiftLeft => "<<",
+            TokenKind::ShiftRight => ">>",
+            TokenKind::Assign => "=",
+            TokenKind::PlusAssign => "+=",
+            TokenKind::MinusAssign => "-=",
+            TokenKind::MultiplyAssign => "*=",
+            TokenKind::DivideAssign => "/=",
+            TokenKind::ModuloAssign => "%=",
+            TokenKind::AndAssign => "&=",
+            TokenKind::OrAssign => "|=",
+            TokenKind::XorAssign => "^=",
+            TokenKind::ShiftLeftAssign => "<<=",
+            TokenKind::ShiftRightAssign => ">>=",
+            TokenKind::LeftParen => "(",
+            TokenKind::RightParen => ")",
+            TokenKind::LeftBrace => "{",
+            TokenKind::RightBrace => "}",
+            TokenKind::LeftBracket => "[",
+            TokenKind::RightBracket => "]",
+            TokenKind::Semicolon => ";",
+            TokenKind::Comma => ",",
+            TokenKind::Dot => ".",
+            TokenKind::Arrow => "->",
+            TokenKind::Colon => ":",
+            TokenKind::QuestionMark => "?",
+            TokenKind::Ellipsis => "...",
+            TokenKind::Hash => "#",
+            TokenKind::HashHash => "##",
+            TokenKind::Eof => "",
+            keyword => KEYWORDS
+                .iter()
+                .find(|(_, kind)| *kind == keyword)
+                .map(|(text, _)| *text)
+                .unwrap_or(""),
+        };
+
+        write!(f, "{}", text)
+    }
 }
 
 lazy_static! {
     static ref KEYWORDS: HashMap<&'static str, TokenKind> = {
         let mut m = HashMap::new();
+        m.insert("_Bool", TokenKind::Bool);
         m.insert("auto", TokenKind::Auto);
         m.insert("break", TokenKind::Break);
         m.insert("case", TokenKind::Case);
@@ -178,6 +300,12 @@ pub struct Lexer<'a> {
     line: usize,
     column: usize,
     current_char: Option<char>,
+    at_bol: bool,
+    /// Number of characters consumed so far, not counting line splices
+    offset: usize,
+    /// Whether a `/*` inside a block comment is a warning rather than an error
+    nested_comment_warnings: bool,
+    warnings: Vec<Warning>,
 }
 
 impl<'a> Lexer<'a> {
@@ -185,22 +313,35 @@ impl<'a> Lexer<'a> {
         let mut chars = input.chars().peekable();
         let current_char = chars.next();
 
-        Self {
+        let mut lexer = Self {
             input: chars,
             filename,
             line: 1,
             column: 1,
             current_char,
-        }
+            at_bol: true,
+            offset: 0,
+            nested_comment_warnings: false,
+            warnings: Vec::new(),
+        };
+        lexer.splice_lines();
+        lexer
+    }
+
+    /// Report a `/*` inside a block comment as a warning rather than an error
+    pub fn with_nested_comment_warnings(mut self, nested_comment_warnings: bool) -> Self {
+        self.nested_comment_warnings = nested_comment_warnings;
+        self
+    }
+
+    /// Take the warnings reported while tokenizing
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
     }
 
     /// Get the current location in the source code
     fn location(&self) -> Location {
-        Location {
-            file: self.filename.clone(),
-            line: self.line,
-            column: self.column,
-        }
+        Location::point(self.filename.clone(), self.line, self.column)
     }
 
     /// Advance to the next character
@@ -209,12 +350,36 @@ impl<'a> Lexer<'a> {
             if c == '\n' {
                 self.line += 1;
                 self.column = 1;
+                self.at_bol = true;
             } else {
                 self.column += 1;
             }
         }
 
         self.current_char = self.input.next();
+        self.offset += 1;
+        self.splice_lines();
+    }
+
+    /// Skip any backslash-newline sequences at the current character, joining the
+    /// physical lines into one logical line while still counting them
+    fn splice_lines(&mut self) {
+        while self.current_char == Some('\\') {
+            let mut ahead = self.input.clone();
+            let newline_length = match (ahead.next(), ahead.next()) {
+                (Some('\n'), _) => 1,
+                (Some('\r'), Some('\n')) => 2,
+                _ => return,
+            };
+
+            for _ in 0..newline_length {
+                self.input.next();
+            }
+
+            self.line += 1;
+            self.column = 1;
+            self.current_char = self.input.next();
+        }
     }
 
     /// Peek at the next character without advancing
@@ -232,6 +397,18 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Skip whitespace and comments up to the start of the next token
+    fn skip_whitespace_and_comments(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.current_char == Some('/') && matches!(self.peek(), Some('/' | '*')) {
+                self.skip_comment()?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
     /// Skip a comment (either // or /* */)
     fn skip_comment(&mut self) -> Result<()> {
         if self.current_char == Some('/') {
@@ -262,6 +439,15 @@ impl<'a> Lexer<'a> {
                             self.advance(); // Skip the '/'
                             return Ok(());
                         }
+                        if c == '/' && self.peek() == Some('*') {
+                            // Block comments do not nest, so this is most likely a
+                            // comment that was never closed, or one commented out
+                            let message = "'/*' within block comment; block comments do not nest";
+                            if !self.nested_comment_warnings {
+                                return Err(lexical_error(&self.location(), message));
+                            }
+                            self.warnings.push(warning(&self.location(), message));
+                        }
                         self.advance();
                     }
 
@@ -276,8 +462,7 @@ impl<'a> Lexer<'a> {
     }
 
     /// Tokenize an identifier or keyword
-    fn identifier(&mut self) -> Result<Token> {
-        let start_location = self.location();
+    fn identifier(&mut self) -> Result<TokenKind> {
         let mut identifier = String::new();
 
         while let Some(c) = self.current_char {
@@ -295,13 +480,13 @@ impl<'a> Lexer<'a> {
             TokenKind::Identifier(identifier)
         };
 
-        Ok(Token::new(token_kind, start_location))
+        Ok(token_kind)
     }
 
     /// Tokenize a number literal
-    fn number(&mut self) -> Result<Token> {
-        let start_location = self.location();
+    fn number(&mut self, start_location: &Location) -> Result<TokenKind> {
         let mut number = String::new();
+        let mut radix = 10;
 
         // Check for hexadecimal, octal, or decimal
         if self.current_char == Some('0') {
@@ -311,44 +496,23 @@ impl<'a> Lexer<'a> {
             if let Some(c) = self.current_char {
                 if c == 'x' || c == 'X' {
                     // Hexadecimal
-                    number.push(c);
+                    radix = 16;
+                    number.clear();
+                    self.advance();
+                } else if c == 'b' || c == 'B' {
+                    // Binary, a GCC extension
+                    radix = 2;
+                    number.clear();
                     self.advance();
-
-                    while let Some(c) = self.current_char {
-                        if c.is_digit(16) {
-                            number.push(c);
-                            self.advance();
-                        } else {
-                            break;
-                        }
-                    }
-
-                    let value = i64::from_str_radix(&number[2..], 16)
-                        .map_err(|_| lexical_error(&start_location, "Invalid hexadecimal literal"))?;
-
-                    return Ok(Token::new(TokenKind::IntLiteral(value), start_location));
                 } else if c.is_digit(8) {
                     // Octal
-                    while let Some(c) = self.current_char {
-                        if c.is_digit(8) {
-                            number.push(c);
-                            self.advance();
-                        } else {
-                            break;
-                        }
-                    }
-
-                    let value = i64::from_str_radix(&number, 8)
-                        .map_err(|_| lexical_error(&start_location, "Invalid octal literal"))?;
-
-                    return Ok(Token::new(TokenKind::IntLiteral(value), start_location));
+                    radix = 8;
                 }
             }
         }
 
-        // Decimal
         while let Some(c) = self.current_char {
-            if c.is_digit(10) {
+            if c.is_digit(radix) {
                 number.push(c);
                 self.advance();
             } else {
@@ -356,63 +520,215 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        let value = number
-            .parse::<i64>()
-            .map_err(|_| lexical_error(&start_location, "Invalid integer literal"))?;
+        // A fraction or exponent makes it a floating literal
+        if radix != 16 && matches!(self.current_char, Some('.' | 'e' | 'E')) {
+            return self.float_literal(number, start_location);
+        }
+
+        // Integer suffixes: 'u' makes the literal unsigned, and 'l' makes it at
+        // least a long
+        let mut is_unsigned = false;
+        let mut is_long = false;
+        while let Some(c) = self.current_char {
+            match c {
+                'u' | 'U' if !is_unsigned => is_unsigned = true,
+                'l' | 'L' => is_long = true,
+                _ => break,
+            }
+            self.advance();
+        }
+
+        let message = match radix {
+            16 => "Invalid hexadecimal literal",
+            8 => "Invalid octal literal",
+            2 => "Invalid binary literal",
+            _ => "Invalid integer literal",
+        };
 
-        Ok(Token::new(TokenKind::IntLiteral(value), start_location))
+        let value = u64::from_str_radix(&number, radix).map_err(|e| match e.kind() {
+            IntErrorKind::PosOverflow => lexical_error(
+                start_location,
+                format!("Integer literal {} is too large for any integer type", number_text(&number, radix)),
+            ),
+            _ => lexical_error(start_location, message),
+        })?;
+
+        // A literal takes the first type that can hold its value: int, then long
+        // for a decimal literal, while hexadecimal, octal and binary ones may also
+        // be unsigned int or unsigned long. Decimal values too large for long are
+        // unsigned long, as in GCC. A long literal starts from long instead.
+        let is_decimal = radix == 10;
+        let is_unsigned = is_unsigned
+            || value > i64::MAX as u64
+            || (!is_decimal && !is_long && value > i32::MAX as u64 && value <= u32::MAX as u64);
+
+        if is_long && is_unsigned {
+            Ok(TokenKind::ULongLiteral(value))
+        } else if is_long {
+            Ok(TokenKind::LongLiteral(value as i64))
+        } else if is_unsigned {
+            Ok(TokenKind::UIntLiteral(value))
+        } else {
+            Ok(TokenKind::IntLiteral(value as i64))
+        }
     }
 
-    /// Tokenize a character literal
-    fn char_literal(&mut self) -> Result<Token> {
-        let start_location = self.location();
+    /// Tokenize the rest of a decimal floating literal, after its integer digits.
+    /// The value always has type double; an 'f' suffix only rounds it to float precision.
+    fn float_literal(&mut self, mut number: String, start_location: &Location) -> Result<TokenKind> {
+        if self.current_char == Some('.') {
+            number.push('.');
+            self.advance();
+            while let Some(c) = self.current_char.filter(|c| c.is_ascii_digit()) {
+                number.push(c);
+                self.advance();
+            }
+        }
+
+        if let Some(e @ ('e' | 'E')) = self.current_char {
+            number.push(e);
+            self.advance();
+            if let Some(sign @ ('+' | '-')) = self.current_char {
+                number.push(sign);
+                self.advance();
+            }
+            while let Some(c) = self.current_char.filter(|c| c.is_ascii_digit()) {
+                number.push(c);
+                self.advance();
+            }
+        }
+
+        let mut value: f64 = number
+            .parse()
+            .map_err(|_| lexical_error(start_location, "Invalid floating literal"))?;
+
+        match self.current_char {
+            Some('f' | 'F') => {
+                value = value as f32 as f64;
+                self.advance();
+            }
+            Some('l' | 'L') => self.advance(),
+            _ => {}
+        }
+
+        Ok(TokenKind::FloatLiteral(value))
+    }
+
+    /// Tokenize a character literal. A constant with several characters, such as
+    /// 'AB', is an int holding their bytes, the first one in the highest byte.
+    fn char_literal(&mut self, start_location: &Location) -> Result<TokenKind> {
         self.advance(); // Skip the opening quote
 
+        let mut chars = Vec::new();
+        loop {
+            let c = match self.current_char {
+                Some('\'') => break,
+                Some('\\') => self.escape_sequence("character")?,
+                Some('\n') => return Err(lexical_error(
+                    &self.location(),
+                    "Expected closing quote for character literal",
+                )),
+                None => return Err(lexical_error(
+                    &self.location(),
+                    "Unterminated character literal",
+                )),
+                Some(c) => {
+                    self.advance(); // Skip the character
+                    c
+                }
+            };
+            chars.push(c);
+        }
+
+        self.advance(); // Skip the closing quote
+
+        match chars.as_slice() {
+            [] => Err(lexical_error(start_location, "Empty character literal")),
+            [c] => Ok(TokenKind::CharLiteral(*c)),
+            _ => {
+                // Like GCC, keep the low 32 bits when there are more than 4 characters
+                let value = chars.iter().fold(0i32, |value, c| value.wrapping_shl(8) | (*c as u8 as i32));
+                Ok(TokenKind::IntLiteral(value as i64))
+            }
+        }
+    }
+
+    /// Decode an escape sequence starting at the backslash, consuming all of it.
+    /// Numeric escapes produce the byte they denote.
+    fn escape_sequence(&mut self, literal: &str) -> Result<char> {
+        let start_location = self.location();
+        self.advance(); // Skip the backslash
+
         let c = match self.current_char {
-            Some('\\') => {
-                self.advance();
-                match self.current_char {
-                    Some('n') => '\n',
-                    Some('t') => '\t',
-                    Some('r') => '\r',
-                    Some('\\') => '\\',
-                    Some('\'') => '\'',
-                    Some('\"') => '\"',
-                    Some('0') => '\0',
-                    Some(c) => return Err(lexical_error(
-                        &self.location(),
-                        format!("Unknown escape sequence: \\{}", c),
-                    )),
-                    None => return Err(lexical_error(
-                        &self.location(),
-                        "Unterminated character literal",
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('a') => '\x07',
+            Some('b') => '\x08',
+            Some('f') => '\x0c',
+            Some('v') => '\x0b',
+            Some('\\') => '\\',
+            Some('\'') => '\'',
+            Some('\"') => '\"',
+            Some('?') => '?',
+            Some('x') => {
+                self.advance(); // Skip the 'x'
+
+                let mut digits = String::new();
+                while let Some(c) = self.current_char.filter(|c| c.is_ascii_hexdigit()) {
+                    digits.push(c);
+                    self.advance();
+                }
+
+                if digits.is_empty() {
+                    return Err(lexical_error(&start_location, "Expected hex digits after \\x"));
+                }
+
+                return match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) => Ok(byte as char),
+                    Err(_) => Err(lexical_error(
+                        &start_location,
+                        format!("Hex escape sequence \\x{} is out of range", digits),
                     )),
+                };
+            }
+            Some(c) if c.is_digit(8) => {
+                // Up to three octal digits
+                let mut value = 0u32;
+                let mut count = 0;
+                while let Some(digit) = self.current_char.and_then(|c| c.to_digit(8)) {
+                    if count == 3 {
+                        break;
+                    }
+                    value = value * 8 + digit;
+                    count += 1;
+                    self.advance();
                 }
+
+                return match u8::try_from(value) {
+                    Ok(byte) => Ok(byte as char),
+                    Err(_) => Err(lexical_error(
+                        &start_location,
+                        "Octal escape sequence is out of range",
+                    )),
+                };
             }
-            Some(c) => c,
+            Some(c) => return Err(lexical_error(
+                &self.location(),
+                format!("Unknown escape sequence: \\{}", c),
+            )),
             None => return Err(lexical_error(
                 &self.location(),
-                "Unterminated character literal",
+                format!("Unterminated {} literal", literal),
             )),
         };
 
-        self.advance(); // Skip the character
-
-        if self.current_char != Some('\'') {
-            return Err(lexical_error(
-                &self.location(),
-                "Expected closing quote for character literal",
-            ));
-        }
-
-        self.advance(); // Skip the closing quote
-
-        Ok(Token::new(TokenKind::CharLiteral(c), start_location))
+        self.advance(); // Skip the escaped character
+        Ok(c)
     }
 
     /// Tokenize a string literal
-    fn string_literal(&mut self) -> Result<Token> {
-        let start_location = self.location();
+    fn string_literal(&mut self, start_location: &Location) -> Result<TokenKind> {
         self.advance(); // Skip the opening quote
 
         let mut string = String::new();
@@ -420,101 +736,85 @@ impl<'a> Lexer<'a> {
         while let Some(c) = self.current_char {
             if c == '"' {
                 self.advance(); // Skip the closing quote
-                return Ok(Token::new(TokenKind::StringLiteral(string), start_location));
+                return Ok(TokenKind::StringLiteral(string));
             } else if c == '\\' {
-                self.advance();
-                match self.current_char {
-                    Some('n') => string.push('\n'),
-                    Some('t') => string.push('\t'),
-                    Some('r') => string.push('\r'),
-                    Some('\\') => string.push('\\'),
-                    Some('\'') => string.push('\''),
-                    Some('\"') => string.push('\"'),
-                    Some('0') => string.push('\0'),
-                    Some(c) => return Err(lexical_error(
-                        &self.location(),
-                        format!("Unknown escape sequence: \\{}", c),
-                    )),
-                    None => return Err(lexical_error(
-                        &self.location(),
-                        "Unterminated string literal",
-                    )),
-                }
+                string.push(self.escape_sequence("string")?);
             } else {
-                string.push(c);
+                // A character outside ASCII stands for the bytes of its UTF-8 encoding,
+                // kept one to a character like the bytes numeric escapes produce
+                let mut buffer = [0; 4];
+                string.extend(c.encode_utf8(&mut buffer).bytes().map(char::from));
+                self.advance();
             }
-            self.advance();
         }
 
         Err(lexical_error(
-            &start_location,
+            start_location,
             "Unterminated string literal",
         ))
     }
 
     /// Get the next token from the input
     pub fn next_token(&mut self) -> Result<Token> {
-        self.skip_whitespace();
+        self.skip_whitespace_and_comments()?;
+
+        // Every token is located at its first character, and spans the characters
+        // consumed while scanning it
+        let mut location = self.location();
+        let start = self.offset;
+        let kind = self.scan_token(&location)?;
+        location.end_line = self.line;
+        location.end_column = self.column;
+        Ok(Token::new(kind, location).with_length(self.offset - start))
+    }
 
+    /// Scan the token starting at the current character, which is not whitespace
+    /// or a comment, returning its kind
+    fn scan_token(&mut self, location: &Location) -> Result<TokenKind> {
         if let Some(c) = self.current_char {
-            let location = self.location();
-
             match c {
                 // End of file
                 '\0' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::Eof, location))
+                    Ok(TokenKind::Eof)
                 }
 
                 // Identifiers and keywords
                 c if c.is_alphabetic() || c == '_' => self.identifier(),
 
-                // Number literals
-                c if c.is_digit(10) => self.number(),
+                // Number literals, including floating literals that start with '.'
+                c if c.is_ascii_digit() => self.number(location),
+                '.' if self.peek().is_some_and(|c| c.is_ascii_digit()) => self.number(location),
 
                 // Character literals
-                '\'' => self.char_literal(),
+                '\'' => self.char_literal(location),
 
                 // String literals
-                '"' => self.string_literal(),
+                '"' => self.string_literal(location),
 
-                // Comments and operators
+                // Operators and punctuation
                 '/' => {
-                    if let Some(next) = self.peek() {
-                        match next {
-                            '/' | '*' => {
-                                self.skip_comment()?;
-                                self.next_token()
-                            }
-                            '=' => {
-                                self.advance();
-                                self.advance();
-                                Ok(Token::new(TokenKind::DivideAssign, location))
-                            }
-                            _ => {
-                                self.advance();
-                                Ok(Token::new(TokenKind::Slash, location))
-                            }
+                    self.advance();
+                    match self.current_char {
+                        Some('=') => {
+                            self.advance();
+                            Ok(TokenKind::DivideAssign)
                         }
-                    } else {
-                        self.advance();
-                        Ok(Token::new(TokenKind::Slash, location))
+                        _ => Ok(TokenKind::Slash),
                     }
                 }
-
-                // Operators and punctuation
                 '+' => {
                     self.advance();
                     match self.current_char {
                         Some('+') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::Increment, location))
+                            Ok(TokenKind::Increment)
                         }
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::PlusAssign, location))
+                            Ok(TokenKind::PlusAssign)
                         }
-                        _ => Ok(Token::new(TokenKind::Plus, location)),
+                        _ => Ok(TokenKind::Plus),
                     }
                 }
                 '-' => {
@@ -522,17 +822,17 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('-') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::Decrement, location))
+                            Ok(TokenKind::Decrement)
                         }
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::MinusAssign, location))
+                            Ok(TokenKind::MinusAssign)
                         }
                         Some('>') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::Arrow, location))
+                            Ok(TokenKind::Arrow)
                         }
-                        _ => Ok(Token::new(TokenKind::Minus, location)),
+                        _ => Ok(TokenKind::Minus),
                     }
                 }
                 '*' => {
@@ -540,9 +840,9 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::MultiplyAssign, location))
+                            Ok(TokenKind::MultiplyAssign)
                         }
-                        _ => Ok(Token::new(TokenKind::Asterisk, location)),
+                        _ => Ok(TokenKind::Asterisk),
                     }
                 }
                 '%' => {
@@ -550,9 +850,9 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::ModuloAssign, location))
+                            Ok(TokenKind::ModuloAssign)
                         }
-                        _ => Ok(Token::new(TokenKind::Percent, location)),
+                        _ => Ok(TokenKind::Percent),
                     }
                 }
                 '=' => {
@@ -560,9 +860,9 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::Equal, location))
+                            Ok(TokenKind::Equal)
                         }
-                        _ => Ok(Token::new(TokenKind::Assign, location)),
+                        _ => Ok(TokenKind::Assign),
                     }
                 }
                 '!' => {
@@ -570,9 +870,9 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::NotEqual, location))
+                            Ok(TokenKind::NotEqual)
                         }
-                        _ => Ok(Token::new(TokenKind::LogicalNot, location)),
+                        _ => Ok(TokenKind::LogicalNot),
                     }
                 }
                 '<' => {
@@ -580,19 +880,19 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::LessThanEqual, location))
+                            Ok(TokenKind::LessThanEqual)
                         }
                         Some('<') => {
                             self.advance();
                             match self.current_char {
                                 Some('=') => {
                                     self.advance();
-                                    Ok(Token::new(TokenKind::ShiftLeftAssign, location))
+                                    Ok(TokenKind::ShiftLeftAssign)
                                 }
-                                _ => Ok(Token::new(TokenKind::ShiftLeft, location)),
+                                _ => Ok(TokenKind::ShiftLeft),
                             }
                         }
-                        _ => Ok(Token::new(TokenKind::LessThan, location)),
+                        _ => Ok(TokenKind::LessThan),
                     }
                 }
                 '>' => {
@@ -600,19 +900,19 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::GreaterThanEqual, location))
+                            Ok(TokenKind::GreaterThanEqual)
                         }
                         Some('>') => {
                             self.advance();
                             match self.current_char {
                                 Some('=') => {
                                     self.advance();
-                                    Ok(Token::new(TokenKind::ShiftRightAssign, location))
+                                    Ok(TokenKind::ShiftRightAssign)
                                 }
-                                _ => Ok(Token::new(TokenKind::ShiftRight, location)),
+                                _ => Ok(TokenKind::ShiftRight),
                             }
                         }
-                        _ => Ok(Token::new(TokenKind::GreaterThan, location)),
+                        _ => Ok(TokenKind::GreaterThan),
                     }
                 }
                 '&' => {
@@ -620,13 +920,13 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('&') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::LogicalAnd, location))
+                            Ok(TokenKind::LogicalAnd)
                         }
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::AndAssign, location))
+                            Ok(TokenKind::AndAssign)
                         }
-                        _ => Ok(Token::new(TokenKind::BitwiseAnd, location)),
+                        _ => Ok(TokenKind::BitwiseAnd),
                     }
                 }
                 '|' => {
@@ -634,13 +934,13 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('|') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::LogicalOr, location))
+                            Ok(TokenKind::LogicalOr)
                         }
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::OrAssign, location))
+                            Ok(TokenKind::OrAssign)
                         }
-                        _ => Ok(Token::new(TokenKind::BitwiseOr, location)),
+                        _ => Ok(TokenKind::BitwiseOr),
                     }
                 }
                 '^' => {
@@ -648,74 +948,74 @@ impl<'a> Lexer<'a> {
                     match self.current_char {
                         Some('=') => {
                             self.advance();
-                            Ok(Token::new(TokenKind::XorAssign, location))
+                            Ok(TokenKind::XorAssign)
                         }
-                        _ => Ok(Token::new(TokenKind::BitwiseXor, location)),
+                        _ => Ok(TokenKind::BitwiseXor),
                     }
                 }
                 '~' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::BitwiseNot, location))
+                    Ok(TokenKind::BitwiseNot)
                 }
 
                 // Punctuation
                 '(' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::LeftParen, location))
+                    Ok(TokenKind::LeftParen)
                 }
                 ')' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::RightParen, location))
+                    Ok(TokenKind::RightParen)
                 }
                 '{' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::LeftBrace, location))
+                    Ok(TokenKind::LeftBrace)
                 }
                 '}' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::RightBrace, location))
+                    Ok(TokenKind::RightBrace)
                 }
                 '[' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::LeftBracket, location))
+                    Ok(TokenKind::LeftBracket)
                 }
                 ']' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::RightBracket, location))
+                    Ok(TokenKind::RightBracket)
                 }
                 ';' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::Semicolon, location))
+                    Ok(TokenKind::Semicolon)
                 }
                 ',' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::Comma, location))
+                    Ok(TokenKind::Comma)
                 }
                 '.' => {
                     self.advance();
                     if self.current_char == Some('.') && self.peek() == Some('.') {
                         self.advance();
                         self.advance();
-                        Ok(Token::new(TokenKind::Ellipsis, location))
+                        Ok(TokenKind::Ellipsis)
                     } else {
-                        Ok(Token::new(TokenKind::Dot, location))
+                        Ok(TokenKind::Dot)
                     }
                 }
                 ':' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::Colon, location))
+                    Ok(TokenKind::Colon)
                 }
                 '?' => {
                     self.advance();
-                    Ok(Token::new(TokenKind::QuestionMark, location))
+                    Ok(TokenKind::QuestionMark)
                 }
                 '#' => {
                     self.advance();
                     if self.current_char == Some('#') {
                         self.advance();
-                        Ok(Token::new(TokenKind::HashHash, location))
+                        Ok(TokenKind::HashHash)
                     } else {
-                        Ok(Token::new(TokenKind::Hash, location))
+                        Ok(TokenKind::Hash)
                     }
                 }
 
@@ -723,14 +1023,14 @@ impl<'a> Lexer<'a> {
                 _ => {
                     self.advance();
                     Err(lexical_error(
-                        &location,
+                        location,
                         format!("Unexpected character: {}", c),
                     ))
                 }
             }
         } else {
             // End of file
-            Ok(Token::new(TokenKind::Eof, self.location()))
+            Ok(TokenKind::Eof)
         }
     }
 
@@ -739,7 +1039,10 @@ impl<'a> Lexer<'a> {
         let mut tokens = Vec::new();
 
         loop {
+            // Remember whether a newline came before the token, for preprocessor directives
             let token = self.next_token()?;
+            let at_bol = std::mem::replace(&mut self.at_bol, false);
+            let token = token.with_at_bol(at_bol);
             let is_eof = token.kind == TokenKind::Eof;
             tokens.push(token);
 
@@ -0,0 +1,194 @@
+//! FerriCC compiles C source to x86-64 assembly.
+//!
+//! The pipeline runs the lexer, preprocessor and parser (`parse`), then intrinsic
+//! and constant folding, type checking and code generation (`generate`). `compile`
+//! runs both. Warnings are collected rather than printed, for the caller to report.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+mod asm;
+pub mod assembler;
+pub mod ast;
+mod codegen;
+mod constfold;
+mod cse;
+pub mod dump;
+pub mod error;
+pub mod intrinsics;
+mod lexer;
+mod parser;
+mod peephole;
+mod preprocessor;
+pub mod target;
+mod typechecker;
+
+use crate::ast::{Location, Node};
+use crate::codegen::CodeGenerator;
+use crate::error::{semantic_error, CompilerError, Result, Warning};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::preprocessor::Preprocessor;
+use crate::target::Target;
+use crate::typechecker::TypeChecker;
+
+/// Settings that control what the compiler reports while it works, and the
+/// platform it generates code for
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// 0 reports nothing, 1 reports each phase, 2 also dumps the tokens
+    pub verbosity: u8,
+    /// Dump the tokens regardless of the verbosity
+    pub dump_tokens: bool,
+    /// Dump the names the type checker defines, with their types and scope depths
+    pub dump_symbols: bool,
+    /// Directories searched for `#include <...>` files, in order, before the
+    /// standard headers bundled with the compiler
+    pub include_paths: Vec<PathBuf>,
+    /// The platform to generate code for, the host by default
+    pub target: Target,
+    /// Emit `.file` and `.loc` directives so debuggers can map instructions to
+    /// source lines
+    pub debug_info: bool,
+    /// Leave calls to library functions whose result is known at compile time,
+    /// such as `strlen` of a string literal, as calls
+    pub no_builtin: bool,
+    /// Fail the compilation when any warning is reported
+    pub warnings_as_errors: bool,
+    /// Report a `/*` inside a block comment as a warning rather than an error
+    pub nested_comment_warnings: bool,
+    /// Optimization level: 0 generates code for every statement, 1 leaves out
+    /// code that can never run
+    pub opt_level: u8,
+    /// Abort the program when signed `+`, `-` or `*` overflows, rather than let
+    /// the result wrap around
+    pub trap_overflow: bool,
+}
+
+/// Fail when warnings are treated as errors and any have been reported
+fn check_warnings(warnings: &[Warning], options: &Options) -> Result<()> {
+    if options.warnings_as_errors && !warnings.is_empty() {
+        return Err(CompilerError::WarningsAsErrors { count: warnings.len() });
+    }
+    Ok(())
+}
+
+/// Tokenize, preprocess and parse C source into a syntax tree, reporting
+/// progress to `log` and adding warnings to `warnings`
+pub fn parse(
+    source: &str,
+    file_name: &str,
+    options: &Options,
+    log: &mut dyn Write,
+    warnings: &mut Vec<Warning>,
+) -> Result<Node> {
+    // Tokenize
+    let mut lexer = Lexer::new(source, file_name.to_string()).with_nested_comment_warnings(options.nested_comment_warnings);
+    let tokens = lexer.tokenize()?;
+    warnings.extend(lexer.take_warnings());
+
+    if options.verbosity >= 1 {
+        writeln!(log, "Tokenization complete: {} tokens", tokens.len())?;
+    }
+
+    // Print tokens for debugging
+    if options.verbosity >= 2 || options.dump_tokens {
+        write!(log, "{}", dump::tokens(&tokens))?;
+    }
+
+    // Preprocess
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.set_nested_comment_warnings(options.nested_comment_warnings);
+
+    // The bundled standard headers are searched after the user's include paths
+    for path in &options.include_paths {
+        preprocessor.add_include_path(path);
+    }
+
+    let preprocessed_tokens = preprocessor.preprocess(tokens)?;
+    warnings.extend(preprocessor.take_warnings());
+    check_warnings(warnings, options)?;
+
+    if options.verbosity >= 1 {
+        writeln!(log, "Preprocessing complete: {} tokens", preprocessed_tokens.len())?;
+    }
+
+    // Parse
+    let mut parser = Parser::new(&preprocessed_tokens);
+    let ast = parser.parse_program()?;
+
+    if options.verbosity >= 1 {
+        writeln!(log, "Parsing complete")?;
+    }
+
+    Ok(ast)
+}
+
+/// Check a parsed program and generate its assembly, reporting progress to `log`
+/// and adding warnings to `warnings`
+pub fn generate(ast: Node, options: &Options, log: &mut dyn Write, warnings: &mut Vec<Warning>) -> Result<String> {
+    // Replace library calls with known results, then fold constant expressions
+    let ast = if options.no_builtin { ast } else { intrinsics::fold_program(ast) };
+    let ast = constfold::fold_program(ast)?;
+
+    // Type check
+    let mut typechecker = TypeChecker::new();
+    let checked = typechecker.check_program(&ast);
+    warnings.extend(typechecker.take_warnings());
+
+    // The names defined up to a type error help to explain it, so they are dumped
+    // either way
+    if options.dump_symbols {
+        write!(log, "{}", dump::symbols(typechecker.symbols()))?;
+    }
+    checked?;
+    check_warnings(warnings, options)?;
+
+    if options.verbosity >= 1 {
+        writeln!(log, "Type checking complete")?;
+    }
+
+    // Generate code, reusing the types computed by the type checker
+    let mut codegen = CodeGenerator::new_with_target(options.target)
+        .with_types(typechecker.take_types())
+        .with_debug_info(options.debug_info)
+        .with_opt_level(options.opt_level)
+        .with_trap_overflow(options.trap_overflow);
+    let assembly = codegen.generate(&ast)?;
+
+    if options.verbosity >= 1 {
+        writeln!(log, "Code generation complete")?;
+    }
+
+    Ok(assembly)
+}
+
+/// Check that a program defines the `main` function an executable starts in.
+/// A program without it still compiles to assembly, but cannot be linked.
+pub fn check_main(program: &Node, file_name: &str) -> Result<()> {
+    let defines_main = matches!(
+        program,
+        Node::Program(declarations) if declarations.iter().any(|decl| matches!(
+            decl,
+            Node::FunctionDecl { name, body: Some(_), .. } if name == "main"
+        ))
+    );
+    if defines_main {
+        return Ok(());
+    }
+
+    Err(semantic_error(&Location::point(file_name, 1, 1), "no 'main' function found"))
+}
+
+/// Compile C source to assembly, reporting progress to `log` and adding warnings
+/// to `warnings`
+pub fn compile(
+    source: &str,
+    file_name: &str,
+    options: &Options,
+    log: &mut dyn Write,
+    warnings: &mut Vec<Warning>,
+) -> Result<String> {
+    let ast = parse(source, file_name, options, log, warnings)?;
+    generate(ast, options, log, warnings)
+}
@@ -1,84 +1,190 @@
 use std::fs;
+use std::io;
 use std::path::PathBuf;
 use std::process::Command;
-use std::env;
-
-mod ast;
-mod codegen;
-mod error;
-mod lexer;
-mod parser;
-mod preprocessor;
-mod typechecker;
-
-use crate::codegen::CodeGenerator;
-use crate::error::Result;
-use crate::lexer::Lexer;
-use crate::parser::Parser as CParser;
-use crate::preprocessor::Preprocessor;
-use crate::typechecker::TypeChecker;
 
-fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
+use clap::{Parser, ValueEnum};
 
-    if args.len() < 2 {
-        println!("Usage: {} <input.c> [output]", args[0]);
-        return Ok(());
-    }
+use ferricc::error::{self, Result, Warning};
+use ferricc::target::Target;
+use ferricc::{dump, Options};
 
-    let input = PathBuf::from(&args[1]);
-    let output = if args.len() >= 3 {
-        PathBuf::from(&args[2])
-    } else {
-        let stem = input.file_stem().unwrap().to_string_lossy().to_string();
-        PathBuf::from(stem)
-    };
+/// Command-line arguments
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// C source file to compile
+    input: PathBuf,
 
-    println!("Compiling {} to {}", input.display(), output.display());
+    /// Name of the generated assembly and executable, defaulting to the input's stem
+    output: Option<PathBuf>,
 
-    // Read input file
-    let source = fs::read_to_string(&input).map_err(|e| {
-        error::CompilerError::IoError(e)
-    })?;
+    /// Report each compilation phase; repeat (-vv) to also print the tokens
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Print the tokens produced by the lexer
+    #[arg(long)]
+    dump_tokens: bool,
+
+    /// Print each name the type checker defines, with its type and the depth of
+    /// its scope
+    #[arg(long)]
+    dump_symbols: bool,
+
+    /// Add a directory to search for #include <...> files, before the bundled headers,
+    /// and for #include "..." files not found beside the including file
+    #[arg(short = 'I', value_name = "DIR")]
+    include: Vec<PathBuf>,
+
+    /// Print the parsed syntax tree and stop
+    #[arg(long)]
+    dump_ast: bool,
+
+    /// Print the parsed syntax tree as JSON and stop
+    #[arg(long)]
+    emit_ast_json: bool,
 
-    // Tokenize
-    let mut lexer = Lexer::new(&source, input.to_string_lossy().to_string());
-    let tokens = lexer.tokenize()?;
+    /// Print the parsed syntax tree as a Graphviz DOT graph and stop
+    #[arg(long)]
+    emit_dot: bool,
 
-    println!("Tokenization complete: {} tokens", tokens.len());
+    /// Emit line information so debuggers can step through the C source
+    #[arg(short = 'g')]
+    debug: bool,
+
+    /// Platform to generate code for and link against, the host by default
+    #[arg(long, value_enum, default_value_t = Target::host())]
+    target: Target,
+
+    /// Call library functions even when their result is known at compile time,
+    /// such as strlen of a string literal
+    #[arg(long)]
+    no_builtin: bool,
+
+    /// Optimization level: -O1 (or -O) leaves out code that can never run and
+    /// computes subexpressions repeated within a statement once
+    #[arg(
+        short = 'O',
+        value_name = "LEVEL",
+        default_value_t = 0,
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u8).range(0..=1)
+    )]
+    opt_level: u8,
+
+    /// Warning option; -Werror makes any warning fail the compilation, and
+    /// -Wno-error=comment only warns about a /* inside a block comment
+    #[arg(short = 'W', value_enum, value_name = "OPTION")]
+    warning_options: Vec<WarningOption>,
+
+    /// Code generation option; -ftrap-overflow aborts the program when signed
+    /// arithmetic overflows, which otherwise wraps around
+    #[arg(short = 'f', value_enum, value_name = "OPTION")]
+    codegen_options: Vec<CodegenOption>,
+
+    /// Check the generated assembly with the assembler, reporting invalid assembly
+    /// at the C line that generated it, and stop without linking
+    #[arg(long)]
+    check_asm: bool,
+
+    /// Run the executable once it is linked, exiting with its exit code
+    #[arg(long)]
+    run: bool,
+
+    /// Arguments for the program run with --run, given after --
+    #[arg(last = true, value_name = "ARGS")]
+    program_args: Vec<String>,
+}
+
+/// Options given with -f
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CodegenOption {
+    /// Abort when signed +, - or * overflows
+    TrapOverflow,
+}
+
+/// Options given with -W
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum WarningOption {
+    /// Treat warnings as errors
+    Error,
+    /// Report a /* inside a block comment as a warning rather than an error
+    #[value(name = "no-error=comment")]
+    NoErrorComment,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
 
-    // Print tokens for debugging
-    for token in &tokens {
-        println!("Token: {:?} at {}:{}", token.kind, token.location.line, token.location.column);
+    // Warnings are printed once compilation is over, whether or not it succeeded
+    let mut warnings = Vec::new();
+    let result = run(args, &mut warnings);
+    for warning in &warnings {
+        eprintln!("{}", warning);
     }
 
-    // Preprocess
-    let mut preprocessor = Preprocessor::new();
+    // A program run with --run decides the exit code
+    match result? {
+        0 => Ok(()),
+        code => std::process::exit(code),
+    }
+}
 
-    // Add include paths
-    preprocessor.add_include_path("include");
+/// Compile, assemble and link the input file, and run it if asked to. Returns the
+/// code to exit with: the program's own when it was run, otherwise 0.
+fn run(args: Args, warnings: &mut Vec<Warning>) -> Result<i32> {
+    let input = args.input;
+    let output = match args.output {
+        Some(output) => output,
+        None => PathBuf::from(input.file_stem().unwrap()),
+    };
+    let options = Options {
+        verbosity: args.verbose,
+        dump_tokens: args.dump_tokens,
+        dump_symbols: args.dump_symbols,
+        include_paths: args.include,
+        target: args.target,
+        debug_info: args.debug,
+        no_builtin: args.no_builtin,
+        warnings_as_errors: args.warning_options.contains(&WarningOption::Error),
+        nested_comment_warnings: args.warning_options.contains(&WarningOption::NoErrorComment),
+        opt_level: args.opt_level,
+        trap_overflow: args.codegen_options.contains(&CodegenOption::TrapOverflow),
+    };
+    let mut log = io::stdout();
 
-    let preprocessed_tokens = preprocessor.preprocess(tokens)?;
+    if options.verbosity >= 1 {
+        println!("Compiling {} to {}", input.display(), output.display());
+    }
 
-    println!("Preprocessing complete: {} tokens", preprocessed_tokens.len());
+    // Read input file
+    let source = fs::read_to_string(&input).map_err(|e| {
+        error::CompilerError::IoError(e)
+    })?;
 
-    // Parse
-    let mut parser = CParser::new(&preprocessed_tokens);
-    let ast = parser.parse_program()?;
+    let ast = ferricc::parse(&source, &input.to_string_lossy(), &options, &mut log, warnings)?;
 
-    println!("Parsing complete");
+    if args.dump_ast {
+        print!("{}", dump::ast(&ast));
+        return Ok(0);
+    }
 
-    // Type check
-    let mut typechecker = TypeChecker::new();
-    typechecker.check_program(&ast)?;
+    if args.emit_ast_json {
+        print!("{}", dump::ast_json(&ast));
+        return Ok(0);
+    }
 
-    println!("Type checking complete");
+    if args.emit_dot {
+        print!("{}", dump::ast_dot(&ast));
+        return Ok(0);
+    }
 
-    // Generate code
-    let mut codegen = CodeGenerator::new();
-    let assembly = codegen.generate(&ast)?;
+    // Report a missing main here rather than as a linker error
+    ferricc::check_main(&ast, &input.to_string_lossy())?;
 
-    println!("Code generation complete");
+    let assembly = ferricc::generate(ast, &options, &mut log, warnings)?;
 
     // Create output directories if they don't exist
     let asm_dir = PathBuf::from("output/asm");
@@ -94,17 +200,25 @@ fn main() -> Result<()> {
     // Write assembly to file in the asm directory
     let asm_file = asm_dir.join(format!("{}.s", output.to_string_lossy()));
 
-    fs::write(&asm_file, assembly).map_err(|e| {
+    fs::write(&asm_file, &assembly).map_err(|e| {
         error::CompilerError::IoError(e)
     })?;
 
+    if args.check_asm {
+        ferricc::assembler::check(&assembly, options.target)?;
+        println!("Checked the assembly in {}", asm_file.display());
+        return Ok(0);
+    }
+
     // Assemble and link
-    println!("Assembling and linking");
+    if options.verbosity >= 1 {
+        println!("Assembling and linking");
+    }
 
     // Set the output executable path to be in the bin directory
-    let exe_file = bin_dir.join(format!("{}.exe", output.to_string_lossy()));
+    let exe_file = bin_dir.join(format!("{}{}", output.to_string_lossy(), options.target.executable_extension()));
 
-    let status = Command::new("gcc")
+    let status = Command::new(options.target.linker())
         .arg("-o")
         .arg(&exe_file)
         .arg(&asm_file)
@@ -115,13 +229,36 @@ fn main() -> Result<()> {
 
     if !status.success() {
         return Err(error::CompilerError::CodeGenError {
+            location: None,
             message: "Assembly or linking failed".to_string(),
         });
     }
 
-    println!("Compilation successful:");
-    println!("  Assembly: {}", asm_file.display());
-    println!("  Executable: {}", exe_file.display());
+    if options.verbosity >= 1 {
+        println!("Compilation successful:");
+        println!("  Assembly: {}", asm_file.display());
+        println!("  Executable: {}", exe_file.display());
+    } else if !args.run {
+        // The output of a program that is run is left to the program alone
+        println!("Compiled {} to {}", input.display(), exe_file.display());
+    }
+
+    if !args.run {
+        return Ok(0);
+    }
+
+    // The program shares this process's standard streams. Its executable is kept
+    // in output/bin like any other, and replaced by the next compilation.
+    if options.verbosity >= 1 {
+        println!("Running {}", exe_file.display());
+    }
+    let status = Command::new(&exe_file)
+        .args(&args.program_args)
+        .status()
+        .map_err(|e| {
+            error::CompilerError::IoError(e)
+        })?;
 
-    Ok(())
-}
\ No newline at end of file
+    // A program killed by a signal has no exit code of its own
+    Ok(status.code().unwrap_or(1))
+}
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
 use std::iter::Peekable;
 use std::slice::Iter;
 
-use crate::ast::{BinaryOp, Location, Node, Type, UnaryOp};
+use crate::ast::{BinaryOp, Location, Node, StorageClass, Type, UnaryOp};
+use crate::constfold;
 use crate::error::{syntax_error, Result};
 use crate::lexer::{Token, TokenKind};
 
@@ -9,6 +11,23 @@ use crate::lexer::{Token, TokenKind};
 pub struct Parser<'a> {
     tokens: Peekable<Iter<'a, Token>>,
     current: Option<&'a Token>,
+    /// Members of the structs and unions defined so far, by keyword and tag (such
+    /// as `struct Node`), so a later mention of the tag alone refers to them
+    tags: HashMap<String, Vec<(String, Type)>>,
+    /// The typedef names of each enclosing scope, outermost first. A name declared
+    /// as an ordinary identifier in an inner scope maps to None, hiding the typedef.
+    typedefs: Vec<HashMap<String, Option<Type>>>,
+}
+
+/// One step a declarator takes from the type it starts with toward the declared
+/// type, such as the `*` of `*name` making a pointer to it
+enum Derivation {
+    /// A pointer, and whether it is const and volatile
+    Pointer(bool, bool),
+    /// An array, with its size if given
+    Array(Option<usize>),
+    /// A function, with its parameters; a variadic one ends with one named "..."
+    Function(Vec<(String, Type)>),
 }
 
 impl<'a> Parser<'a> {
@@ -19,6 +38,8 @@ impl<'a> Parser<'a> {
         Self {
             tokens: iter,
             current,
+            tags: HashMap::new(),
+            typedefs: vec![HashMap::new()],
         }
     }
 
@@ -63,11 +84,34 @@ impl<'a> Parser<'a> {
                 format!("{}, found {:?}", message, token.kind),
             )),
             None => Err(syntax_error(
-                &Location {
-                    file: "unknown".to_string(),
-                    line: 0,
-                    column: 0,
-                },
+                &Location::point("unknown", 0, 0),
+                format!("{}, found end of file", message),
+            )),
+        }
+    }
+
+    /// Consume the current token if it is one of the given binary operators, and
+    /// return the operator with the location of its token
+    fn match_binary_op(&mut self, operators: &[(TokenKind, BinaryOp)]) -> Option<(BinaryOp, Location)> {
+        let token = self.current?;
+        let (_, op) = operators.iter().find(|(kind, _)| *kind == token.kind)?;
+        self.advance();
+        Some((op.clone(), token.location.clone()))
+    }
+
+    /// Consume an identifier and return its name and location, otherwise return an error
+    fn expect_identifier(&mut self, message: &str) -> Result<(String, Location)> {
+        match self.current {
+            Some(Token { kind: TokenKind::Identifier(name), location, .. }) => {
+                self.advance();
+                Ok((name.clone(), location.clone()))
+            }
+            Some(token) => Err(syntax_error(
+                &token.location,
+                format!("{}, found {:?}", message, token.kind),
+            )),
+            None => Err(syntax_error(
+                &Location::point("unknown", 0, 0),
                 format!("{}, found end of file", message),
             )),
         }
@@ -78,19 +122,55 @@ impl<'a> Parser<'a> {
         let mut declarations = Vec::new();
 
         while self.current.is_some() && !self.check(&TokenKind::Eof) {
-            declarations.push(self.parse_declaration()?);
+            declarations.extend(self.parse_declaration()?);
         }
 
         Ok(Node::Program(declarations))
     }
 
-    /// Parse a declaration
-    fn parse_declaration(&mut self) -> Result<Node> {
+    /// Parse a single expression that makes up all of the input, such as an `#if` condition
+    pub fn parse_constant_expression(&mut self) -> Result<Node> {
+        let expr = self.parse_expression()?;
+        self.expect(&TokenKind::Eof, "Expected end of expression")?;
+        Ok(expr)
+    }
+
+    /// Parse a declaration, which may declare several variables at once
+    fn parse_declaration(&mut self) -> Result<Vec<Node>> {
+        if self.match_token(&TokenKind::Typedef) {
+            self.parse_typedef()?;
+            return Ok(Vec::new());
+        }
+
         // Check for type specifiers
-        if self.check(&TokenKind::Int) || self.check(&TokenKind::Char) ||
-           self.check(&TokenKind::Void) || self.check(&TokenKind::Long) ||
-           self.check(&TokenKind::Struct) {
-            let type_ = self.parse_type()?;
+        if self.is_type_start() {
+            let storage = self.parse_storage_class()?;
+            let base_type = self.parse_base_type()?;
+            let type_ = self.parse_pointers(base_type.clone());
+
+            // A struct or union may be declared for its tag alone, with no variable
+            if base_type.is_record() && type_ == base_type && self.match_token(&TokenKind::Semicolon) {
+                return Ok(Vec::new());
+            }
+
+            // A function pointer or pointer to an array has its name inside parentheses
+            if self.at_parenthesized_declarator() {
+                let (name, mut inner) = self.parse_nested_declarator()?;
+                let Some((name, location)) = name else {
+                    return Err(syntax_error(&self.current.unwrap().location, "Expected variable name"));
+                };
+                let type_ = derive(type_, self.parse_declarator_suffixes()?);
+
+                // So does a function returning one, such as `(*row(int i))[4]`,
+                // whose parameters are inside the parentheses too
+                if let Some(Derivation::Function(_)) = inner.last() {
+                    let Some(Derivation::Function(params)) = inner.pop() else { unreachable!() };
+                    self.declare_identifier(&name);
+                    self.typedefs.push(HashMap::new());
+                    return Ok(vec![self.parse_function_body(name, derive(type_, inner), params, location)?]);
+                }
+                return self.parse_variable_declaration(name, storage, &base_type, derive(type_, inner), location);
+            }
 
             // Parse the identifier
             if let Some(token) = self.current {
@@ -99,11 +179,13 @@ impl<'a> Parser<'a> {
                     let location = token.location.clone();
                     self.advance(); // Consume the identifier
 
-                    // Check if it's a function declaration or a variable declaration
+                    // Check if it's a function declaration or a variable declaration.
+                    // Functions other than main are not exported, so a storage class
+                    // does not change how a function is emitted
                     if self.check(&TokenKind::LeftParen) {
-                        self.parse_function_declaration(name, type_, location)
+                        Ok(vec![self.parse_function_declaration(name, type_, location)?])
                     } else {
-                        self.parse_variable_declaration(name, type_, location)
+                        self.parse_variable_declaration(name, storage, &base_type, type_, location)
                     }
                 } else {
                     Err(syntax_error(
@@ -113,11 +195,7 @@ impl<'a> Parser<'a> {
                 }
             } else {
                 Err(syntax_error(
-                    &Location {
-                        file: "unknown".to_string(),
-                        line: 0,
-                        column: 0,
-                    },
+                    &Location::point("unknown", 0, 0),
                     "Unexpected end of file",
                 ))
             }
@@ -154,9 +232,9 @@ impl<'a> Parser<'a> {
 
                             // Check if it's a function declaration or a variable declaration
                             if self.check(&TokenKind::LeftParen) {
-                                self.parse_function_declaration(name, type_, location)
+                                Ok(vec![self.parse_function_declaration(name, type_, location)?])
                             } else {
-                                self.parse_variable_declaration(name, type_, location)
+                                self.parse_variable_declaration(name, StorageClass::Auto, &type_.clone(), type_, location)
                             }
                         } else {
                             Err(syntax_error(
@@ -166,11 +244,7 @@ impl<'a> Parser<'a> {
                         }
                     } else {
                         Err(syntax_error(
-                            &Location {
-                                file: "unknown".to_string(),
-                                line: 0,
-                                column: 0,
-                            },
+                            &Location::point("unknown", 0, 0),
                             "Unexpected end of file",
                         ))
                     }
@@ -188,63 +262,129 @@ impl<'a> Parser<'a> {
             }
         } else {
             Err(syntax_error(
-                &Location {
-                    file: "unknown".to_string(),
-                    line: 0,
-                    column: 0,
-                },
+                &Location::point("unknown", 0, 0),
                 "Expected declaration",
             ))
         }
     }
 
-    /// Parse a type
-    fn parse_type(&mut self) -> Result<Type> {
-        let base_type = if self.match_token(&TokenKind::Void) {
-            Type::Void
-        } else if self.match_token(&TokenKind::Char) {
-            Type::Char
-        } else if self.match_token(&TokenKind::Int) {
-            Type::Int
-        } else if self.match_token(&TokenKind::Long) {
-            Type::Long
-        } else if self.match_token(&TokenKind::Struct) {
-            // Parse struct type
-            let name = if let Some(token) = self.current {
-                if let TokenKind::Identifier(name) = &token.kind {
-                    self.advance();
-                    name.clone()
-                } else {
-                    "".to_string()
+    /// Parse the declarators of a typedef, after its keyword, and record each name as
+    /// standing for its type. A typedef declares no variable.
+    fn parse_typedef(&mut self) -> Result<()> {
+        let base_type = self.parse_base_type()?;
+
+        loop {
+            let type_ = self.parse_pointers(base_type.clone());
+            let (name, type_) = if self.at_parenthesized_declarator() {
+                match self.parse_parenthesized_declarator(type_)? {
+                    (Some((name, _)), type_) => (name, type_),
+                    (None, _) => {
+                        return Err(syntax_error(&self.current.unwrap().location, "Expected typedef name"));
+                    }
                 }
             } else {
-                "".to_string()
+                (self.expect_identifier("Expected typedef name")?.0, type_)
             };
+            let type_ = self.parse_array_suffix(type_)?;
 
-            // Parse struct body if present
-            let members = if self.match_token(&TokenKind::LeftBrace) {
-                let mut members = Vec::new();
+            self.typedefs.last_mut().unwrap().insert(name, Some(type_));
 
-                while !self.check(&TokenKind::RightBrace) && self.current.is_some() {
-                    let member_type = self.parse_type()?;
-                    let member_name = match &self.expect(&TokenKind::Identifier("".to_string()), "Expected member name")?.kind {
-                        TokenKind::Identifier(name) => name.clone(),
-                        _ => unreachable!(),
-                    };
+            if !self.match_token(&TokenKind::Comma) {
+                break;
+            }
+        }
 
-                    self.expect(&TokenKind::Semicolon, "Expected ';' after struct member")?;
+        self.expect(&TokenKind::Semicolon, "Expected ';' after typedef")?;
+        Ok(())
+    }
 
-                    members.push((member_name, member_type));
-                }
+    /// Look up the type a typedef name stands for in the innermost scope that
+    /// declares the name
+    fn typedef_type(&self, name: &str) -> Option<&Type> {
+        self.typedefs.iter().rev().find_map(|scope| scope.get(name)).and_then(|type_| type_.as_ref())
+    }
 
-                self.expect(&TokenKind::RightBrace, "Expected '}' after struct body")?;
+    /// Record an ordinary identifier declared in the current scope, which hides any
+    /// typedef of the same name from an outer scope
+    fn declare_identifier(&mut self, name: &str) {
+        if self.typedef_type(name).is_some() || self.typedefs.last().unwrap().contains_key(name) {
+            self.typedefs.last_mut().unwrap().insert(name.to_string(), None);
+        }
+    }
 
-                members
-            } else {
-                Vec::new()
+    /// Parse the storage class that may start a declaration, such as `static`
+    fn parse_storage_class(&mut self) -> Result<StorageClass> {
+        let mut storage = None;
+
+        while let Some(token) = self.current {
+            let class = match token.kind {
+                TokenKind::Static => StorageClass::Static,
+                TokenKind::Extern => StorageClass::Extern,
+                // `register` only hints that the variable is used often; it is
+                // stored like any other automatic variable
+                TokenKind::Register => StorageClass::Auto,
+                _ => break,
             };
 
+            if storage.is_some() {
+                return Err(syntax_error(&token.location, "Multiple storage classes in declaration"));
+            }
+            storage = Some(class);
+            self.advance();
+        }
+
+        Ok(storage.unwrap_or_default())
+    }
+
+    /// Parse any `const` and `volatile` qualifiers, returning which were present
+    fn parse_qualifiers(&mut self) -> (bool, bool) {
+        let (mut is_const, mut is_volatile) = (false, false);
+        loop {
+            if self.match_token(&TokenKind::Const) {
+                is_const = true;
+            } else if self.match_token(&TokenKind::Volatile) {
+                is_volatile = true;
+            } else {
+                return (is_const, is_volatile);
+            }
+        }
+    }
+
+    /// Parse a type
+    fn parse_type(&mut self) -> Result<Type> {
+        let base_type = self.parse_base_type()?;
+        Ok(self.parse_pointers(base_type))
+    }
+
+    /// Parse the qualifiers and specifiers of a type, without any pointer declarators
+    fn parse_base_type(&mut self) -> Result<Type> {
+        // Qualifiers may come before the type specifiers, as in `const int`
+        let (is_const, is_volatile) = self.parse_qualifiers();
+
+        let base_type = if self.match_token(&TokenKind::Void) {
+            Type::Void
+        } else if self.check(&TokenKind::Char) || self.check(&TokenKind::Int) ||
+                  self.check(&TokenKind::Long) || self.check(&TokenKind::Signed) ||
+                  self.check(&TokenKind::Unsigned) {
+            self.parse_integer_type()?
+        } else if self.match_token(&TokenKind::Bool) {
+            Type::Bool
+        } else if self.match_token(&TokenKind::Float) {
+            Type::Float
+        } else if self.match_token(&TokenKind::Double) {
+            Type::Double
+        } else if self.match_token(&TokenKind::Struct) {
+            let (name, members) = self.parse_record("struct")?;
             Type::Struct(name, members)
+        } else if self.match_token(&TokenKind::Union) {
+            let (name, members) = self.parse_record("union")?;
+            Type::Union(name, members)
+        } else if let Some(Token { kind: TokenKind::Identifier(name), .. }) = self.current {
+            let Some(type_) = self.typedef_type(name).cloned() else {
+                return Err(syntax_error(&self.current.unwrap().location, "Expected type specifier"));
+            };
+            self.advance();
+            type_
         } else {
             return Err(syntax_error(
                 &self.current.unwrap().location,
@@ -252,72 +392,371 @@ impl<'a> Parser<'a> {
             ));
         };
 
-        // Handle pointers
+        // ... or after them, as in `int const`
+        let (const_after, volatile_after) = self.parse_qualifiers();
+
+        Ok(base_type.qualified(is_const || const_after, is_volatile || volatile_after))
+    }
+
+    /// Parse the optional tag and member list of a struct or union, after its keyword
+    fn parse_record(&mut self, keyword: &str) -> Result<(String, Vec<(String, Type)>)> {
+        let name = if let Some(token) = self.current {
+            if let TokenKind::Identifier(name) = &token.kind {
+                self.advance();
+                name.clone()
+            } else {
+                "".to_string()
+            }
+        } else {
+            "".to_string()
+        };
+
+        // Parse the body if present
+        let members = if self.match_token(&TokenKind::LeftBrace) {
+            let mut members = Vec::new();
+
+            while !self.check(&TokenKind::RightBrace) && self.current.is_some() {
+                let member_type = self.parse_type()?;
+                let (member_name, member_type) = if self.at_parenthesized_declarator() {
+                    match self.parse_parenthesized_declarator(member_type)? {
+                        (Some((name, _)), member_type) => (name, member_type),
+                        (None, _) => {
+                            return Err(syntax_error(&self.current.unwrap().location, "Expected member name"));
+                        }
+                    }
+                } else {
+                    (self.expect_identifier("Expected member name")?.0, member_type)
+                };
+                let member_type = self.parse_array_suffix(member_type)?;
+
+                self.expect(&TokenKind::Semicolon, &format!("Expected ';' after {} member", keyword))?;
+
+                members.push((member_name, member_type));
+            }
+
+            self.expect(&TokenKind::RightBrace, &format!("Expected '}}' after {} body", keyword))?;
+
+            if !name.is_empty() {
+                self.tags.insert(format!("{} {}", keyword, name), members.clone());
+            }
+            members
+        } else {
+            // A tag without a body refers to the members it was defined with. Until
+            // then, as within its own body, it has none and is only pointed to.
+            self.tags.get(&format!("{} {}", keyword, name)).cloned().unwrap_or_default()
+        };
+
+        Ok((name, members))
+    }
+
+    /// Parse the pointer declarators that follow a base type;
+    /// a const or volatile after the `*` qualifies the pointer itself
+    fn parse_pointers(&mut self, base_type: Type) -> Type {
         let mut type_ = base_type;
+
         while self.match_token(&TokenKind::Asterisk) {
-            type_ = Type::Pointer(Box::new(type_));
+            let (is_const, is_volatile) = self.parse_qualifiers();
+            type_ = Type::Pointer(Box::new(type_)).qualified(is_const, is_volatile);
         }
 
-        Ok(type_)
+        type_
     }
 
-    /// Parse a variable declaration
-    fn parse_variable_declaration(&mut self, name: String, type_: Type, location: Location) -> Result<Node> {
-        let mut var_type = type_;
+    /// Check if the current token can start a declaration
+    fn is_type_start(&self) -> bool {
+        self.current.is_some_and(|token| self.is_type_name(token)) ||
+        self.check(&TokenKind::Static) || self.check(&TokenKind::Extern) ||
+        self.check(&TokenKind::Register) || self.check(&TokenKind::Typedef)
+    }
 
-        // Handle array declarations
-        if self.match_token(&TokenKind::LeftBracket) {
-            let size = if let Some(token) = self.current {
-                if let TokenKind::IntLiteral(size) = token.kind {
-                    self.advance();
-                    Some(size as usize)
-                } else {
-                    None
+    /// Check if a token can start a type name: a type keyword, or an identifier
+    /// declared by a typedef that is in scope
+    fn is_type_name(&self, token: &Token) -> bool {
+        match &token.kind {
+            TokenKind::Identifier(name) => self.typedef_type(name).is_some(),
+            kind => is_type_name_start(kind),
+        }
+    }
+
+    /// Parse a sequence of integer type specifiers such as `unsigned long int`
+    fn parse_integer_type(&mut self) -> Result<Type> {
+        let location = self.current.unwrap().location.clone();
+        let mut is_unsigned = None;
+        let mut is_char = false;
+        let mut long_count = 0;
+
+        loop {
+            if self.match_token(&TokenKind::Signed) {
+                if is_unsigned.is_some() {
+                    return Err(syntax_error(&location, "Duplicate signedness specifier"));
+                }
+                is_unsigned = Some(false);
+            } else if self.match_token(&TokenKind::Unsigned) {
+                if is_unsigned.is_some() {
+                    return Err(syntax_error(&location, "Duplicate signedness specifier"));
+                }
+                is_unsigned = Some(true);
+            } else if self.match_token(&TokenKind::Char) {
+                is_char = true;
+            } else if self.match_token(&TokenKind::Long) {
+                long_count += 1;
+            } else if !self.match_token(&TokenKind::Int) {
+                break;
+            }
+        }
+
+        if is_char && long_count > 0 || long_count > 2 {
+            return Err(syntax_error(&location, "Invalid combination of type specifiers"));
+        }
+
+        let is_unsigned = is_unsigned.unwrap_or(false);
+        Ok(match (is_char, long_count > 0, is_unsigned) {
+            (true, _, false) => Type::Char,
+            (true, _, true) => Type::UChar,
+            (false, true, false) => Type::Long,
+            (false, true, true) => Type::ULong,
+            (false, false, false) => Type::Int,
+            (false, false, true) => Type::UInt,
+        })
+    }
+
+    /// Parse a variable declaration
+    /// Parse the declarators of a variable declaration, starting after the first name.
+    /// Each further declarator applies its own pointers, array size and initializer
+    /// to the shared base type.
+    fn parse_variable_declaration(&mut self, name: String, storage: StorageClass, base_type: &Type, type_: Type, location: Location) -> Result<Vec<Node>> {
+        let mut declarations = vec![self.parse_declarator_suffix(name, storage, type_, location)?];
+
+        while self.match_token(&TokenKind::Comma) {
+            let type_ = self.parse_pointers(base_type.clone());
+            let (name, location, type_) = if self.at_parenthesized_declarator() {
+                match self.parse_parenthesized_declarator(type_)? {
+                    (Some((name, location)), type_) => (name, location, type_),
+                    (None, _) => {
+                        return Err(syntax_error(&self.current.unwrap().location, "Expected variable name"));
+                    }
                 }
             } else {
-                None
+                let (name, location) = self.expect_identifier("Expected variable name")?;
+                (name, location, type_)
             };
 
-            self.expect(&TokenKind::RightBracket, "Expected ']' after array size")?;
-
-            var_type = Type::Array(Box::new(var_type), size);
+            declarations.push(self.parse_declarator_suffix(name, storage, type_, location)?);
         }
 
+        self.expect(&TokenKind::Semicolon, "Expected ';' after variable declaration")?;
+
+        Ok(declarations)
+    }
+
+    /// Parse the array size and initializer that follow a declared variable's name
+    fn parse_declarator_suffix(&mut self, name: String, storage: StorageClass, type_: Type, location: Location) -> Result<Node> {
+        self.declare_identifier(&name);
+        let var_type = self.parse_array_suffix(type_)?;
+
         // Handle initializer
         let initializer = if self.match_token(&TokenKind::Assign) {
-            Some(Box::new(self.parse_expression()?))
+            Some(Box::new(self.parse_assignment()?))
         } else {
             None
         };
 
-        self.expect(&TokenKind::Semicolon, "Expected ';' after variable declaration")?;
+        // An array sized by a string initializer holds the string and its terminator
+        let var_type = match (var_type, initializer.as_deref()) {
+            (Type::Array(element, None), Some(Node::StringLiteral(value, _))) => {
+                Type::Array(element, Some(value.chars().count() + 1))
+            }
+            (var_type, _) => var_type,
+        };
 
         Ok(Node::VarDecl {
             name,
             type_: var_type,
+            storage,
             initializer,
             location,
         })
     }
 
+    /// Parse the array sizes that may follow a declared name, such as `[10]`, or
+    /// `[3][4]` for an array of 3 arrays of 4
+    fn parse_array_suffix(&mut self, type_: Type) -> Result<Type> {
+        // The last size is that of the innermost array
+        let sizes = self.parse_array_sizes()?;
+        Ok(sizes.into_iter().rev().fold(type_, |element, size| Type::Array(Box::new(element), size)))
+    }
+
+    /// Parse a sequence of array sizes, outermost first. Only the first size may be
+    /// left out.
+    fn parse_array_sizes(&mut self) -> Result<Vec<Option<usize>>> {
+        let mut sizes = Vec::new();
+
+        // Handle array declarations
+        while self.check(&TokenKind::LeftBracket) {
+            let bracket_location = self.current.unwrap().location.clone();
+            self.advance();
+
+            let size = if self.check(&TokenKind::RightBracket) {
+                if !sizes.is_empty() {
+                    return Err(syntax_error(&bracket_location, "Only the first array size may be left out"));
+                }
+                None
+            } else {
+                let size_location = self.current.unwrap().location.clone();
+                let size_expr = self.parse_assignment()?;
+
+                match constfold::eval_constant(size_expr)? {
+                    Some(size) if size > 0 => Some(size as usize),
+                    Some(size) => {
+                        return Err(syntax_error(&size_location, format!("Array size must be positive, but is {}", size)));
+                    }
+                    None => {
+                        return Err(syntax_error(&size_location, "Array size must be an integer constant expression"));
+                    }
+                }
+            };
+
+            self.expect(&TokenKind::RightBracket, "Expected ']' after array size")?;
+            sizes.push(size);
+        }
+
+        Ok(sizes)
+    }
+
     /// Parse a function declaration
     fn parse_function_declaration(&mut self, name: String, return_type: Type, location: Location) -> Result<Node> {
-        // Special case for main function with command-line arguments
-        let is_main = name == "main";
         self.expect(&TokenKind::LeftParen, "Expected '(' after function name")?;
+        self.declare_identifier(&name);
+
+        // The parameters are in scope for the body, where they hide any typedef of
+        // the same name
+        self.typedefs.push(HashMap::new());
+        let params = self.parse_parameters()?;
+        self.expect(&TokenKind::RightParen, "Expected ')' after parameters")?;
+        self.parse_function_body(name, return_type, params, location)
+    }
+
+    /// Parse the body of a function after its parameters, or the `;` ending its
+    /// prototype. The scope of its parameters, entered before them, ends with it.
+    fn parse_function_body(&mut self, name: String, return_type: Type, params: Vec<(String, Type)>, location: Location) -> Result<Node> {
+        for (param, _) in &params {
+            self.declare_identifier(param);
+        }
+
+        // Parse function body if present
+        let body = if self.check(&TokenKind::LeftBrace) {
+            Some(Box::new(self.parse_block()?))
+        } else {
+            self.expect(&TokenKind::Semicolon, "Expected ';' after function declaration")?;
+            None
+        };
+        self.typedefs.pop();
+
+        Ok(Node::FunctionDecl {
+            name,
+            return_type,
+            params,
+            body,
+            location,
+        })
+    }
+
+    /// Check if a parenthesized declarator such as the `(*name)` of `(*name)(int)`
+    /// or `(*name)[4]` comes next
+    fn at_parenthesized_declarator(&mut self) -> bool {
+        self.check(&TokenKind::LeftParen) && matches!(self.peek(), Some(Token { kind: TokenKind::Asterisk, .. }))
+    }
+
+    /// Parse a parenthesized declarator and the parameters or array sizes after it,
+    /// giving the type it declares from the given one: `(*name)(int, char *)` points
+    /// to a function returning that type, `(*name)[4]` to an array of 4 of it, and
+    /// `(*name[2])(void)` is an array of 2 such function pointers. The name may be
+    /// left out, as for a prototype's parameter or in a cast.
+    fn parse_parenthesized_declarator(&mut self, type_: Type) -> Result<(Option<(String, Location)>, Type)> {
+        let (name, inner) = self.parse_nested_declarator()?;
+        // What follows the parentheses applies to the given type first, so
+        // `(*name)[4]` is a pointer to an array rather than an array of pointers
+        let outer = self.parse_declarator_suffixes()?;
+        Ok((name, derive(derive(type_, outer), inner)))
+    }
+
+    /// Parse a declarator in parentheses, returning its name, if any, and the steps
+    /// it takes from the type that what follows the parentheses gives it
+    fn parse_nested_declarator(&mut self) -> Result<NestedDeclarator> {
+        self.expect(&TokenKind::LeftParen, "Expected '(' before declarator")?;
+
+        // Each * nearer the name adds a level of pointer, as in `(**handler)(int)`
+        let mut derivations = Vec::new();
+        while self.match_token(&TokenKind::Asterisk) {
+            let (is_const, is_volatile) = self.parse_qualifiers();
+            derivations.push(Derivation::Pointer(is_const, is_volatile));
+        }
+
+        // Declarators nest, as in `(*(*name)(int))[4]`, whose innermost one applies last
+        let (name, inner) = if self.at_parenthesized_declarator() {
+            self.parse_nested_declarator()?
+        } else {
+            let name = match self.current {
+                Some(Token { kind: TokenKind::Identifier(name), location, .. }) => {
+                    let name = (name.clone(), location.clone());
+                    self.advance();
+                    Some(name)
+                }
+                _ => None,
+            };
+            (name, Vec::new())
+        };
+        derivations.extend(self.parse_declarator_suffixes()?);
+        derivations.extend(inner);
+
+        self.expect(&TokenKind::RightParen, "Expected ')' after declarator")?;
+        Ok((name, derivations))
+    }
+
+    /// Parse the parameter list or array sizes that may follow a direct declarator,
+    /// in the order they apply to the type they derive from
+    fn parse_declarator_suffixes(&mut self) -> Result<Vec<Derivation>> {
+        if self.match_token(&TokenKind::LeftParen) {
+            let params = self.parse_parameters()?;
+            self.expect(&TokenKind::RightParen, "Expected ')' after parameters")?;
+            return Ok(vec![Derivation::Function(params)]);
+        }
+
+        // The last size is that of the innermost array
+        Ok(self.parse_array_sizes()?.into_iter().rev().map(Derivation::Array).collect())
+    }
 
-        // Parse parameters
+    /// Parse the parameters of a function or function pointer, up to the closing ')'.
+    /// A variadic function ends with a parameter named "...".
+    fn parse_parameters(&mut self) -> Result<Vec<(String, Type)>> {
         let mut params = Vec::new();
 
+        // `(void)` declares a function without parameters
+        let is_void_list = self.check(&TokenKind::Void)
+            && matches!(self.peek(), Some(Token { kind: TokenKind::RightParen, .. }));
+        if is_void_list {
+            self.advance();
+        }
+
         if !self.check(&TokenKind::RightParen) {
             loop {
+                // register is the only storage class a parameter can have, and changes nothing
+                self.match_token(&TokenKind::Register);
                 let param_type = self.parse_type()?;
 
-                if let Some(token) = self.current {
+                if self.at_parenthesized_declarator() {
+                    let (name, param_type) = self.parse_parenthesized_declarator(param_type)?;
+                    params.push((name.map(|(name, _)| name).unwrap_or_default(), adjust_parameter(param_type)));
+                } else if let Some(token) = self.current {
                     if let TokenKind::Identifier(name) = &token.kind {
                         let param_name = name.clone();
                         self.advance(); // Consume the identifier
+                        let param_type = self.parse_array_parameter(param_type)?;
                         params.push((param_name, param_type));
+                    } else if matches!(token.kind, TokenKind::Comma | TokenKind::RightParen | TokenKind::LeftBracket) {
+                        // A prototype may leave its parameters unnamed
+                        let param_type = self.parse_array_parameter(param_type)?;
+                        params.push((String::new(), param_type));
                     } else {
                         return Err(syntax_error(
                             &token.location,
@@ -326,11 +765,7 @@ impl<'a> Parser<'a> {
                     }
                 } else {
                     return Err(syntax_error(
-                        &Location {
-                            file: "unknown".to_string(),
-                            line: 0,
-                            column: 0,
-                        },
+                        &Location::point("unknown", 0, 0),
                         "Unexpected end of file",
                     ));
                 }
@@ -350,23 +785,14 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.expect(&TokenKind::RightParen, "Expected ')' after parameters")?;
-
-        // Parse function body if present
-        let body = if self.check(&TokenKind::LeftBrace) {
-            Some(Box::new(self.parse_block()?))
-        } else {
-            self.expect(&TokenKind::Semicolon, "Expected ';' after function declaration")?;
-            None
-        };
+        Ok(params)
+    }
 
-        Ok(Node::FunctionDecl {
-            name,
-            return_type,
-            params,
-            body,
-            location,
-        })
+    /// Parse the array sizes that may follow a parameter's name. A parameter declared
+    /// as an array is a pointer to its first element, so `int m[][4]` is a pointer
+    /// to arrays of 4 ints.
+    fn parse_array_parameter(&mut self, type_: Type) -> Result<Type> {
+        Ok(adjust_parameter(self.parse_array_suffix(type_)?))
     }
 
     /// Parse a block statement
@@ -374,11 +800,19 @@ impl<'a> Parser<'a> {
         let location = self.current.unwrap().location.clone();
         self.expect(&TokenKind::LeftBrace, "Expected '{'")?;
 
+        // Typedefs declared in the block, and identifiers hiding them, end with it
+        self.typedefs.push(HashMap::new());
         let mut statements = Vec::new();
 
         while !self.check(&TokenKind::RightBrace) && self.current.is_some() {
-            statements.push(self.parse_statement()?);
+            if self.is_type_start() {
+                // A declaration can introduce several variables into the block
+                statements.extend(self.parse_declaration()?);
+            } else {
+                statements.push(self.parse_statement()?);
+            }
         }
+        self.typedefs.pop();
 
         self.expect(&TokenKind::RightBrace, "Expected '}'")?;
 
@@ -393,14 +827,39 @@ impl<'a> Parser<'a> {
                 TokenKind::While => self.parse_while_statement(),
                 TokenKind::For => self.parse_for_statement(),
                 TokenKind::Return => self.parse_return_statement(),
+                TokenKind::Goto => self.parse_goto_statement(),
+                TokenKind::Break => {
+                    self.advance();
+                    self.expect(&TokenKind::Semicolon, "Expected ';' after 'break'")?;
+                    Ok(Node::BreakStmt(token.location.clone()))
+                }
+                TokenKind::Continue => {
+                    self.advance();
+                    self.expect(&TokenKind::Semicolon, "Expected ';' after 'continue'")?;
+                    Ok(Node::ContinueStmt(token.location.clone()))
+                }
+                TokenKind::Identifier(name) if matches!(self.peek(), Some(Token { kind: TokenKind::Colon, .. })) => {
+                    // A label marks the statement that follows it
+                    let name = name.clone();
+                    self.advance(); // Skip the label name
+                    self.advance(); // Skip ':'
+
+                    let statement = self.parse_statement()?;
+                    Ok(Node::LabelStmt(name, Box::new(statement), token.location.clone()))
+                }
                 TokenKind::LeftBrace => self.parse_block(),
-                TokenKind::Int | TokenKind::Char | TokenKind::Void | TokenKind::Long | TokenKind::Struct => {
-                    let decl = self.parse_declaration()?;
-                    Ok(decl)
+                _ if self.is_type_start() => {
+                    // A declaration used as a whole statement gets its own scope
+                    let mut decls = self.parse_declaration()?;
+                    if decls.len() == 1 {
+                        Ok(decls.remove(0))
+                    } else {
+                        Ok(Node::BlockStmt(decls, token.location.clone()))
+                    }
                 }
                 TokenKind::Semicolon => {
                     self.advance();
-                    Ok(Node::ExpressionStmt(Box::new(Node::IntLiteral(0, token.location.clone()))))
+                    Ok(Node::EmptyStmt(token.location.clone()))
                 }
                 _ => {
                     let expr = self.parse_expression()?;
@@ -409,24 +868,40 @@ impl<'a> Parser<'a> {
                 }
             },
             None => Err(syntax_error(
-                &Location {
-                    file: "unknown".to_string(),
-                    line: 0,
-                    column: 0,
-                },
+                &Location::point("unknown", 0, 0),
                 "Unexpected end of file",
             )),
         }
     }
 
+    /// Parse the parenthesized condition of an if or while statement. Also returns
+    /// whether the condition is wrapped in a second pair of parentheses of its own,
+    /// as in `if ((c = next()))`, which is found by parsing ahead and backing up
+    /// when the inner parentheses turn out to close only part of the condition.
+    fn parse_condition(&mut self, keyword: &str) -> Result<(Node, bool)> {
+        self.expect(&TokenKind::LeftParen, &format!("Expected '(' after '{}'", keyword))?;
+
+        let start = (self.tokens.clone(), self.current);
+        if self.match_token(&TokenKind::LeftParen) {
+            if let Ok(condition) = self.parse_expression() {
+                if self.match_token(&TokenKind::RightParen) && self.match_token(&TokenKind::RightParen) {
+                    return Ok((condition, true));
+                }
+            }
+            (self.tokens, self.current) = start;
+        }
+
+        let condition = self.parse_expression()?;
+        self.expect(&TokenKind::RightParen, "Expected ')' after condition")?;
+        Ok((condition, false))
+    }
+
     /// Parse an if statement
     fn parse_if_statement(&mut self) -> Result<Node> {
         let location = self.current.unwrap().location.clone();
         self.advance(); // Skip 'if'
 
-        self.expect(&TokenKind::LeftParen, "Expected '(' after 'if'")?;
-        let condition = self.parse_expression()?;
-        self.expect(&TokenKind::RightParen, "Expected ')' after condition")?;
+        let (condition, parenthesized) = self.parse_condition("if")?;
 
         let then_branch = self.parse_statement()?;
 
@@ -440,6 +915,7 @@ impl<'a> Parser<'a> {
             condition: Box::new(condition),
             then_branch: Box::new(then_branch),
             else_branch,
+            parenthesized,
             location,
         })
     }
@@ -449,15 +925,14 @@ impl<'a> Parser<'a> {
         let location = self.current.unwrap().location.clone();
         self.advance(); // Skip 'while'
 
-        self.expect(&TokenKind::LeftParen, "Expected '(' after 'while'")?;
-        let condition = self.parse_expression()?;
-        self.expect(&TokenKind::RightParen, "Expected ')' after condition")?;
+        let (condition, parenthesized) = self.parse_condition("while")?;
 
         let body = self.parse_statement()?;
 
         Ok(Node::WhileStmt {
             condition: Box::new(condition),
             body: Box::new(body),
+            parenthesized,
             location,
         })
     }
@@ -523,21 +998,50 @@ impl<'a> Parser<'a> {
         Ok(Node::ReturnStmt(value, location))
     }
 
+    /// Parse a goto statement
+    fn parse_goto_statement(&mut self) -> Result<Node> {
+        let location = self.current.unwrap().location.clone();
+        self.advance(); // Skip 'goto'
+
+        let (label, _) = self.expect_identifier("Expected label name after 'goto'")?;
+        self.expect(&TokenKind::Semicolon, "Expected ';' after goto statement")?;
+
+        Ok(Node::GotoStmt(label, location))
+    }
+
     /// Parse an expression
     fn parse_expression(&mut self) -> Result<Node> {
-        self.parse_assignment()
+        self.parse_comma_expression()
+    }
+
+    /// Parse a comma expression
+    fn parse_comma_expression(&mut self) -> Result<Node> {
+        let mut expr = self.parse_assignment()?;
+
+        while self.check(&TokenKind::Comma) {
+            let location = self.current.unwrap().location.clone();
+            self.advance();
+            let right = self.parse_assignment()?;
+
+            expr = Node::CommaExpr {
+                left: Box::new(expr),
+                right: Box::new(right),
+                location,
+            };
+        }
+
+        Ok(expr)
     }
 
     /// Parse an assignment expression
     fn parse_assignment(&mut self) -> Result<Node> {
-        let expr = self.parse_logical_or()?;
+        let expr = self.parse_conditional()?;
 
-        if self.match_token(&TokenKind::Assign) {
-            let location = self.current.unwrap().location.clone();
+        if let Some((op, location)) = self.match_binary_op(&[(TokenKind::Assign, BinaryOp::Assign)]) {
             let value = self.parse_assignment()?;
 
             Ok(Node::BinaryExpr {
-                op: BinaryOp::Assign,
+                op,
                 left: Box::new(expr),
                 right: Box::new(value),
                 location,
@@ -547,16 +1051,39 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parse a conditional expression. The middle operand may be any expression,
+    /// even a comma expression, and the last one is another conditional
+    /// expression, so `a ? b : c ? d : e` groups as `a ? b : (c ? d : e)`.
+    fn parse_conditional(&mut self) -> Result<Node> {
+        let condition = self.parse_logical_or()?;
+
+        if !self.check(&TokenKind::QuestionMark) {
+            return Ok(condition);
+        }
+        let location = self.current.unwrap().location.clone();
+        self.advance();
+
+        let then_expr = self.parse_expression()?;
+        self.expect(&TokenKind::Colon, "Expected ':' in conditional expression")?;
+        let else_expr = self.parse_conditional()?;
+
+        Ok(Node::ConditionalExpr {
+            condition: Box::new(condition),
+            then_expr: Box::new(then_expr),
+            else_expr: Box::new(else_expr),
+            location,
+        })
+    }
+
     /// Parse a logical OR expression
     fn parse_logical_or(&mut self) -> Result<Node> {
         let mut expr = self.parse_logical_and()?;
 
-        while self.match_token(&TokenKind::LogicalOr) {
-            let location = self.current.unwrap().location.clone();
+        while let Some((op, location)) = self.match_binary_op(&[(TokenKind::LogicalOr, BinaryOp::LogicalOr)]) {
             let right = self.parse_logical_and()?;
 
             expr = Node::BinaryExpr {
-                op: BinaryOp::LogicalOr,
+                op,
                 left: Box::new(expr),
                 right: Box::new(right),
                 location,
@@ -570,12 +1097,11 @@ impl<'a> Parser<'a> {
     fn parse_logical_and(&mut self) -> Result<Node> {
         let mut expr = self.parse_equality()?;
 
-        while self.match_token(&TokenKind::LogicalAnd) {
-            let location = self.current.unwrap().location.clone();
+        while let Some((op, location)) = self.match_binary_op(&[(TokenKind::LogicalAnd, BinaryOp::LogicalAnd)]) {
             let right = self.parse_equality()?;
 
             expr = Node::BinaryExpr {
-                op: BinaryOp::LogicalAnd,
+                op,
                 left: Box::new(expr),
                 right: Box::new(right),
                 location,
@@ -589,16 +1115,10 @@ impl<'a> Parser<'a> {
     fn parse_equality(&mut self) -> Result<Node> {
         let mut expr = self.parse_relational()?;
 
-        loop {
-            let op = if self.match_token(&TokenKind::Equal) {
-                BinaryOp::Equal
-            } else if self.match_token(&TokenKind::NotEqual) {
-                BinaryOp::NotEqual
-            } else {
-                break;
-            };
-
-            let location = self.current.unwrap().location.clone();
+        while let Some((op, location)) = self.match_binary_op(&[
+            (TokenKind::Equal, BinaryOp::Equal),
+            (TokenKind::NotEqual, BinaryOp::NotEqual),
+        ]) {
             let right = self.parse_relational()?;
 
             expr = Node::BinaryExpr {
@@ -614,22 +1134,35 @@ impl<'a> Parser<'a> {
 
     /// Parse a relational expression
     fn parse_relational(&mut self) -> Result<Node> {
-        let mut expr = self.parse_additive()?;
+        let mut expr = self.parse_shift()?;
 
-        loop {
-            let op = if self.match_token(&TokenKind::LessThan) {
-                BinaryOp::Less
-            } else if self.match_token(&TokenKind::LessThanEqual) {
-                BinaryOp::LessEqual
-            } else if self.match_token(&TokenKind::GreaterThan) {
-                BinaryOp::Greater
-            } else if self.match_token(&TokenKind::GreaterThanEqual) {
-                BinaryOp::GreaterEqual
-            } else {
-                break;
+        while let Some((op, location)) = self.match_binary_op(&[
+            (TokenKind::LessThan, BinaryOp::Less),
+            (TokenKind::LessThanEqual, BinaryOp::LessEqual),
+            (TokenKind::GreaterThan, BinaryOp::Greater),
+            (TokenKind::GreaterThanEqual, BinaryOp::GreaterEqual),
+        ]) {
+            let right = self.parse_shift()?;
+
+            expr = Node::BinaryExpr {
+                op,
+                left: Box::new(expr),
+                right: Box::new(right),
+                location,
             };
+        }
 
-            let location = self.current.unwrap().location.clone();
+        Ok(expr)
+    }
+
+    /// Parse a shift expression
+    fn parse_shift(&mut self) -> Result<Node> {
+        let mut expr = self.parse_additive()?;
+
+        while let Some((op, location)) = self.match_binary_op(&[
+            (TokenKind::ShiftLeft, BinaryOp::ShiftLeft),
+            (TokenKind::ShiftRight, BinaryOp::ShiftRight),
+        ]) {
             let right = self.parse_additive()?;
 
             expr = Node::BinaryExpr {
@@ -647,16 +1180,10 @@ impl<'a> Parser<'a> {
     fn parse_additive(&mut self) -> Result<Node> {
         let mut expr = self.parse_multiplicative()?;
 
-        loop {
-            let op = if self.match_token(&TokenKind::Plus) {
-                BinaryOp::Add
-            } else if self.match_token(&TokenKind::Minus) {
-                BinaryOp::Subtract
-            } else {
-                break;
-            };
-
-            let location = self.current.unwrap().location.clone();
+        while let Some((op, location)) = self.match_binary_op(&[
+            (TokenKind::Plus, BinaryOp::Add),
+            (TokenKind::Minus, BinaryOp::Subtract),
+        ]) {
             let right = self.parse_multiplicative()?;
 
             expr = Node::BinaryExpr {
@@ -674,18 +1201,11 @@ impl<'a> Parser<'a> {
     fn parse_multiplicative(&mut self) -> Result<Node> {
         let mut expr = self.parse_unary()?;
 
-        loop {
-            let op = if self.match_token(&TokenKind::Asterisk) {
-                BinaryOp::Multiply
-            } else if self.match_token(&TokenKind::Slash) {
-                BinaryOp::Divide
-            } else if self.match_token(&TokenKind::Percent) {
-                BinaryOp::Modulo
-            } else {
-                break;
-            };
-
-            let location = self.current.unwrap().location.clone();
+        while let Some((op, location)) = self.match_binary_op(&[
+            (TokenKind::Asterisk, BinaryOp::Multiply),
+            (TokenKind::Slash, BinaryOp::Divide),
+            (TokenKind::Percent, BinaryOp::Modulo),
+        ]) {
             let right = self.parse_unary()?;
 
             expr = Node::BinaryExpr {
@@ -723,6 +1243,8 @@ impl<'a> Parser<'a> {
                     self.advance();
                     Some(UnaryOp::AddressOf)
                 }
+                TokenKind::Sizeof => return self.parse_sizeof(),
+                TokenKind::LeftParen if self.at_parenthesized_type_name() => return self.parse_cast(),
                 _ => None,
             };
 
@@ -741,6 +1263,90 @@ impl<'a> Parser<'a> {
         self.parse_postfix()
     }
 
+    /// Parse `sizeof` applied to a parenthesized type name or to a unary expression
+    fn parse_sizeof(&mut self) -> Result<Node> {
+        let location = self.current.unwrap().location.clone();
+        self.advance(); // Skip 'sizeof'
+
+        // A '(' followed by a type name is a type; otherwise it begins the operand
+        if self.at_parenthesized_type_name() {
+            self.advance();
+            let type_ = self.parse_type_name()?;
+            let close = self.expect(&TokenKind::RightParen, "Expected ')' after type name")?;
+            return Ok(Node::SizeofType(type_, location.join(&close.location)));
+        }
+
+        let expr = self.parse_unary()?;
+        Ok(Node::SizeofExpr(Box::new(expr), location))
+    }
+
+    /// Parse a cast such as `(long)x`, applied to a unary expression
+    fn parse_cast(&mut self) -> Result<Node> {
+        let location = self.current.unwrap().location.clone();
+        self.advance(); // Skip '('
+
+        let type_ = self.parse_type_name()?;
+        let close = self.expect(&TokenKind::RightParen, "Expected ')' after type name")?;
+        let expr = self.parse_unary()?;
+
+        Ok(Node::Cast {
+            type_,
+            expr: Box::new(expr),
+            location: location.join(&close.location),
+        })
+    }
+
+    /// Check if a '(' followed by a type name comes next, as in a cast or `sizeof(int)`.
+    /// An identifier is a type name only if a typedef in scope declares it, so
+    /// `(count)` remains a parenthesized expression.
+    fn at_parenthesized_type_name(&mut self) -> bool {
+        self.check(&TokenKind::LeftParen) && self.peek().is_some_and(|token| self.is_type_name(token))
+    }
+
+    /// Parse a type name without a declared name, as written in a cast or sizeof:
+    /// a type with its pointers, and possibly an abstract function pointer such as
+    /// `int (*)(int)` or an array size
+    fn parse_type_name(&mut self) -> Result<Type> {
+        let type_ = self.parse_type()?;
+        if self.at_parenthesized_declarator() {
+            let (name, type_) = self.parse_parenthesized_declarator(type_)?;
+            if let Some((_, location)) = name {
+                return Err(syntax_error(&location, "Unexpected name in type name"));
+            }
+            return Ok(type_);
+        }
+        self.parse_array_suffix(type_)
+    }
+
+    /// Parse one of the builtins that `<stdarg.h>` defines va_start, va_arg and
+    /// va_end as. Their operands are not all expressions, so they are not calls.
+    fn parse_va_builtin(&mut self) -> Result<Node> {
+        let (name, location) = self.expect_identifier("Expected builtin name")?;
+        self.expect(&TokenKind::LeftParen, &format!("Expected '(' after {}", name))?;
+        let list = Box::new(self.parse_assignment()?);
+
+        let node = match name.as_str() {
+            "__builtin_va_start" => {
+                self.expect(&TokenKind::Comma, "Expected ',' after va_list in va_start")?;
+                let (last, _) = self.expect_identifier("Expected the last named parameter in va_start")?;
+                let close = self.expect(&TokenKind::RightParen, "Expected ')' after builtin arguments")?;
+                Node::VaStart(list, last, location.join(&close.location))
+            }
+            "__builtin_va_arg" => {
+                self.expect(&TokenKind::Comma, "Expected ',' after va_list in va_arg")?;
+                let type_ = self.parse_type()?;
+                let close = self.expect(&TokenKind::RightParen, "Expected ')' after builtin arguments")?;
+                Node::VaArg(list, type_, location.join(&close.location))
+            }
+            _ => {
+                let close = self.expect(&TokenKind::RightParen, "Expected ')' after builtin arguments")?;
+                Node::VaEnd(list, location.join(&close.location))
+            }
+        };
+
+        Ok(node)
+    }
+
     /// Parse a postfix expression
     fn parse_postfix(&mut self) -> Result<Node> {
         let mut expr = self.parse_primary()?;
@@ -753,34 +1359,48 @@ impl<'a> Parser<'a> {
 
                 if !self.check(&TokenKind::RightParen) {
                     loop {
-                        args.push(self.parse_expression()?);
+                        // Arguments are assignment expressions so the separating commas
+                        // are not mistaken for the comma operator
+                        args.push(self.parse_assignment()?);
 
+                        let comma = self.current.map(|token| token.location.clone());
                         if !self.match_token(&TokenKind::Comma) {
                             break;
                         }
+
+                        // An argument list cannot end in a comma
+                        if self.check(&TokenKind::RightParen) {
+                            return Err(syntax_error(
+                                &comma.unwrap(),
+                                "Expected an argument after ',' in function call",
+                            ));
+                        }
                     }
                 }
 
-                self.expect(&TokenKind::RightParen, "Expected ')' after arguments")?;
+                let close = self.expect(&TokenKind::RightParen, "Expected ')' after arguments")?;
 
-                if let Node::Identifier(name, _) = expr {
-                    expr = Node::FunctionCall {
+                // A named callee may be a function or a function pointer variable; any
+                // other callee is an expression that evaluates to a function pointer.
+                // Either call ends with its ')'.
+                expr = match expr {
+                    Node::Identifier(name, name_location) => Node::FunctionCall {
                         name,
                         args,
-                        location,
-                    };
-                } else {
-                    return Err(syntax_error(
-                        &location,
-                        "Expected function name before '('",
-                    ));
-                }
+                        location: name_location.join(&close.location),
+                    },
+                    callee => Node::IndirectCall {
+                        callee: Box::new(callee),
+                        args,
+                        location: location.join(&close.location),
+                    },
+                };
             } else if self.match_token(&TokenKind::LeftBracket) {
                 // Array access
                 let location = self.current.unwrap().location.clone();
                 let index = self.parse_expression()?;
 
-                self.expect(&TokenKind::RightBracket, "Expected ']' after index")?;
+                let close = self.expect(&TokenKind::RightBracket, "Expected ']' after index")?;
 
                 // Array access is equivalent to *(array + index)
                 let array_plus_index = Node::BinaryExpr {
@@ -793,38 +1413,32 @@ impl<'a> Parser<'a> {
                 expr = Node::UnaryExpr {
                     op: UnaryOp::Dereference,
                     expr: Box::new(array_plus_index),
-                    location,
+                    location: location.join(&close.location),
                 };
             } else if self.match_token(&TokenKind::Dot) {
                 // Struct member access
-                let location = self.current.unwrap().location.clone();
-                let _member = match &self.expect(&TokenKind::Identifier("".to_string()), "Expected member name after '.'")?
-                    .kind
-                {
-                    TokenKind::Identifier(name) => name.clone(),
-                    _ => unreachable!(),
-                };
+                let (member, location) = self.expect_identifier("Expected member name after '.'")?;
 
-                // TODO: Implement struct member access
-                return Err(syntax_error(
-                    &location,
-                    "Struct member access not implemented yet",
-                ));
+                expr = Node::MemberAccess {
+                    expr: Box::new(expr),
+                    member,
+                    location,
+                };
             } else if self.match_token(&TokenKind::Arrow) {
-                // Struct pointer member access
-                let location = self.current.unwrap().location.clone();
-                let _member = match &self.expect(&TokenKind::Identifier("".to_string()), "Expected member name after '->'")?
-                    .kind
-                {
-                    TokenKind::Identifier(name) => name.clone(),
-                    _ => unreachable!(),
+                // Struct pointer member access: p->member is equivalent to (*p).member
+                let (member, location) = self.expect_identifier("Expected member name after '->'")?;
+
+                let struct_expr = Node::UnaryExpr {
+                    op: UnaryOp::Dereference,
+                    expr: Box::new(expr),
+                    location: location.clone(),
                 };
 
-                // TODO: Implement struct pointer member access
-                return Err(syntax_error(
-                    &location,
-                    "Struct pointer member access not implemented yet",
-                ));
+                expr = Node::MemberAccess {
+                    expr: Box::new(struct_expr),
+                    member,
+                    location,
+                };
             } else {
                 break;
             }
@@ -844,6 +1458,22 @@ impl<'a> Parser<'a> {
                         self.advance();
                         Ok(Node::IntLiteral(*value, location))
                     }
+                    TokenKind::UIntLiteral(value) => {
+                        self.advance();
+                        Ok(Node::UIntLiteral(*value, location))
+                    }
+                    TokenKind::LongLiteral(value) => {
+                        self.advance();
+                        Ok(Node::LongLiteral(*value, location))
+                    }
+                    TokenKind::ULongLiteral(value) => {
+                        self.advance();
+                        Ok(Node::ULongLiteral(*value, location))
+                    }
+                    TokenKind::FloatLiteral(value) => {
+                        self.advance();
+                        Ok(Node::FloatLiteral(*value, location))
+                    }
                     TokenKind::CharLiteral(value) => {
                         self.advance();
                         Ok(Node::CharLiteral(*value, location))
@@ -852,6 +1482,10 @@ impl<'a> Parser<'a> {
                         self.advance();
                         Ok(Node::StringLiteral(value.clone(), location))
                     }
+                    TokenKind::Identifier(name) if matches!(
+                        name.as_str(),
+                        "__builtin_va_start" | "__builtin_va_arg" | "__builtin_va_end"
+                    ) => self.parse_va_builtin(),
                     TokenKind::Identifier(name) => {
                         self.advance();
                         Ok(Node::Identifier(name.clone(), location))
@@ -869,13 +1503,45 @@ impl<'a> Parser<'a> {
                 }
             }
             None => Err(syntax_error(
-                &Location {
-                    file: "unknown".to_string(),
-                    line: 0,
-                    column: 0,
-                },
+                &Location::point("unknown", 0, 0),
                 "Unexpected end of file",
             )),
         }
     }
 }
+
+/// Check if a token can start a type name: a type specifier or qualifier
+fn is_type_name_start(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Int | TokenKind::Char | TokenKind::Void | TokenKind::Long | TokenKind::Bool |
+        TokenKind::Signed | TokenKind::Unsigned | TokenKind::Float | TokenKind::Double |
+        TokenKind::Struct | TokenKind::Union | TokenKind::Const | TokenKind::Volatile
+    )
+}
+
+/// The name a nested declarator declares, if any, with its location, and the steps
+/// it takes toward the declared type
+type NestedDeclarator = (Option<(String, Location)>, Vec<Derivation>);
+
+/// Apply the steps of a declarator to a type, in order
+fn derive(type_: Type, derivations: Vec<Derivation>) -> Type {
+    derivations.into_iter().fold(type_, |type_, derivation| match derivation {
+        Derivation::Pointer(is_const, is_volatile) => Type::Pointer(Box::new(type_)).qualified(is_const, is_volatile),
+        Derivation::Array(size) => Type::Array(Box::new(type_), size),
+        Derivation::Function(params) => {
+            let is_variadic = params.iter().any(|(name, _)| name == "...");
+            let param_types = params.into_iter().filter(|(name, _)| name != "...").map(|(_, type_)| type_).collect();
+            Type::Function(Box::new(type_), param_types, is_variadic)
+        }
+    })
+}
+
+/// Adjust the declared type of a parameter to the type it has: an array parameter
+/// is a pointer to its first element
+fn adjust_parameter(type_: Type) -> Type {
+    match type_ {
+        Type::Array(element, _) => Type::Pointer(element),
+        type_ => type_,
+    }
+}
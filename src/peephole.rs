@@ -0,0 +1,208 @@
+use crate::asm::Instruction;
+
+/// Run the peephole passes over the generated instructions until nothing changes
+pub fn optimize(instructions: &mut Vec<Instruction>) {
+    loop {
+        let mut changed = forward_push_pop(instructions);
+        changed |= fold_move_into_copy(instructions);
+        changed |= fold_move_into_push(instructions);
+        changed |= remove_self_moves(instructions);
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Check if an instruction is the given mnemonic with exactly the given operands
+fn is_op(instruction: &Instruction, expected_mnemonic: &str, expected_operands: &[&str]) -> bool {
+    match instruction {
+        Instruction::Op { mnemonic, operands } => {
+            mnemonic == expected_mnemonic
+                && operands.len() == expected_operands.len()
+                && operands.iter().zip(expected_operands).all(|(o, e)| o == e)
+        }
+        _ => false,
+    }
+}
+
+/// Check if an operand names one of the given registers
+fn mentions(operand: &str, registers: &[&str]) -> bool {
+    operand
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| registers.contains(&word))
+}
+
+const RCX: &[&str] = &["rcx", "ecx", "cx", "cl", "ch"];
+const RAX: &[&str] = &["rax", "eax", "ax", "al", "ah"];
+const RSP: &[&str] = &["rsp", "esp", "sp", "spl"];
+
+/// Check if an instruction can be moved across without changing its meaning:
+/// it must not touch RCX or the stack, and must not transfer control
+fn is_transparent(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::Op { mnemonic, operands } => {
+            !matches!(mnemonic.as_str(), "push" | "pop" | "call" | "ret" | "leave")
+                && !mnemonic.starts_with('j')
+                && !operands.iter().any(|o| mentions(o, RCX) || mentions(o, RSP))
+        }
+        _ => false,
+    }
+}
+
+/// Check if an instruction overwrites RAX without reading its previous value
+fn overwrites_rax(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::Op { mnemonic, operands } => {
+            matches!(mnemonic.as_str(), "mov" | "lea" | "movsx" | "movsxd" | "movzx")
+                && operands.len() == 2
+                && (operands[0] == "rax" || (operands[0] == "eax" && mnemonic == "mov"))
+                && !mentions(&operands[1], RAX)
+        }
+        _ => false,
+    }
+}
+
+/// Replace `push rax` ... `pop rcx` with a single `mov rcx, rax` when nothing in
+/// between touches RCX or the stack
+fn forward_push_pop(instructions: &mut Vec<Instruction>) -> bool {
+    let mut changed = false;
+    let mut i = 0;
+
+    while i < instructions.len() {
+        if is_op(&instructions[i], "push", &["rax"]) {
+            let mut j = i + 1;
+            while j < instructions.len() && is_transparent(&instructions[j]) {
+                j += 1;
+            }
+
+            if j < instructions.len() && is_op(&instructions[j], "pop", &["rcx"]) {
+                instructions[i] = Instruction::op("mov", &["rcx", "rax"]);
+                instructions.remove(j);
+                changed = true;
+            }
+        }
+        i += 1;
+    }
+
+    changed
+}
+
+/// Get the source operand of a `mov rax, X` instruction
+fn rax_move_source(instruction: &Instruction) -> Option<String> {
+    match instruction {
+        Instruction::Op { mnemonic, operands }
+            if mnemonic == "mov" && operands.len() == 2 && operands[0] == "rax" =>
+        {
+            Some(operands[1].clone())
+        }
+        _ => None,
+    }
+}
+
+/// Registers the code generator copies RAX into to keep an operand aside
+const COPY_TARGETS: &[&str] = &["rcx", "r10", "r11", "rbx", "r12", "r13", "r14", "r15"];
+
+/// Get the destination of a `mov R, rax` copy into one of the operand registers
+fn rax_copy_target(instruction: &Instruction) -> Option<String> {
+    match instruction {
+        Instruction::Op { mnemonic, operands }
+            if mnemonic == "mov"
+                && operands.len() == 2
+                && operands[1] == "rax"
+                && COPY_TARGETS.contains(&operands[0].as_str()) =>
+        {
+            Some(operands[0].clone())
+        }
+        _ => None,
+    }
+}
+
+/// Fold `mov rax, X` + `mov R, rax` into `mov R, X` when RAX is overwritten next
+fn fold_move_into_copy(instructions: &mut Vec<Instruction>) -> bool {
+    let mut changed = false;
+    let mut i = 0;
+
+    while i + 2 < instructions.len() {
+        if let (Some(source), Some(target)) = (
+            rax_move_source(&instructions[i]),
+            rax_copy_target(&instructions[i + 1]),
+        ) {
+            if overwrites_rax(&instructions[i + 2]) {
+                instructions[i] = Instruction::op("mov", &[&target, &source]);
+                instructions.remove(i + 1);
+                changed = true;
+            }
+        }
+        i += 1;
+    }
+
+    changed
+}
+
+/// Check if an operand can be pushed directly: a 32-bit immediate or a qword in memory
+fn is_pushable(operand: &str) -> bool {
+    match operand.parse::<i64>() {
+        Ok(value) => i32::try_from(value).is_ok(),
+        Err(_) => operand.starts_with("qword ptr [") && !mentions(operand, RSP),
+    }
+}
+
+/// Fold `mov rax, X` + `push rax` into `push X` when RAX is overwritten next
+fn fold_move_into_push(instructions: &mut Vec<Instruction>) -> bool {
+    let mut changed = false;
+    let mut i = 0;
+
+    while i + 2 < instructions.len() {
+        if let Some(source) = rax_move_source(&instructions[i]) {
+            if is_pushable(&source)
+                && is_op(&instructions[i + 1], "push", &["rax"])
+                && overwrites_rax(&instructions[i + 2])
+            {
+                instructions[i] = Instruction::op("push", &[&source]);
+                instructions.remove(i + 1);
+                changed = true;
+            }
+        }
+        i += 1;
+    }
+
+    changed
+}
+
+const REGISTERS_64: &[&str] = &[
+    "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+];
+
+/// Remove `mov R, R` on a 64-bit register, which does nothing. Narrower self-moves
+/// such as `mov eax, eax` are kept because they zero the upper half.
+fn remove_self_moves(instructions: &mut Vec<Instruction>) -> bool {
+    let before = instructions.len();
+    instructions.retain(|instruction| {
+        !REGISTERS_64.iter().any(|register| is_op(instruction, "mov", &[register, register]))
+    });
+    instructions.len() != before
+}
+
+/// Remove the instructions after an unconditional `jmp` or a `ret` up to the next
+/// label, which nothing can jump to. Line information goes with them, but other
+/// directives are kept, as they may switch sections or name source files.
+pub fn remove_unreachable(instructions: &mut Vec<Instruction>) {
+    let mut reachable = true;
+    instructions.retain(|instruction| {
+        let keep = match instruction {
+            Instruction::Label(_) => {
+                reachable = true;
+                true
+            }
+            Instruction::Directive(text) => reachable || !text.starts_with(".loc "),
+            Instruction::Op { .. } | Instruction::Blank => reachable,
+        };
+        if let Instruction::Op { mnemonic, .. } = instruction {
+            if reachable && (mnemonic == "jmp" || mnemonic == "ret") {
+                reachable = false;
+            }
+        }
+        keep
+    });
+}
@@ -1,41 +1,131 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::error::{preprocessor_error, Result};
+use crate::ast::{literal_text, Location};
+use crate::constfold;
+use crate::error::{preprocessor_error, warning, Result, Warning};
 use crate::lexer::{Lexer, Token, TokenKind};
+use crate::parser::Parser;
+
+/// State of one `#if` ... `#endif` group
+struct Conditional {
+    /// Whether the enclosing code is being emitted
+    parent_active: bool,
+    /// Whether the current arm is being emitted
+    active: bool,
+    /// Whether some arm of the group has already been taken
+    taken: bool,
+    /// Whether `#else` has been seen
+    seen_else: bool,
+    /// Location of the opening directive, for unterminated groups
+    location: Location,
+}
+
+/// A macro definition
+#[derive(Debug, Clone)]
+struct Macro {
+    /// Parameter names, for function-like macros
+    params: Option<Vec<String>>,
+    /// Replacement list
+    body: Vec<Token>,
+}
+
+/// How many macro expansions may be nested inside one another before the
+/// preprocessor gives up
+const MAX_MACRO_DEPTH: usize = 200;
+
+/// The standard headers built into the compiler, found by `#include <...>` when
+/// no include path has a file of the same name
+const BUNDLED_HEADERS: [(&str, &str); 5] = [
+    ("stdarg.h", include_str!("../include/stdarg.h")),
+    ("stdbool.h", include_str!("../include/stdbool.h")),
+    ("stdio.h", include_str!("../include/stdio.h")),
+    ("stdlib.h", include_str!("../include/stdlib.h")),
+    ("string.h", include_str!("../include/string.h")),
+];
+
+/// Get the contents of a bundled standard header
+fn bundled_header(name: &str) -> Option<&'static str> {
+    BUNDLED_HEADERS.iter().find(|(header, _)| *header == name).map(|(_, content)| *content)
+}
 
 /// Preprocessor for C source code
 pub struct Preprocessor {
     include_paths: Vec<PathBuf>,
+    macros: HashMap<String, Macro>,
+    /// Messages of the `#warning` directives seen so far, and warnings from
+    /// tokenizing included files
+    warnings: Vec<Warning>,
+    /// Whether a `/*` inside a block comment of an included file is a warning
+    /// rather than an error
+    nested_comment_warnings: bool,
 }
 
 impl Preprocessor {
     pub fn new() -> Self {
         Self {
             include_paths: vec![],
+            macros: HashMap::new(),
+            warnings: Vec::new(),
+            nested_comment_warnings: false,
         }
     }
 
+    /// Report a `/*` inside a block comment of an included file as a warning
+    /// rather than an error
+    pub fn set_nested_comment_warnings(&mut self, nested_comment_warnings: bool) {
+        self.nested_comment_warnings = nested_comment_warnings;
+    }
+
+    /// Take the warnings reported while preprocessing
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
     /// Add an include path
     pub fn add_include_path(&mut self, path: impl AsRef<Path>) {
         self.include_paths.push(path.as_ref().to_path_buf());
     }
 
-    /// Define a macro (stub for now)
-    pub fn define_macro(&mut self, _name: &str, _value: Vec<Token>) {
-        // Not implemented yet
+    /// Define an object-like macro
+    pub fn define_macro(&mut self, name: &str, value: Vec<Token>) {
+        self.macros.insert(name.to_string(), Macro { params: None, body: value });
+    }
+
+    /// Find the end of the directive line starting at `i`
+    fn line_end(tokens: &[Token], mut i: usize) -> usize {
+        while i < tokens.len() && !tokens[i].at_bol && tokens[i].kind != TokenKind::Eof {
+            i += 1;
+        }
+        i
+    }
+
+    /// The text of a directive line, such as the message of `#error`: its tokens as
+    /// spelled, with string literals giving just their contents
+    fn line_text(line: &[Token]) -> String {
+        let words: Vec<String> = line
+            .iter()
+            .map(|token| match &token.kind {
+                TokenKind::StringLiteral(text) => literal_text(text),
+                kind => kind.to_string(),
+            })
+            .collect();
+        words.join(" ")
     }
 
     /// Preprocess a token stream
     pub fn preprocess(&mut self, tokens: Vec<Token>) -> Result<Vec<Token>> {
         let mut result = Vec::new();
+        let mut conditionals: Vec<Conditional> = Vec::new();
         let mut i = 0;
 
         while i < tokens.len() {
             let token = &tokens[i];
+            let active = conditionals.last().is_none_or(|c| c.active);
 
-            if token.kind == TokenKind::Hash {
-                // Preprocessor directive
+            if token.kind == TokenKind::Hash && token.at_bol {
+                // Preprocessor directive, which runs to the end of the line
                 i += 1;
 
                 if i >= tokens.len() {
@@ -47,15 +137,53 @@ impl Preprocessor {
 
                 let directive = &tokens[i];
 
+                // `if` and `else` are lexed as keywords
+                let name = match &directive.kind {
+                    TokenKind::Identifier(name) => Some(name.as_str()),
+                    TokenKind::If => Some("if"),
+                    TokenKind::Else => Some("else"),
+                    _ => None,
+                };
+
+                // Conditional directives are tracked even inside skipped groups
+                if let Some(name @ ("if" | "ifdef" | "ifndef" | "elif" | "else" | "endif")) = name {
+                    i = self.process_conditional(name, &tokens, i, &mut conditionals)?;
+                    continue;
+                }
+
+                if !active {
+                    i = Self::line_end(&tokens, i);
+                    continue;
+                }
+
                 match &directive.kind {
                     TokenKind::Identifier(name) => {
                         match name.as_str() {
                             "include" => {
                                 i = self.process_include(&tokens, i, &mut result)?;
                             }
+                            "define" => {
+                                i = self.process_define(&tokens, i)?;
+                            }
+                            "undef" => {
+                                let end = Self::line_end(&tokens, i + 1);
+                                if let Some(Token { kind: TokenKind::Identifier(name), .. }) = tokens.get(i + 1).filter(|_| i + 1 < end) {
+                                    self.macros.remove(name);
+                                }
+                                i = end;
+                            }
+                            "error" | "warning" => {
+                                let end = Self::line_end(&tokens, i + 1);
+                                let message = format!("#{} {}", name, Self::line_text(&tokens[i + 1..end]));
+                                if name == "error" {
+                                    return Err(preprocessor_error(&token.location, message));
+                                }
+                                self.warnings.push(warning(&token.location, message));
+                                i = end;
+                            }
                             _ => {
-                                // Skip to the next token
-                                i += 1;
+                                // Skip the rest of the directive line
+                                i = Self::line_end(&tokens, i + 1);
                             }
                         }
                     }
@@ -64,15 +192,482 @@ impl Preprocessor {
                         i += 1;
                     }
                 }
+            } else if active {
+                i = self.expand_at(&tokens, i, None, &mut Vec::new(), &mut result)?;
             } else {
-                result.push(token.clone());
                 i += 1;
             }
         }
 
+        if let Some(conditional) = conditionals.last() {
+            return Err(preprocessor_error(
+                &conditional.location,
+                "Unterminated conditional directive",
+            ));
+        }
+
         Ok(result)
     }
 
+    /// Process a conditional directive (#if, #ifdef, #ifndef, #elif, #else, #endif)
+    fn process_conditional(&mut self, name: &str, tokens: &[Token], i: usize, conditionals: &mut Vec<Conditional>) -> Result<usize> {
+        let directive = &tokens[i];
+        let end = Self::line_end(tokens, i + 1);
+        let line = &tokens[i + 1..end];
+
+        match name {
+            "if" | "ifdef" | "ifndef" => {
+                let parent_active = conditionals.last().is_none_or(|c| c.active);
+
+                // Conditions inside skipped groups are never evaluated
+                let condition = parent_active && match name {
+                    "if" => self.evaluate_condition(line, directive)?,
+                    _ => {
+                        let defined = match line.first().map(|t| &t.kind) {
+                            Some(TokenKind::Identifier(macro_name)) => self.macros.contains_key(macro_name),
+                            _ => {
+                                return Err(preprocessor_error(
+                                    &directive.location,
+                                    format!("Expected macro name after #{}", name),
+                                ));
+                            }
+                        };
+                        defined == (name == "ifdef")
+                    }
+                };
+
+                conditionals.push(Conditional {
+                    parent_active,
+                    active: condition,
+                    taken: condition,
+                    seen_else: false,
+                    location: directive.location.clone(),
+                });
+            }
+            "elif" | "else" => {
+                let conditional = match conditionals.last() {
+                    Some(conditional) if !conditional.seen_else => conditional,
+                    Some(_) => {
+                        return Err(preprocessor_error(
+                            &directive.location,
+                            format!("#{} after #else", name),
+                        ));
+                    }
+                    None => {
+                        return Err(preprocessor_error(
+                            &directive.location,
+                            format!("#{} without #if", name),
+                        ));
+                    }
+                };
+
+                // Only the first arm whose condition holds is emitted
+                let candidate = conditional.parent_active && !conditional.taken;
+                let active = candidate && (name == "else" || self.evaluate_condition(line, directive)?);
+
+                let conditional = conditionals.last_mut().unwrap();
+                conditional.active = active;
+                conditional.taken |= active;
+                conditional.seen_else = name == "else";
+            }
+            _ => {
+                if conditionals.pop().is_none() {
+                    return Err(preprocessor_error(
+                        &directive.location,
+                        "#endif without #if",
+                    ));
+                }
+            }
+        }
+
+        Ok(end)
+    }
+
+    /// Evaluate the integer constant expression of an #if or #elif directive
+    fn evaluate_condition(&self, line: &[Token], directive: &Token) -> Result<bool> {
+        let mut resolved = Vec::new();
+        let mut j = 0;
+
+        while j < line.len() {
+            let token = &line[j];
+
+            if token.kind == TokenKind::Identifier("defined".to_string()) {
+                // defined NAME or defined(NAME)
+                let parenthesized = line.get(j + 1).is_some_and(|t| t.kind == TokenKind::LeftParen);
+                let name_index = if parenthesized { j + 2 } else { j + 1 };
+
+                let defined = match line.get(name_index).map(|t| &t.kind) {
+                    Some(TokenKind::Identifier(name)) => self.macros.contains_key(name),
+                    _ => {
+                        return Err(preprocessor_error(
+                            &token.location,
+                            "Expected macro name after defined",
+                        ));
+                    }
+                };
+
+                j = name_index + 1;
+                if parenthesized {
+                    if line.get(j).map(|t| &t.kind) != Some(&TokenKind::RightParen) {
+                        return Err(preprocessor_error(
+                            &token.location,
+                            "Expected ')' after macro name",
+                        ));
+                    }
+                    j += 1;
+                }
+
+                let mut literal = token.clone();
+                literal.kind = TokenKind::IntLiteral(defined as i64);
+                resolved.push(literal);
+            } else {
+                resolved.push(token.clone());
+                j += 1;
+            }
+        }
+
+        let mut expanded = self.expand_list(&resolved, None, &mut Vec::new())?;
+
+        // Identifiers that are not macros evaluate to 0
+        for token in &mut expanded {
+            if matches!(token.kind, TokenKind::Identifier(_)) {
+                token.kind = TokenKind::IntLiteral(0);
+            }
+        }
+
+        if expanded.is_empty() {
+            return Err(preprocessor_error(
+                &directive.location,
+                "Expected expression in conditional directive",
+            ));
+        }
+
+        expanded.push(Token::new(TokenKind::Eof, directive.location.clone()));
+
+        let expr = Parser::new(&expanded).parse_constant_expression()?;
+        match constfold::eval_constant(expr)? {
+            Some(value) => Ok(value != 0),
+            None => Err(preprocessor_error(
+                &directive.location,
+                "Conditional directive requires an integer constant expression",
+            )),
+        }
+    }
+
+    /// Process #define directive
+    fn process_define(&mut self, tokens: &[Token], mut i: usize) -> Result<usize> {
+        let directive = &tokens[i];
+        i += 1; // Skip 'define'
+
+        let end = Self::line_end(tokens, i);
+
+        let name = match tokens.get(i).filter(|_| i < end).map(|t| &t.kind) {
+            Some(TokenKind::Identifier(name)) => name.clone(),
+            _ => {
+                return Err(preprocessor_error(
+                    &directive.location,
+                    "Expected macro name after #define",
+                ));
+            }
+        };
+
+        // A '(' directly after the name starts a parameter list
+        let name_token = &tokens[i];
+        let params = match tokens.get(i + 1).filter(|_| i + 1 < end) {
+            Some(paren) if paren.kind == TokenKind::LeftParen
+                && paren.location.line == name_token.location.line
+                && paren.location.column == name_token.location.column + name.len() =>
+            {
+                let mut params = Vec::new();
+                i += 2; // Skip the name and '('
+
+                if i < end && tokens[i].kind == TokenKind::RightParen {
+                    i += 1;
+                } else {
+                    loop {
+                        match tokens.get(i).filter(|_| i < end).map(|t| &t.kind) {
+                            Some(TokenKind::Identifier(param)) => params.push(param.clone()),
+                            _ => {
+                                return Err(preprocessor_error(
+                                    &paren.location,
+                                    "Expected parameter name in macro definition",
+                                ));
+                            }
+                        }
+                        i += 1;
+
+                        match tokens.get(i).filter(|_| i < end).map(|t| &t.kind) {
+                            Some(TokenKind::Comma) => i += 1,
+                            Some(TokenKind::RightParen) => {
+                                i += 1;
+                                break;
+                            }
+                            _ => {
+                                return Err(preprocessor_error(
+                                    &paren.location,
+                                    "Expected ',' or ')' in macro parameter list",
+                                ));
+                            }
+                        }
+                    }
+                }
+
+                Some(params)
+            }
+            _ => {
+                i += 1; // Skip the name
+                None
+            }
+        };
+
+        let body = tokens[i..end].to_vec();
+        match params {
+            Some(params) => {
+                self.macros.insert(name, Macro { params: Some(params), body });
+            }
+            None => self.define_macro(&name, body),
+        }
+
+        Ok(end)
+    }
+
+    /// Expand every macro in a list of tokens
+    fn expand_list(&self, tokens: &[Token], origin: Option<&Token>, expanding: &mut Vec<String>) -> Result<Vec<Token>> {
+        let mut result = Vec::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            i = self.expand_at(tokens, i, origin, expanding, &mut result)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Append the token at `i` to the output, replacing a macro name (and the
+    /// arguments of a function-like macro) by its expansion, and return the index
+    /// of the next unconsumed token. `expanding` is the hide set: a macro named
+    /// there is already being expanded, so its name is left alone, which stops
+    /// `#define A A` or `#define A B` / `#define B A` from expanding forever.
+    /// Expanded tokens are reported at `origin`, the place the outermost macro
+    /// was used.
+    fn expand_at(&self, tokens: &[Token], i: usize, origin: Option<&Token>, expanding: &mut Vec<String>, result: &mut Vec<Token>) -> Result<usize> {
+        let token = &tokens[i];
+        let site = origin.unwrap_or(token);
+
+        if let TokenKind::Identifier(name) = &token.kind {
+            if let Some(macro_) = self.macros.get(name).filter(|_| !expanding.contains(name)) {
+                match &macro_.params {
+                    None => {
+                        let consumed = self.expand_body(name, &macro_.body, &tokens[i + 1..], site, expanding, result)?;
+                        return Ok(i + 1 + consumed);
+                    }
+                    Some(params) if tokens.get(i + 1).is_some_and(|t| t.kind == TokenKind::LeftParen) => {
+                        let (mut args, next) = Self::collect_args(tokens, i + 1)?;
+
+                        // `F()` passes no arguments rather than one empty argument
+                        if params.is_empty() && args.len() == 1 && args[0].is_empty() {
+                            args.clear();
+                        }
+
+                        if args.len() != params.len() {
+                            return Err(preprocessor_error(
+                                &token.location,
+                                format!(
+                                    "Macro {} expects {} arguments, but {} were provided",
+                                    name,
+                                    params.len(),
+                                    args.len()
+                                ),
+                            ));
+                        }
+
+                        let substituted = self.substitute(macro_, params, &args, site, expanding)?;
+                        let consumed = self.expand_body(name, &substituted, &tokens[next..], site, expanding, result)?;
+                        return Ok(next + consumed);
+                    }
+                    // A function-like macro name without arguments is an ordinary identifier
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let mut token = token.clone();
+        if origin.is_some() {
+            token.location = site.location.clone();
+            token.filename = site.filename.clone();
+            token.at_bol = false;
+        }
+        result.push(token);
+
+        Ok(i + 1)
+    }
+
+    /// Rescan the replacement list of the macro `name`, used at `site`, and append
+    /// its expansion to the output. The expansion may end in the name of a
+    /// function-like macro whose arguments start the `rest` of the input, as
+    /// `#define g f` does for `g(1)`; that name is expanded with them, while `name`
+    /// is still hidden. Returns how many tokens of `rest` were consumed.
+    fn expand_body(&self, name: &str, body: &[Token], rest: &[Token], site: &Token, expanding: &mut Vec<String>, result: &mut Vec<Token>) -> Result<usize> {
+        let mut next = 0;
+        Self::enter_macro(name, site, expanding)?;
+        let mut expansion = self.expand_list(body, Some(site), expanding)?;
+
+        while expansion.last().is_some_and(|token| self.is_function_like(token, expanding))
+            && rest.get(next).is_some_and(|token| token.kind == TokenKind::LeftParen)
+        {
+            let (_, end) = Self::collect_args(rest, next)?;
+            let mut spliced = vec![expansion.pop().unwrap()];
+            spliced.extend_from_slice(&rest[next..end]);
+            self.expand_at(&spliced, 0, Some(site), expanding, &mut expansion)?;
+            next = end;
+        }
+
+        result.extend(expansion);
+        expanding.pop();
+        Ok(next)
+    }
+
+    /// Check if a token names a function-like macro that is not already being expanded
+    fn is_function_like(&self, token: &Token, expanding: &[String]) -> bool {
+        matches!(&token.kind, TokenKind::Identifier(name)
+            if !expanding.contains(name) && self.macros.get(name).is_some_and(|macro_| macro_.params.is_some()))
+    }
+
+    /// Add a macro to the hide set as its expansion starts, unless expansions are
+    /// already nested too deeply
+    fn enter_macro(name: &str, site: &Token, expanding: &mut Vec<String>) -> Result<()> {
+        if expanding.len() >= MAX_MACRO_DEPTH {
+            return Err(preprocessor_error(
+                &site.location,
+                format!("Expanding macro {} nests more than {} macro expansions", name, MAX_MACRO_DEPTH),
+            ));
+        }
+        expanding.push(name.to_string());
+        Ok(())
+    }
+
+    /// Collect the arguments of a macro invocation whose '(' is at `i`,
+    /// returning them with the index after the closing ')'
+    fn collect_args(tokens: &[Token], mut i: usize) -> Result<(Vec<Vec<Token>>, usize)> {
+        let open = &tokens[i];
+        let mut args = vec![Vec::new()];
+        let mut depth = 0;
+
+        i += 1; // Skip '('
+
+        while i < tokens.len() && tokens[i].kind != TokenKind::Eof {
+            let token = &tokens[i];
+            i += 1;
+
+            match token.kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen if depth == 0 => return Ok((args, i)),
+                TokenKind::RightParen => depth -= 1,
+                // Commas nested in parentheses belong to the argument
+                TokenKind::Comma if depth == 0 => {
+                    args.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+
+            args.last_mut().unwrap().push(token.clone());
+        }
+
+        Err(preprocessor_error(
+            &open.location,
+            "Unterminated macro invocation",
+        ))
+    }
+
+    /// Replace the parameters in a function-like macro's body by their arguments,
+    /// applying the # and ## operators
+    fn substitute(&self, macro_: &Macro, params: &[String], args: &[Vec<Token>], site: &Token, expanding: &mut Vec<String>) -> Result<Vec<Token>> {
+        let param_index = |token: &Token| match &token.kind {
+            TokenKind::Identifier(name) => params.iter().position(|p| p == name),
+            _ => None,
+        };
+        let body = &macro_.body;
+        let mut substituted = Vec::new();
+        let mut j = 0;
+
+        while j < body.len() {
+            let token = &body[j];
+
+            if token.kind == TokenKind::Hash {
+                // #param turns the argument into a string literal
+                let index = body.get(j + 1).and_then(param_index).ok_or_else(|| {
+                    preprocessor_error(&token.location, "'#' is not followed by a macro parameter")
+                })?;
+
+                let text: Vec<String> = args[index].iter().map(|t| t.kind.to_string()).collect();
+                let mut string = token.clone();
+                string.kind = TokenKind::StringLiteral(text.join(" "));
+                substituted.push(string);
+                j += 2;
+            } else if let Some(index) = param_index(token) {
+                // Operands of ## are pasted as written; other arguments are expanded first
+                let pasted = (j > 0 && body[j - 1].kind == TokenKind::HashHash)
+                    || body.get(j + 1).is_some_and(|t| t.kind == TokenKind::HashHash);
+
+                if pasted {
+                    substituted.extend(args[index].iter().cloned());
+                } else {
+                    substituted.extend(self.expand_list(&args[index], Some(site), expanding)?);
+                }
+                j += 1;
+            } else {
+                substituted.push(token.clone());
+                j += 1;
+            }
+        }
+
+        // Join the tokens on either side of each ##
+        let mut result: Vec<Token> = Vec::new();
+        let mut j = 0;
+
+        while j < substituted.len() {
+            if substituted[j].kind == TokenKind::HashHash {
+                match (result.pop(), substituted.get(j + 1)) {
+                    (Some(left), Some(right)) => result.push(Self::paste(&left, right)?),
+                    (Some(left), None) => result.push(left),
+                    (None, Some(right)) => result.push(right.clone()),
+                    (None, None) => {}
+                }
+                j += 2;
+            } else {
+                result.push(substituted[j].clone());
+                j += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Concatenate two tokens into one by re-lexing their joined spelling
+    fn paste(left: &Token, right: &Token) -> Result<Token> {
+        let text = format!("{}{}", left.kind, right.kind);
+        let invalid = || {
+            preprocessor_error(
+                &left.location,
+                format!("Pasting {} and {} does not give a valid token", left.kind, right.kind),
+            )
+        };
+
+        let mut lexer = Lexer::new(&text, left.filename.clone());
+        let tokens = lexer.tokenize().map_err(|_| invalid())?;
+
+        // The lexer ends every token list with Eof
+        match tokens.as_slice() {
+            [token, eof] if eof.kind == TokenKind::Eof => {
+                let mut pasted = left.clone();
+                pasted.kind = token.kind.clone();
+                Ok(pasted)
+            }
+            _ => Err(invalid()),
+        }
+    }
+
     /// Process #include directive
     fn process_include(&mut self, tokens: &[Token], mut i: usize, result: &mut Vec<Token>) -> Result<usize> {
         i += 1; // Skip 'include'
@@ -86,14 +681,17 @@ impl Preprocessor {
 
         let token = &tokens[i];
 
+        // The directive is the rest of its line; the filename cannot continue past it
+        let end = Self::line_end(tokens, i);
+
         let (filename, is_system) = match &token.kind {
-            TokenKind::StringLiteral(name) => (name.clone(), false),
-            TokenKind::LessThan => {
+            TokenKind::StringLiteral(name) if i < end => (literal_text(name), false),
+            TokenKind::LessThan if i < end => {
                 // Parse <filename>
                 i += 1;
                 let mut filename = String::new();
 
-                while i < tokens.len() && tokens[i].kind != TokenKind::GreaterThan {
+                while i < end && tokens[i].kind != TokenKind::GreaterThan {
                     if let TokenKind::Identifier(part) = &tokens[i].kind {
                         filename.push_str(part);
                     } else if let TokenKind::Dot = &tokens[i].kind {
@@ -109,14 +707,13 @@ impl Preprocessor {
                     i += 1;
                 }
 
-                if i >= tokens.len() {
+                if i >= end {
                     return Err(preprocessor_error(
                         &token.location,
                         "Unterminated include filename",
                     ));
                 }
 
-                i += 1; // Skip '>'
                 (filename, true)
             }
             _ => {
@@ -127,56 +724,44 @@ impl Preprocessor {
             }
         };
 
-        // Find the file
-        let file_path = if is_system {
-            // Search in include paths
-            let mut found_path = None;
-            for path in &self.include_paths {
-                let full_path = path.join(&filename);
-                if full_path.exists() {
-                    found_path = Some(full_path);
-                    break;
-                }
-            }
+        // Find the file: a quoted name is first looked for relative to the current
+        // file, then like a system header, in the include paths and finally among
+        // the bundled headers
+        let current_dir = Path::new(&token.filename).parent().unwrap_or_else(|| Path::new(""));
+        let beside_current = Some(current_dir.join(&filename)).filter(|path| !is_system && path.exists());
+        let file_path = beside_current
+            .or_else(|| self.include_paths.iter().map(|path| path.join(&filename)).find(|path| path.exists()));
 
-            found_path.ok_or_else(|| {
-                preprocessor_error(
-                    &token.location,
-                    format!("Cannot find include file: {}", filename),
-                )
-            })?
-        } else {
-            // Relative to current file
-            let current_dir = Path::new(&token.filename).parent().unwrap_or_else(|| Path::new(""));
-            let full_path = current_dir.join(&filename);
-
-            if !full_path.exists() {
-                return Err(preprocessor_error(
-                    &token.location,
-                    format!("Cannot find include file: {}", filename),
-                ));
+        let (content, file_name) = match file_path {
+            Some(file_path) => {
+                let content = fs::read_to_string(&file_path).map_err(|e| {
+                    preprocessor_error(
+                        &token.location,
+                        format!("Failed to read include file: {}", e),
+                    )
+                })?;
+                (content, file_path.to_string_lossy().to_string())
             }
-
-            full_path
+            None => match bundled_header(&filename) {
+                Some(content) => (content.to_string(), format!("include/{}", filename)),
+                None => {
+                    return Err(preprocessor_error(
+                        &token.location,
+                        format!("Cannot find include file: {}", filename),
+                    ));
+                }
+            },
         };
 
-        // Read and preprocess the included file
-        let content = fs::read_to_string(&file_path).map_err(|e| {
-            preprocessor_error(
-                &token.location,
-                format!("Failed to read include file: {}", e),
-            )
-        })?;
-
-        let mut lexer = Lexer::new(&content, file_path.to_string_lossy().to_string());
+        let mut lexer = Lexer::new(&content, file_name).with_nested_comment_warnings(self.nested_comment_warnings);
         let included_tokens = lexer.tokenize()?;
+        self.warnings.extend(lexer.take_warnings());
 
+        // The end of the included file is not the end of the including one
         let preprocessed_tokens = self.preprocess(included_tokens)?;
-        result.extend(preprocessed_tokens);
-
-        // Skip to the next token
-        i += 1;
+        result.extend(preprocessed_tokens.into_iter().filter(|t| t.kind != TokenKind::Eof));
 
-        Ok(i)
+        // Continue with the first token of the next line
+        Ok(end)
     }
 }
@@ -0,0 +1,120 @@
+use clap::ValueEnum;
+
+/// The platform to generate code for, which decides the calling convention,
+/// symbol naming, sections and how the executable is linked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Target {
+    /// Windows x64 ABI, linked with MinGW gcc into a `.exe`
+    Windows,
+    /// System V AMD64 ABI, linked with gcc
+    Linux,
+    /// System V AMD64 ABI with underscore-prefixed symbols, linked with cc
+    Macos,
+}
+
+impl Target {
+    /// The target of the platform the compiler itself runs on
+    pub fn host() -> Self {
+        if cfg!(target_os = "windows") {
+            Target::Windows
+        } else if cfg!(target_os = "macos") {
+            Target::Macos
+        } else {
+            Target::Linux
+        }
+    }
+
+    /// Registers that pass integer and pointer arguments, in order
+    pub fn arg_registers(self) -> &'static [&'static str] {
+        match self {
+            Target::Windows => &["rcx", "rdx", "r8", "r9"],
+            Target::Linux | Target::Macos => &["rdi", "rsi", "rdx", "rcx", "r8", "r9"],
+        }
+    }
+
+    /// Number of XMM registers that pass floating arguments
+    pub fn float_arg_registers(self) -> usize {
+        match self {
+            Target::Windows => 4,
+            Target::Linux | Target::Macos => 8,
+        }
+    }
+
+    /// Whether each argument position has one register of either kind, so the
+    /// third argument always goes in R8 or XMM2 (Windows), rather than integer
+    /// and floating arguments taking the next free register of their own kind
+    pub fn positional_args(self) -> bool {
+        self == Target::Windows
+    }
+
+    /// Whether a struct argument larger than 8 bytes is passed as the address of a
+    /// copy the caller makes (Windows), rather than by value in two registers
+    pub fn structs_by_reference(self) -> bool {
+        self == Target::Windows
+    }
+
+    /// Bytes a caller reserves above the return address for the callee to spill
+    /// its register arguments into
+    pub fn shadow_space(self) -> usize {
+        match self {
+            Target::Windows => 32,
+            Target::Linux | Target::Macos => 0,
+        }
+    }
+
+    /// The assembler name of a global symbol
+    pub fn symbol(self, name: &str) -> String {
+        match self {
+            Target::Macos => format!("_{}", name),
+            Target::Windows | Target::Linux => name.to_string(),
+        }
+    }
+
+    /// The section directive for read-only data such as string literals
+    pub fn rodata_section(self) -> &'static str {
+        match self {
+            Target::Macos => ".section __TEXT,__const",
+            Target::Windows | Target::Linux => ".section .rodata",
+        }
+    }
+
+    /// The section marking the code as not needing an executable stack, which the
+    /// linker otherwise assumes it does (Linux)
+    pub fn stack_note_section(self) -> Option<&'static str> {
+        match self {
+            Target::Linux => Some(".section .note.GNU-stack,\"\",@progbits"),
+            Target::Windows | Target::Macos => None,
+        }
+    }
+
+    /// Whether executables are linked position-independent, so the addresses of
+    /// symbols in shared libraries are only known through the global offset table
+    pub fn position_independent(self) -> bool {
+        match self {
+            Target::Windows => false,
+            Target::Linux | Target::Macos => true,
+        }
+    }
+
+    /// The file name extension of executables, including the dot
+    pub fn executable_extension(self) -> &'static str {
+        match self {
+            Target::Windows => ".exe",
+            Target::Linux | Target::Macos => "",
+        }
+    }
+
+    /// The compiler driver that assembles and links the generated assembly
+    pub fn linker(self) -> &'static str {
+        match self {
+            Target::Windows | Target::Linux => "gcc",
+            Target::Macos => "cc",
+        }
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Target::host()
+    }
+}
@@ -1,18 +1,31 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::ast::{BinaryOp, Node, Type, UnaryOp};
-use crate::error::{type_error, Result};
+use crate::ast::{BinaryOp, Location, Node, StorageClass, Type, TypeMap, UnaryOp};
+use crate::error::{type_error, warning, Result, Warning};
 
 /// Symbol table for tracking variables and their types
 #[derive(Debug, Clone)]
 struct SymbolTable {
     scopes: Vec<HashMap<String, Type>>,
+    /// Every name defined so far, kept after its scope ends, in definition order
+    definitions: Vec<Symbol>,
+}
+
+/// A name defined in some scope, with its type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub type_: Type,
+    /// Depth of the scope the name was defined in; 0 is global, 1 a function's
+    /// parameters and the locals of its body, and each nested block one more
+    pub depth: usize,
 }
 
 impl SymbolTable {
     fn new() -> Self {
         Self {
             scopes: vec![HashMap::new()],
+            definitions: Vec::new(),
         }
     }
 
@@ -28,8 +41,19 @@ impl SymbolTable {
 
     /// Define a variable in the current scope
     fn define(&mut self, name: &str, type_: Type) {
-        if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(name.to_string(), type_);
+        let depth = self.depth();
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+
+        // A name defined again in the same scope, as a function is by its prototype
+        // and then its body, updates the latest definition of it at this depth
+        let redefined = scope.contains_key(name);
+        scope.insert(name.to_string(), type_.clone());
+        let previous = self.definitions.iter_mut().rev().find(|symbol| symbol.name == name && symbol.depth == depth);
+        match previous {
+            Some(symbol) if redefined => symbol.type_ = type_,
+            _ => self.definitions.push(Symbol { name: name.to_string(), type_, depth }),
         }
     }
 
@@ -42,12 +66,52 @@ impl SymbolTable {
         }
         None
     }
+
+    /// Look up a name in the current scope only
+    fn lookup_current(&self, name: &str) -> Option<Type> {
+        self.scopes.last().and_then(|scope| scope.get(name).cloned())
+    }
+
+    /// Find the depth of the innermost scope that defines a variable (0 is global)
+    fn lookup_depth(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rposition(|scope| scope.contains_key(name))
+    }
+
+    /// Get the depth of the current scope
+    fn depth(&self) -> usize {
+        self.scopes.len() - 1
+    }
 }
 
 /// Type checker for C source code
 pub struct TypeChecker {
     symbol_table: SymbolTable,
     current_function_return_type: Option<Type>,
+    /// The last named parameter of the current function, if it is variadic
+    variadic_last_param: Option<String>,
+    types: TypeMap,
+    /// Names of the variables declared at global scope
+    global_variables: HashSet<String>,
+    /// Locals (by name and scope depth) that may not have been assigned yet
+    unassigned: HashSet<(String, usize)>,
+    /// Names of the functions that have been given a body
+    defined_functions: HashSet<String>,
+    /// Names of the global variables that have been given an initializer
+    initialized_globals: HashSet<String>,
+    /// Labels defined in the current function
+    labels: HashSet<String>,
+    /// Gotos in the current function, checked against its labels at the end
+    gotos: Vec<(String, Location)>,
+    /// Whether the subscript about to be checked is the operand of `&`, which may
+    /// point one past the end of an array
+    subscript_address: bool,
+    /// Warnings reported so far, in source order within each function
+    warnings: Vec<Warning>,
+    /// Number of loops around the statement being checked, for break and continue
+    loop_depth: usize,
+    /// Struct and union types with their members, by keyword and tag, to complete
+    /// the ones mentioned before their body was
+    records: HashMap<String, Type>,
 }
 
 impl TypeChecker {
@@ -55,51 +119,357 @@ impl TypeChecker {
         Self {
             symbol_table: SymbolTable::new(),
             current_function_return_type: None,
+            variadic_last_param: None,
+            types: TypeMap::new(),
+            global_variables: HashSet::new(),
+            unassigned: HashSet::new(),
+            defined_functions: HashSet::new(),
+            initialized_globals: HashSet::new(),
+            labels: HashSet::new(),
+            gotos: Vec::new(),
+            subscript_address: false,
+            warnings: Vec::new(),
+            loop_depth: 0,
+            records: HashMap::new(),
         }
     }
 
+    /// Report a warning that does not stop compilation
+    fn warn(&mut self, location: &Location, message: impl Into<String>) {
+        self.warnings.push(warning(location, message));
+    }
+
+    /// Record that a variable has definitely been assigned
+    fn mark_assigned(&mut self, name: &str) {
+        if let Some(depth) = self.symbol_table.lookup_depth(name) {
+            self.unassigned.remove(&(name.to_string(), depth));
+        }
+    }
+
+    /// Check if every path through a statement ends in a return
+    fn always_returns(&self, node: &Node) -> bool {
+        match node {
+            Node::ReturnStmt(_, _) => true,
+            Node::LabelStmt(_, statement, _) => self.always_returns(statement),
+            Node::BlockStmt(statements, _) => statements.iter().any(|stmt| self.always_returns(stmt)),
+            Node::IfStmt {
+                then_branch,
+                else_branch: Some(else_branch),
+                ..
+            } => self.always_returns(then_branch) && self.always_returns(else_branch),
+            // A loop without an exit condition can only be left by returning, or
+            // by breaking out of it
+            Node::ForStmt { condition: None, body, .. } => !self.breaks_out(body),
+            Node::WhileStmt { condition, body, .. } => {
+                matches!(condition.as_ref(), Node::IntLiteral(value, _) if *value != 0) && !self.breaks_out(body)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if a loop body has a break that leaves the loop, rather than one of
+    /// the loops inside it
+    fn breaks_out(&self, node: &Node) -> bool {
+        match node {
+            Node::BreakStmt(_) => true,
+            Node::LabelStmt(_, statement, _) => self.breaks_out(statement),
+            Node::BlockStmt(statements, _) => statements.iter().any(|stmt| self.breaks_out(stmt)),
+            Node::IfStmt { then_branch, else_branch, .. } => {
+                self.breaks_out(then_branch) || else_branch.as_ref().is_some_and(|branch| self.breaks_out(branch))
+            }
+            _ => false,
+        }
+    }
+
+    /// Warn about an assignment used as a condition, which is usually a mistyped
+    /// comparison. Parentheses around the assignment mark it as intended.
+    fn check_condition_assignment(&mut self, condition: &Node, parenthesized: bool) {
+        if let Node::BinaryExpr { op: BinaryOp::Assign, location, .. } = condition {
+            if !parenthesized {
+                self.warn(location, "Assignment used as a condition; use '==' to compare, or add parentheses around the assignment");
+            }
+        }
+    }
+
+    /// Type check the operand of sizeof, which is never evaluated: reading a variable
+    /// there does not use its value, and assigning one does not set it
+    fn check_unevaluated(&mut self, node: &Node) -> Result<Type> {
+        let unassigned = std::mem::take(&mut self.unassigned);
+        let type_ = self.check_node(node);
+        self.unassigned = unassigned;
+        type_
+    }
+
+    /// Type check a node that may not be executed, such as one arm of a branch.
+    /// Variables it leaves unassigned stay unassigned afterwards, and assignments
+    /// it makes do not count as definite.
+    fn check_conditional(&mut self, node: &Node) -> Result<Type> {
+        let before = self.unassigned.clone();
+        let type_ = self.check_node(node)?;
+        self.unassigned.extend(before);
+        Ok(type_)
+    }
+
     /// Check if two types are compatible
     fn is_compatible(&self, left: &Type, right: &Type) -> bool {
-        match (left, right) {
+        // Qualifiers don't change what values a type can hold
+        match (&left.strip_qualifiers(), &right.strip_qualifiers()) {
             (Type::Void, Type::Void) => true,
-            (Type::Char, Type::Char) => true,
-            (Type::Int, Type::Int) => true,
-            (Type::Long, Type::Long) => true,
-            (Type::Int, Type::Char) | (Type::Char, Type::Int) => true,
-            (Type::Long, Type::Int) | (Type::Int, Type::Long) => true,
-            (Type::Long, Type::Char) | (Type::Char, Type::Long) => true,
-            (Type::Pointer(l), Type::Pointer(r)) => self.is_compatible(l, r),
-            (Type::Array(l, _), Type::Array(r, _)) => self.is_compatible(l, r),
-            (Type::Array(l, _), Type::Pointer(r)) | (Type::Pointer(l), Type::Array(r, _)) => {
-                self.is_compatible(l, r)
+            // Arithmetic values convert to each other implicitly
+            (l, r) if l.is_arithmetic() && r.is_arithmetic() => true,
+            // No conversion takes place through a pointer or within an array, so what
+            // is pointed to and the elements must be of the same type
+            (Type::Pointer(l), Type::Pointer(r)) | (Type::Array(l, _), Type::Array(r, _)) => self.is_same_type(l, r),
+            // Structs and unions are identified by their tag
+            (Type::Struct(l, _), Type::Struct(r, _)) | (Type::Union(l, _), Type::Union(r, _)) => l == r,
+            (l @ Type::Function(_, _, _), r @ Type::Function(_, _, _)) => self.is_same_type(l, r),
+            _ => false,
+        }
+    }
+
+    /// Check if a value of one type can be assigned to, or initialize, a target of
+    /// another. Beyond compatible types, a pointer converts to a _Bool telling
+    /// whether it is null, void * converts to and from any object pointer, and a
+    /// null pointer constant such as `0` converts to any pointer.
+    fn is_assignable(&self, target: &Type, value_type: &Type, value: &Node) -> bool {
+        (*target.unqualified() == Type::Bool && self.is_pointer_type(value_type))
+            || (matches!(target.unqualified(), Type::Pointer(_)) && is_null_pointer_constant(value))
+            || (self.is_void_pointer(target) && self.is_object_pointer(value_type))
+            || (self.is_void_pointer(value_type) && self.is_object_pointer(target))
+            || self.is_compatible(target, value_type)
+    }
+
+    /// Check if a type is a pointer to void
+    fn is_void_pointer(&self, type_: &Type) -> bool {
+        matches!(type_.strip_qualifiers(), Type::Pointer(inner) if *inner == Type::Void)
+    }
+
+    /// Check if a type points to an object rather than a function
+    fn is_object_pointer(&self, type_: &Type) -> bool {
+        matches!(type_.strip_qualifiers(), Type::Pointer(inner) if !matches!(*inner, Type::Function(_, _, _)))
+    }
+
+    /// Check the arguments of a call to a function of the given type and return what
+    /// it returns. `callee` describes the function in error messages, after the word
+    /// "function".
+    fn check_call(&mut self, callee: &str, func_type: &Type, args: &[Node], location: &Location) -> Result<Type> {
+        let Type::Function(return_type, param_types, is_variadic) = func_type else {
+            unreachable!("calls are only checked against function types");
+        };
+
+        if !is_variadic && args.len() != param_types.len() {
+            return Err(type_error(
+                location,
+                format!(
+                    "Function {} expects {} arguments, but {} were provided",
+                    callee,
+                    param_types.len(),
+                    args.len()
+                ),
+            ));
+        }
+
+        // Check arguments up to the number of fixed parameters
+        let check_count = param_types.len().min(args.len());
+        for i in 0..check_count {
+            let arg = &args[i];
+            let param_type = &param_types[i];
+            let arg_type = self.check_value(arg, "an argument")?;
+            if !self.is_assignable(param_type, &arg_type, arg) {
+                return Err(type_error(
+                    location,
+                    format!(
+                        "Argument {} has type {:?}, but function {} expects {:?}",
+                        i + 1,
+                        arg_type,
+                        callee,
+                        param_type
+                    ),
+                ));
             }
+        }
+
+        // Variable arguments can have any type, but must have a value
+        for arg in &args[check_count..] {
+            self.check_value(arg, "an argument")?;
+        }
+
+        Ok(*return_type.clone())
+    }
+
+    /// Type check an expression whose value is used, which a call to a function
+    /// returning void does not have. `context` says how the value is used. An
+    /// array used as a value decays to a pointer to its first element; the type
+    /// recorded for the expression itself stays the array's.
+    fn check_value(&mut self, node: &Node, context: &str) -> Result<Type> {
+        let type_ = self.check_node(node)?;
+        if matches!(type_.unqualified(), Type::Void) {
+            return Err(type_error(
+                node.location().unwrap(),
+                format!("Void value used as {}", context),
+            ));
+        }
+        Ok(type_.decay())
+    }
+
+    /// Check if an expression designates a function rather than an object: a
+    /// function's name, or a dereferenced function pointer. Either stands for the
+    /// function's address.
+    fn is_function_designator(&self, node: &Node) -> bool {
+        match node {
+            Node::Identifier(name, _) => matches!(self.symbol_table.lookup(name), Some(Type::Function(_, _, _))),
+            Node::UnaryExpr { op: UnaryOp::Dereference, expr, .. } => {
+                self.types.get(expr).is_some_and(Type::is_function_pointer)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if two types are the same type. Unlike `is_compatible`, no implicit
+    /// conversion is allowed; structs and unions only need the same tag, so a
+    /// declaration without the member list still matches.
+    fn is_same_type(&self, left: &Type, right: &Type) -> bool {
+        match (left, right) {
+            (Type::Const(l), Type::Const(r)) | (Type::Volatile(l), Type::Volatile(r)) => self.is_same_type(l, r),
+            (Type::Pointer(l), Type::Pointer(r)) => self.is_same_type(l, r),
+            (Type::Array(l, l_size), Type::Array(r, r_size)) => l_size == r_size && self.is_same_type(l, r),
+            (Type::Struct(l, _), Type::Struct(r, _)) | (Type::Union(l, _), Type::Union(r, _)) => l == r,
             (Type::Function(l_ret, l_params, l_variadic), Type::Function(r_ret, r_params, r_variadic)) => {
-                self.is_compatible(l_ret, r_ret)
+                // Qualifiers on a parameter itself do not change the function's type
+                self.is_same_type(l_ret.unqualified(), r_ret.unqualified())
                     && l_params.len() == r_params.len()
                     && l_variadic == r_variadic
                     && l_params
                         .iter()
                         .zip(r_params.iter())
-                        .all(|(l, r)| self.is_compatible(l, r))
+                        .all(|(l, r)| self.is_same_type(l.unqualified(), r.unqualified()))
             }
-            _ => false,
+            (l, r) => l == r,
         }
     }
 
     /// Check if a type is an integer type
     fn is_integer_type(&self, type_: &Type) -> bool {
-        matches!(type_, Type::Char | Type::Int | Type::Long)
+        type_.is_integer()
     }
 
     /// Check if a type is a pointer type
     fn is_pointer_type(&self, type_: &Type) -> bool {
-        matches!(type_, Type::Pointer(_) | Type::Array(_, _))
+        matches!(type_.unqualified(), Type::Pointer(_) | Type::Array(_, _))
+    }
+
+    /// Find the type of a conditional expression from the types of its arms. Arithmetic
+    /// arms are converted to their common type, as the operands of `+` are; a null
+    /// pointer constant takes the type of a pointer in the other arm, and void *
+    /// wins over a pointer to an object. Otherwise both arms must have the same
+    /// struct, union or pointer type, or both be void.
+    fn conditional_type(&self, then_type: &Type, else_type: &Type, then_expr: &Node, else_expr: &Node, location: &Location) -> Result<Type> {
+        let (l, r) = (then_type.unqualified(), else_type.unqualified());
+        let else_type_wins = (is_null_pointer_constant(then_expr) && self.is_pointer_type(r))
+            || (self.is_object_pointer(l) && self.is_void_pointer(r) && !is_null_pointer_constant(else_expr));
+        let then_type_wins = (self.is_pointer_type(l) && is_null_pointer_constant(else_expr))
+            || (self.is_void_pointer(l) && self.is_object_pointer(r))
+            || (self.is_pointer_type(l) && self.is_pointer_type(r) && self.is_compatible(l, r))
+            || (l.is_record() && r.is_record() && self.is_same_type(l, r));
+
+        if l.is_arithmetic() && r.is_arithmetic() {
+            Ok(Type::arithmetic_type(l, r))
+        } else if matches!((l, r), (Type::Void, Type::Void)) {
+            Ok(Type::Void)
+        } else if else_type_wins {
+            Ok(r.clone())
+        } else if then_type_wins {
+            Ok(l.clone())
+        } else {
+            Err(type_error(
+                location,
+                format!("Incompatible operand types in conditional expression: {} and {}", then_type, else_type),
+            ))
+        }
+    }
+
+    /// Check that sizeof can be applied to a type; the result is an unsigned long
+    fn check_sizeof(&self, type_: &Type, location: &Location) -> Result<Type> {
+        match type_.unqualified() {
+            Type::Void | Type::Function(_, _, _) | Type::Array(_, None) => Err(type_error(
+                location,
+                format!("Invalid application of sizeof to type {:?}", type_),
+            )),
+            _ => Ok(Type::ULong),
+        }
+    }
+
+    /// Check a subscript `*(left + right)`: one operand must be an array or pointer
+    /// and the other an integer. The sum is recorded with the pointer's type, and a
+    /// constant index outside an array of known size is warned about.
+    fn check_subscript(&mut self, sum: &Node, left: &Node, right: &Node, location: &Location) -> Result<Type> {
+        let address_only = std::mem::take(&mut self.subscript_address);
+        let left_type = self.check_node(left)?;
+        let right_type = self.check_node(right)?;
+
+        // Either operand may be the array, as in i[a]
+        let (base_type, index, index_type) = if self.is_pointer_type(&left_type) {
+            (left_type, right, right_type)
+        } else if self.is_pointer_type(&right_type) {
+            (right_type, left, left_type)
+        } else {
+            return Err(type_error(
+                location,
+                format!("Subscripted value is not an array or pointer: {}", left_type),
+            ));
+        };
+        if !self.is_integer_type(&index_type) {
+            return Err(type_error(
+                location,
+                format!("Array subscript is not an integer: {}", index_type),
+            ));
+        }
+
+        if let (Type::Array(_, Some(size)), Node::IntLiteral(value, _)) = (base_type.unqualified(), index) {
+            // Taking the address one past the end is allowed
+            let limit = if address_only { *size as i64 } else { *size as i64 - 1 };
+            if *value < 0 || *value > limit {
+                self.warn(
+                    location,
+                    format!("Array index {} is out of bounds for an array of {} elements", value, size),
+                );
+            }
+        }
+
+        let base_type = base_type.unqualified().clone();
+        self.types.insert(sum, base_type.clone());
+        match base_type {
+            Type::Pointer(inner) | Type::Array(inner, _) => Ok(*inner),
+            _ => unreachable!("subscripted value was checked to be a pointer"),
+        }
+    }
+
+    /// Check that an operand of va_start, va_arg or va_end is a va_list
+    fn check_va_list(&mut self, list: &Node, location: &Location) -> Result<()> {
+        let list_type = self.check_node(list)?;
+        if !self.is_same_type(list_type.unqualified(), &Type::Pointer(Box::new(Type::Char))) {
+            return Err(type_error(
+                location,
+                format!("Expected a va_list, found {}", list_type),
+            ));
+        }
+        Ok(())
     }
 
     /// Type check a program
     pub fn check_program(&mut self, program: &Node) -> Result<()> {
         match program {
             Node::Program(declarations) => {
+                // Every function is known before any body is checked, so a function
+                // can be called above its definition, as mutually recursive ones are
+                for decl in declarations {
+                    if let Node::FunctionDecl { name, return_type, params, .. } = decl {
+                        if self.symbol_table.lookup(name).is_none() {
+                            self.symbol_table.define(name, function_type(return_type, params));
+                        }
+                    }
+                }
+
                 for decl in declarations {
                     self.check_node(decl)?;
                 }
@@ -109,20 +479,121 @@ impl TypeChecker {
         }
     }
 
-    /// Type check a node
+    /// Take the types computed for the nodes of the last checked program
+    pub fn take_types(&mut self) -> TypeMap {
+        std::mem::take(&mut self.types)
+    }
+
+    /// Take the warnings reported while checking
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// The names defined while checking, in every scope entered, in the order
+    /// they were defined
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbol_table.definitions
+    }
+
+    /// Remember the members of the structs and unions a declared type mentions
+    fn record_tags(&mut self, type_: &Type) {
+        match type_ {
+            Type::Struct(name, members) | Type::Union(name, members) if !name.is_empty() && !members.is_empty() => {
+                for (_, member_type) in members {
+                    self.record_tags(member_type);
+                }
+                self.records.insert(type_.to_string(), type_.clone());
+            }
+            Type::Pointer(inner) | Type::Array(inner, _) | Type::Const(inner) | Type::Volatile(inner) => {
+                self.record_tags(inner)
+            }
+            Type::Function(return_type, params, _) => {
+                self.record_tags(return_type);
+                for param in params {
+                    self.record_tags(param);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Give a struct or union that was mentioned before its body, such as the
+    /// `next` pointer within a list node, the members it was defined with. Only
+    /// the outer type is completed, so a self-referential type stays finite.
+    fn complete(&self, type_: Type) -> Type {
+        match type_ {
+            Type::Struct(_, ref members) | Type::Union(_, ref members) if members.is_empty() => {
+                self.records.get(&type_.to_string()).cloned().unwrap_or(type_)
+            }
+            Type::Pointer(inner) => Type::Pointer(Box::new(self.complete(*inner))),
+            Type::Array(inner, size) => Type::Array(Box::new(self.complete(*inner)), size),
+            Type::Const(inner) => Type::Const(Box::new(self.complete(*inner))),
+            Type::Volatile(inner) => Type::Volatile(Box::new(self.complete(*inner))),
+            other => other,
+        }
+    }
+
+    /// Type check a node and record its type for later phases. Incomplete
+    /// struct types are completed, so later phases can find their members.
     fn check_node(&mut self, node: &Node) -> Result<Type> {
+        let type_ = self.check_node_type(node)?;
+        let type_ = self.complete(type_);
+        self.types.insert(node, type_.clone());
+        Ok(type_)
+    }
+
+    /// Compute the type of a node
+    fn check_node_type(&mut self, node: &Node) -> Result<Type> {
         match node {
-            Node::IntLiteral(_, _) => Ok(Type::Int),
-            Node::CharLiteral(_, _) => Ok(Type::Char),
+            Node::IntLiteral(value, _) => {
+                if i32::try_from(*value).is_ok() {
+                    Ok(Type::Int)
+                } else {
+                    Ok(Type::Long)
+                }
+            }
+            Node::UIntLiteral(value, _) => {
+                if u32::try_from(*value).is_ok() {
+                    Ok(Type::UInt)
+                } else {
+                    Ok(Type::ULong)
+                }
+            }
+            Node::LongLiteral(_, _) => Ok(Type::Long),
+            Node::ULongLiteral(_, _) => Ok(Type::ULong),
+            Node::FloatLiteral(_, _) => Ok(Type::Double),
+            // A character constant has type int, as in C
+            Node::CharLiteral(_, _) => Ok(Type::Int),
             Node::StringLiteral(_, _location) => {
                 Ok(Type::Pointer(Box::new(Type::Char)))
             }
             Node::Identifier(name, location) => {
                 if let Some(type_) = self.symbol_table.lookup(name) {
+                    // A function's name stands for its address
+                    if let Type::Function(_, _, _) = type_ {
+                        return Ok(Type::Pointer(Box::new(type_)));
+                    }
+
+                    let depth = self.symbol_table.lookup_depth(name).unwrap_or(0);
+
+                    if depth == 0 && !self.global_variables.contains(name) {
+                        return Err(type_error(
+                            location,
+                            format!("{} does not name a declared global variable", name),
+                        ));
+                    }
+
+                    if self.unassigned.contains(&(name.clone(), depth)) {
+                        self.warn(
+                            location,
+                            format!("Variable {} may be used uninitialized", name),
+                        );
+                    }
+
                     Ok(type_)
                 } else {
                     Err(type_error(
-                        &location,
+                        location,
                         format!("Undefined variable: {}", name),
                     ))
                 }
@@ -133,27 +604,52 @@ impl TypeChecker {
                 right,
                 location,
             } => {
-                let left_type = self.check_node(left)?;
-                let right_type = self.check_node(right)?;
+                let (left_type, right_type) = match (op, left.as_ref()) {
+                    (BinaryOp::Assign, Node::Identifier(name, _)) => {
+                        // The assigned variable is written, not read
+                        let right_type = self.check_value(right, "an assigned value")?;
+                        self.mark_assigned(name);
+                        (self.check_node(left)?, right_type)
+                    }
+                    (BinaryOp::Assign, Node::MemberAccess { .. }) => {
+                        // Writing a member of a struct variable counts as initializing it
+                        let right_type = self.check_value(right, "an assigned value")?;
+                        let mut base = left.as_ref();
+                        while let Node::MemberAccess { expr, .. } = base {
+                            base = expr;
+                        }
+                        if let Node::Identifier(name, _) = base {
+                            self.mark_assigned(name);
+                        }
+                        (self.check_node(left)?, right_type)
+                    }
+                    (BinaryOp::LogicalAnd | BinaryOp::LogicalOr, _) => {
+                        // The right operand is only evaluated depending on the left
+                        let left_type = self.check_value(left, "an operand")?;
+                        let right_type = self.check_conditional(right)?;
+                        if matches!(right_type.unqualified(), Type::Void) {
+                            return Err(type_error(right.location().unwrap(), "Void value used as an operand"));
+                        }
+                        (left_type, right_type)
+                    }
+                    (BinaryOp::Assign, _) => (self.check_node(left)?, self.check_value(right, "an assigned value")?),
+                    _ => (self.check_value(left, "an operand")?, self.check_value(right, "an operand")?),
+                };
 
                 match op {
                     BinaryOp::Add => {
-                        if self.is_integer_type(&left_type) && self.is_integer_type(&right_type) {
-                            // Integer addition
-                            if matches!(left_type, Type::Long) || matches!(right_type, Type::Long) {
-                                Ok(Type::Long)
-                            } else {
-                                Ok(Type::Int)
-                            }
+                        if left_type.is_arithmetic() && right_type.is_arithmetic() {
+                            // Arithmetic addition
+                            Ok(Type::arithmetic_type(&left_type, &right_type))
                         } else if self.is_pointer_type(&left_type) && self.is_integer_type(&right_type) {
-                            // Pointer arithmetic
-                            Ok(left_type)
+                            // Pointer arithmetic, which yields a pointer even from an array
+                            Ok(left_type.unqualified().decay())
                         } else if self.is_integer_type(&left_type) && self.is_pointer_type(&right_type) {
                             // Pointer arithmetic
-                            Ok(right_type)
+                            Ok(right_type.unqualified().decay())
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Invalid operands for addition: {:?} and {:?}",
                                     left_type, right_type
@@ -162,22 +658,18 @@ impl TypeChecker {
                         }
                     }
                     BinaryOp::Subtract => {
-                        if self.is_integer_type(&left_type) && self.is_integer_type(&right_type) {
-                            // Integer subtraction
-                            if matches!(left_type, Type::Long) || matches!(right_type, Type::Long) {
-                                Ok(Type::Long)
-                            } else {
-                                Ok(Type::Int)
-                            }
+                        if left_type.is_arithmetic() && right_type.is_arithmetic() {
+                            // Arithmetic subtraction
+                            Ok(Type::arithmetic_type(&left_type, &right_type))
                         } else if self.is_pointer_type(&left_type) && self.is_integer_type(&right_type) {
                             // Pointer arithmetic
-                            Ok(left_type)
+                            Ok(left_type.unqualified().decay())
                         } else if self.is_pointer_type(&left_type) && self.is_pointer_type(&right_type) {
                             // Pointer subtraction
                             Ok(Type::Int)
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Invalid operands for subtraction: {:?} and {:?}",
                                     left_type, right_type
@@ -186,16 +678,19 @@ impl TypeChecker {
                         }
                     }
                     BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => {
-                        if self.is_integer_type(&left_type) && self.is_integer_type(&right_type) {
-                            // Integer multiplication/division/modulo
-                            if matches!(left_type, Type::Long) || matches!(right_type, Type::Long) {
-                                Ok(Type::Long)
-                            } else {
-                                Ok(Type::Int)
-                            }
+                        // Modulo is only defined on integers
+                        let operands_valid = if let BinaryOp::Modulo = op {
+                            self.is_integer_type(&left_type) && self.is_integer_type(&right_type)
+                        } else {
+                            left_type.is_arithmetic() && right_type.is_arithmetic()
+                        };
+
+                        if operands_valid {
+                            // Multiplication/division/modulo
+                            Ok(Type::arithmetic_type(&left_type, &right_type))
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Invalid operands for arithmetic operation: {:?} and {:?}",
                                     left_type, right_type
@@ -204,11 +699,17 @@ impl TypeChecker {
                         }
                     }
                     BinaryOp::Equal | BinaryOp::NotEqual => {
-                        if self.is_compatible(&left_type, &right_type) {
+                        // A pointer compares with the null pointer constant, and with
+                        // any pointer to an object through void *
+                        let null_comparison = (self.is_pointer_type(&left_type) && is_null_pointer_constant(right))
+                            || (is_null_pointer_constant(left) && self.is_pointer_type(&right_type));
+                        let void_pointer_comparison = (self.is_void_pointer(&left_type) && self.is_object_pointer(&right_type))
+                            || (self.is_object_pointer(&left_type) && self.is_void_pointer(&right_type));
+                        if self.is_compatible(&left_type, &right_type) || null_comparison || void_pointer_comparison {
                             Ok(Type::Int)
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Invalid operands for comparison: {:?} and {:?}",
                                     left_type, right_type
@@ -217,13 +718,25 @@ impl TypeChecker {
                         }
                     }
                     BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => {
-                        if (self.is_integer_type(&left_type) && self.is_integer_type(&right_type))
+                        // a < b < c compares the 0 or 1 result of a < b with c
+                        if let Node::BinaryExpr {
+                            op: BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual,
+                            ..
+                        } = left.as_ref()
+                        {
+                            self.warn(
+                                location,
+                                "Comparisons like 'a < b < c' do not have their mathematical meaning",
+                            );
+                        }
+
+                        if (left_type.is_arithmetic() && right_type.is_arithmetic())
                             || (self.is_pointer_type(&left_type) && self.is_pointer_type(&right_type))
                         {
                             Ok(Type::Int)
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Invalid operands for comparison: {:?} and {:?}",
                                     left_type, right_type
@@ -237,14 +750,15 @@ impl TypeChecker {
                     }
                     BinaryOp::BitwiseAnd | BinaryOp::BitwiseOr | BinaryOp::BitwiseXor | BinaryOp::ShiftLeft | BinaryOp::ShiftRight => {
                         if self.is_integer_type(&left_type) && self.is_integer_type(&right_type) {
-                            if matches!(left_type, Type::Long) || matches!(right_type, Type::Long) {
-                                Ok(Type::Long)
+                            if matches!(op, BinaryOp::ShiftLeft | BinaryOp::ShiftRight) {
+                                // The result of a shift has the promoted type of the left operand
+                                Ok(left_type.promote())
                             } else {
-                                Ok(Type::Int)
+                                Ok(Type::arithmetic_type(&left_type, &right_type))
                             }
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Invalid operands for bitwise operation: {:?} and {:?}",
                                     left_type, right_type
@@ -253,11 +767,20 @@ impl TypeChecker {
                         }
                     }
                     BinaryOp::Assign => {
-                        if self.is_compatible(&left_type, &right_type) {
+                        if self.is_function_designator(left) {
+                            Err(type_error(location, "Cannot assign to a function"))
+                        } else if !is_lvalue(left) {
+                            Err(type_error(location, "Cannot assign to an expression that is not an lvalue"))
+                        } else if left_type.is_const() {
+                            Err(type_error(
+                                location,
+                                format!("Cannot assign to const-qualified lvalue of type {:?}", left_type),
+                            ))
+                        } else if self.is_assignable(&left_type, &right_type, right) {
                             Ok(left_type)
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Cannot assign value of type {:?} to variable of type {:?}",
                                     right_type, left_type
@@ -272,16 +795,46 @@ impl TypeChecker {
                 expr,
                 location,
             } => {
-                let expr_type = self.check_node(expr)?;
+                // a[i] is parsed as *(a + i), so a dereferenced sum is checked as a subscript
+                if let (UnaryOp::Dereference, Node::BinaryExpr { op: BinaryOp::Add, left, right, .. }) = (op, expr.as_ref()) {
+                    return self.check_subscript(expr, left, right, location);
+                }
+
+                if matches!(op, UnaryOp::AddressOf) && !is_lvalue(expr) {
+                    return Err(type_error(location, "Cannot take the address of an expression that is not an lvalue"));
+                }
+                if let UnaryOp::AddressOf = op {
+                    self.subscript_address = matches!(
+                        expr.as_ref(),
+                        Node::UnaryExpr { op: UnaryOp::Dereference, expr, .. } if matches!(expr.as_ref(), Node::BinaryExpr { op: BinaryOp::Add, .. })
+                    );
+
+                    // Once its address escapes, the variable may be assigned through a
+                    // pointer, and so may a struct whose member address escapes
+                    let mut base = expr.as_ref();
+                    while let Node::MemberAccess { expr, .. } = base {
+                        base = expr;
+                    }
+                    if let Node::Identifier(name, _) = base {
+                        self.mark_assigned(name);
+                    }
+                }
+
+                // The operand of & is not used as a value, so &a of an array a
+                // points to the whole array
+                let expr_type = match op {
+                    UnaryOp::AddressOf => self.check_node(expr)?,
+                    _ => self.check_value(expr, "an operand")?,
+                };
 
                 match op {
                     UnaryOp::Negate => {
-                        if self.is_integer_type(&expr_type) {
-                            Ok(expr_type)
+                        if expr_type.is_arithmetic() {
+                            Ok(expr_type.promote())
                         } else {
                             Err(type_error(
-                                &location,
-                                format!("Cannot negate non-integer type: {:?}", expr_type),
+                                location,
+                                format!("Cannot negate non-arithmetic type: {:?}", expr_type),
                             ))
                         }
                     }
@@ -291,28 +844,38 @@ impl TypeChecker {
                     }
                     UnaryOp::BitwiseNot => {
                         if self.is_integer_type(&expr_type) {
-                            Ok(expr_type)
+                            Ok(expr_type.promote())
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!("Cannot apply bitwise not to non-integer type: {:?}", expr_type),
                             ))
                         }
                     }
                     UnaryOp::Dereference => {
-                        if let Type::Pointer(inner) = expr_type {
-                            Ok(*inner)
-                        } else if let Type::Array(inner, _) = expr_type {
-                            Ok(*inner)
+                        // The function a pointer points to stands for its address again
+                        if expr_type.is_function_pointer() {
+                            Ok(expr_type.unqualified().clone())
+                        } else if matches!(expr_type.unqualified(), Type::Pointer(inner) if inner.unqualified() == &Type::Void) {
+                            Err(type_error(location, "Cannot dereference a pointer to void"))
+                        } else if let Type::Pointer(inner) = expr_type.unqualified() {
+                            Ok(*inner.clone())
+                        } else if let Type::Array(inner, _) = expr_type.unqualified() {
+                            Ok(*inner.clone())
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!("Cannot dereference non-pointer type: {:?}", expr_type),
                             ))
                         }
                     }
                     UnaryOp::AddressOf => {
-                        Ok(Type::Pointer(Box::new(expr_type)))
+                        // A function already stands for its address
+                        if self.is_function_designator(expr) {
+                            Ok(expr_type)
+                        } else {
+                            Ok(Type::Pointer(Box::new(expr_type)))
+                        }
                     }
                 }
             }
@@ -321,75 +884,167 @@ impl TypeChecker {
                 args,
                 location,
             } => {
-                if let Some(func_type) = self.symbol_table.lookup(name) {
-                    if let Type::Function(return_type, param_types, is_variadic) = func_type {
-                        if !is_variadic && args.len() != param_types.len() {
-                            return Err(type_error(
-                                &location,
-                                format!(
-                                    "Function {} expects {} arguments, but {} were provided",
-                                    name,
-                                    param_types.len(),
-                                    args.len()
-                                ),
-                            ));
-                        }
-
-                        // Check arguments up to the number of fixed parameters
-                        let check_count = param_types.len().min(args.len());
-                        for i in 0..check_count {
-                            let arg = &args[i];
-                            let param_type = &param_types[i];
-                            let arg_type = self.check_node(arg)?;
-                            if !self.is_compatible(&arg_type, param_type) {
-                                return Err(type_error(
-                                    &location,
-                                    format!(
-                                        "Argument {} has type {:?}, but function {} expects {:?}",
-                                        i + 1,
-                                        arg_type,
-                                        name,
-                                        param_type
-                                    ),
-                                ));
-                            }
-                        }
-
-                        Ok(*return_type)
-                    } else {
-                        Err(type_error(
-                            &location,
-                            format!("{} is not a function", name),
-                        ))
+                // The name may be a function, or a variable pointing to one
+                match self.symbol_table.lookup(name) {
+                    Some(func_type @ Type::Function(_, _, _)) => {
+                        self.check_call(name, &func_type, args, location)
                     }
-                } else {
-                    Err(type_error(
-                        &location,
+                    Some(type_) if type_.is_function_pointer() => {
+                        let Type::Pointer(func_type) = type_.strip_qualifiers() else { unreachable!() };
+                        self.check_node(&Node::Identifier(name.clone(), location.clone()))?;
+                        self.check_call(&format!("pointer {}", name), &func_type, args, location)
+                    }
+                    Some(_) => Err(type_error(
+                        location,
+                        format!("{} is not a function", name),
+                    )),
+                    None => Err(type_error(
+                        location,
                         format!("Undefined function: {}", name),
-                    ))
+                    )),
+                }
+            }
+            Node::IndirectCall { callee, args, location } => {
+                let callee_type = self.check_node(callee)?;
+                match callee_type.strip_qualifiers() {
+                    Type::Pointer(func_type) if matches!(*func_type, Type::Function(_, _, _)) => {
+                        self.check_call("called through a pointer", &func_type, args, location)
+                    }
+                    _ => Err(type_error(
+                        location,
+                        format!("Called object of type {} is not a function or function pointer", callee_type),
+                    )),
                 }
             }
+            Node::CommaExpr { left, right, .. } => {
+                // The left operand is evaluated only for its side effects, and the
+                // right one is a value, so an array there is a pointer
+                self.check_node(left)?;
+                Ok(self.check_node(right)?.decay())
+            }
+            Node::ConditionalExpr { condition, then_expr, else_expr, location } => {
+                self.check_value(condition, "a condition")?;
+
+                // Only one arm is evaluated, so a variable is only assigned after
+                // the expression if both arms assign it
+                let before = self.unassigned.clone();
+                let then_type = self.check_node(then_expr)?.decay();
+                let after_then = std::mem::replace(&mut self.unassigned, before);
+                let else_type = self.check_node(else_expr)?.decay();
+                self.unassigned.extend(after_then);
+
+                self.conditional_type(&then_type, &else_type, then_expr, else_expr, location)
+            }
+            Node::MemberAccess { expr, member, location } => {
+                let struct_type = self.check_node(expr)?;
+
+                if !struct_type.is_record() {
+                    return Err(type_error(
+                        location,
+                        format!("Member access on non-struct type: {:?}", struct_type),
+                    ));
+                }
+
+                struct_type.member_type(member).ok_or_else(|| {
+                    let kind = match struct_type.unqualified() {
+                        Type::Union(_, _) => "Union",
+                        _ => "Struct",
+                    };
+                    type_error(location, format!("{} has no member named {}", kind, member))
+                })
+            }
+            Node::SizeofExpr(expr, location) => {
+                let expr_type = self.check_unevaluated(expr)?;
+                self.check_sizeof(&expr_type, location)
+            }
+            Node::SizeofType(type_, location) => self.check_sizeof(type_, location),
+            Node::Cast { type_, expr, location } => {
+                // Any value can be cast to void and discarded
+                if matches!(type_.unqualified(), Type::Void) {
+                    self.check_node(expr)?;
+                    return Ok(Type::Void);
+                }
+
+                // Otherwise a scalar converts to another scalar, but a pointer does
+                // not convert to or from a floating value
+                let expr_type = self.check_value(expr, "an operand")?;
+                let is_scalar = |type_: &Type| type_.is_arithmetic() || self.is_pointer_type(type_);
+                let is_function = matches!(expr_type.unqualified(), Type::Function(_, _, _));
+                let valid = is_scalar(type_)
+                    && (is_scalar(&expr_type) || is_function)
+                    && !(self.is_pointer_type(type_) && expr_type.is_floating())
+                    && !(type_.is_floating() && (self.is_pointer_type(&expr_type) || is_function))
+                    && !matches!(type_.unqualified(), Type::Array(_, _));
+                if !valid {
+                    return Err(type_error(
+                        location,
+                        format!("Invalid cast from {:?} to {:?}", expr_type, type_),
+                    ));
+                }
+
+                // The result is a value, not the object it was read from, so it is
+                // not qualified
+                Ok(type_.unqualified().clone())
+            }
+            Node::VaStart(list, last, location) => {
+                // va_start initializes its va_list
+                if let Node::Identifier(name, _) = list.as_ref() {
+                    self.mark_assigned(name);
+                }
+                self.check_va_list(list, location)?;
+
+                match &self.variadic_last_param {
+                    None => Err(type_error(
+                        location,
+                        "va_start used in a function with fixed arguments",
+                    )),
+                    Some(param) if param != last => Err(type_error(
+                        location,
+                        format!("The second argument of va_start must be the last named parameter, {}", param),
+                    )),
+                    Some(_) => Ok(Type::Void),
+                }
+            }
+            Node::VaArg(list, type_, location) => {
+                self.check_va_list(list, location)?;
+
+                // Arguments are read from 8-byte integer slots; floating arguments
+                // are passed in registers that are not saved yet
+                if !type_.is_integer() && !self.is_pointer_type(type_) {
+                    return Err(type_error(
+                        location,
+                        format!("va_arg cannot read an argument of type {}", type_),
+                    ));
+                }
+                Ok(type_.clone())
+            }
+            Node::VaEnd(list, location) => {
+                self.check_va_list(list, location)?;
+                Ok(Type::Void)
+            }
             Node::ExpressionStmt(expr) => {
                 self.check_node(expr)?;
                 Ok(Type::Void)
             }
+            Node::EmptyStmt(_) => Ok(Type::Void),
             Node::ReturnStmt(value, location) => {
                 let current_return_type = match &self.current_function_return_type {
                     Some(rt) => rt.clone(),
                     None => return Err(type_error(
-                        &location,
+                        location,
                         "Return statement outside of function",
                     )),
                 };
 
-                match value {
+                // Nothing after a return is reachable on this path
+                let result = match value {
                     Some(expr) => {
-                        let expr_type = self.check_node(expr)?;
-                        if self.is_compatible(&expr_type, &current_return_type) {
+                        let expr_type = self.check_node(expr)?.decay();
+                        if self.is_assignable(&current_return_type, &expr_type, expr) {
                             Ok(Type::Void)
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Cannot return value of type {:?} from function with return type {:?}",
                                     expr_type, current_return_type
@@ -402,7 +1057,7 @@ impl TypeChecker {
                             Ok(Type::Void)
                         } else {
                             Err(type_error(
-                                &location,
+                                location,
                                 format!(
                                     "Cannot return void from function with return type {:?}",
                                     current_return_type
@@ -410,37 +1065,60 @@ impl TypeChecker {
                             ))
                         }
                     }
-                }
+                };
+
+                self.unassigned.clear();
+                result
             }
             Node::IfStmt {
                 condition,
                 then_branch,
                 else_branch,
+                parenthesized,
                 location: _,
             } => {
-                self.check_node(condition)?;
+                self.check_value(condition, "a condition")?;
+                self.check_condition_assignment(condition, *parenthesized);
+                let before = self.unassigned.clone();
 
                 self.symbol_table.enter_scope();
                 self.check_node(then_branch)?;
                 self.symbol_table.exit_scope();
+                let after_then = std::mem::replace(&mut self.unassigned, before);
 
-                if let Some(else_branch) = else_branch {
-                    self.symbol_table.enter_scope();
-                    self.check_node(else_branch)?;
-                    self.symbol_table.exit_scope();
+                // An `else if` opens scopes for its own branches, so a chain of them
+                // stays at the depth of its first `if` rather than nesting deeper
+                // with each link
+                match else_branch.as_deref() {
+                    Some(else_if @ Node::IfStmt { .. }) => {
+                        self.check_node(else_if)?;
+                    }
+                    Some(else_branch) => {
+                        self.symbol_table.enter_scope();
+                        self.check_node(else_branch)?;
+                        self.symbol_table.exit_scope();
+                    }
+                    None => {}
                 }
 
+                // A variable is only assigned after the if if both paths assign it
+                self.unassigned.extend(after_then);
+
                 Ok(Type::Void)
             }
             Node::WhileStmt {
                 condition,
                 body,
+                parenthesized,
                 location: _,
             } => {
-                self.check_node(condition)?;
+                self.check_value(condition, "a condition")?;
+                self.check_condition_assignment(condition, *parenthesized);
 
                 self.symbol_table.enter_scope();
-                self.check_node(body)?;
+                self.loop_depth += 1;
+                self.check_conditional(body)?;
+                self.loop_depth -= 1;
                 self.symbol_table.exit_scope();
 
                 Ok(Type::Void)
@@ -459,14 +1137,21 @@ impl TypeChecker {
                 }
 
                 if let Some(condition) = condition {
-                    self.check_node(condition)?;
+                    self.check_value(condition, "a condition")?;
                 }
 
+                // The body and increment may run zero times
+                let before = self.unassigned.clone();
+
+                self.loop_depth += 1;
+                self.check_node(body)?;
+                self.loop_depth -= 1;
+
                 if let Some(increment) = increment {
                     self.check_node(increment)?;
                 }
 
-                self.check_node(body)?;
+                self.unassigned.extend(before);
 
                 self.symbol_table.exit_scope();
 
@@ -483,17 +1168,115 @@ impl TypeChecker {
 
                 Ok(Type::Void)
             }
+            Node::LabelStmt(name, statement, location) => {
+                if !self.labels.insert(name.clone()) {
+                    return Err(type_error(
+                        location,
+                        format!("Duplicate label: {}", name),
+                    ));
+                }
+
+                self.check_node(statement)
+            }
+            Node::GotoStmt(name, location) => {
+                self.gotos.push((name.clone(), location.clone()));
+
+                // Nothing after a goto is reachable on this path
+                self.unassigned.clear();
+
+                Ok(Type::Void)
+            }
+            Node::BreakStmt(location) | Node::ContinueStmt(location) => {
+                if self.loop_depth == 0 {
+                    let keyword = if matches!(node, Node::BreakStmt(_)) { "break" } else { "continue" };
+                    return Err(type_error(
+                        location,
+                        format!("'{}' statement not within a loop", keyword),
+                    ));
+                }
+
+                // Nothing after a jump is reachable on this path
+                self.unassigned.clear();
+
+                Ok(Type::Void)
+            }
             Node::VarDecl {
                 name,
                 type_,
+                storage,
                 initializer,
                 location,
             } => {
-                if let Some(init) = initializer {
-                    let init_type = self.check_node(init)?;
-                    if !self.is_compatible(&init_type, type_) {
+                self.record_tags(type_);
+                let is_local = self.symbol_table.depth() > 0;
+                if matches!(type_.unqualified(), Type::Void) {
+                    return Err(type_error(
+                        location,
+                        format!("Variable {} declared with type void", name),
+                    ));
+                }
+                if is_local && *storage == StorageClass::Extern && initializer.is_some() {
+                    return Err(type_error(
+                        location,
+                        format!("Block-scope extern variable {} cannot have an initializer", name),
+                    ));
+                }
+
+                // A global may be declared again with the same type, but only one
+                // declaration may initialize it; a local may only be declared once
+                // per scope, though an inner scope can shadow it
+                if let Some(previous) = self.symbol_table.lookup_current(name) {
+                    let redeclared_extern = *storage == StorageClass::Extern && self.global_variables.contains(name);
+                    if let Type::Function(_, _, _) = previous {
+                        return Err(type_error(
+                            location,
+                            format!("{} redeclared as a variable", name),
+                        ));
+                    } else if is_local && !redeclared_extern {
+                        return Err(type_error(
+                            location,
+                            format!("Redefinition of variable {}", name),
+                        ));
+                    } else if !self.is_same_type(&previous, type_) {
+                        return Err(type_error(
+                            location,
+                            format!("Conflicting types for variable {}: {} and {}", name, previous, type_),
+                        ));
+                    }
+                }
+                if !is_local && initializer.is_some() && !self.initialized_globals.insert(name.clone()) {
+                    return Err(type_error(
+                        location,
+                        format!("Redefinition of variable {}", name),
+                    ));
+                }
+
+                if let (Some(init), Type::Array(element, size)) = (initializer, type_.unqualified()) {
+                    // An array can only be initialized with a string, which fills an
+                    // array of chars; a terminator that does not fit is left out
+                    let length = match init.as_ref() {
+                        Node::StringLiteral(value, _) if matches!(element.unqualified(), Type::Char | Type::UChar) => {
+                            value.chars().count()
+                        }
+                        _ => {
+                            return Err(type_error(
+                                location,
+                                format!("Array {} can only be initialized with a string literal", name),
+                            ));
+                        }
+                    };
+                    if size.is_some_and(|size| length > size) {
                         return Err(type_error(
-                            &location,
+                            location,
+                            format!("Initializer string of length {} is too long for {}", length, type_),
+                        ));
+                    }
+                    self.check_node(init)?;
+                } else if let Some(init) = initializer {
+                    let init_type = self.check_value(init, "an initializer")?;
+                    if !self.is_assignable(type_, &init_type, init) {
+                        return Err(type_error(
+                            location,
                             format!(
                                 "Cannot initialize variable of type {:?} with value of type {:?}",
                                 type_, init_type
@@ -504,6 +1287,17 @@ impl TypeChecker {
 
                 self.symbol_table.define(name, type_.clone());
 
+                let key = (name.clone(), self.symbol_table.depth());
+                if key.1 == 0 || *storage == StorageClass::Extern {
+                    // A block-scope extern names a global variable
+                    self.global_variables.insert(name.clone());
+                } else if initializer.is_none() && *storage == StorageClass::Auto && !matches!(type_, Type::Array(_, _)) {
+                    // Static locals start out zeroed, so only automatic ones are tracked
+                    self.unassigned.insert(key);
+                } else {
+                    self.unassigned.remove(&key);
+                }
+
                 Ok(Type::Void)
             }
             Node::FunctionDecl {
@@ -511,26 +1305,50 @@ impl TypeChecker {
                 return_type,
                 params,
                 body,
-                location: _,
+                location,
             } => {
-                // This line is no longer needed as we filter out variadic parameters below
-                // let param_types: Vec<Type> = params.iter().map(|(_, t)| t.clone()).collect();
-                // Check if the function is variadic (has ... in parameters)
-                let is_variadic = params.iter().any(|(name, _)| name == "...");
-
-                // Remove the ... parameter if present
-                let param_types: Vec<Type> = params.iter()
-                    .filter(|(name, _)| name != "...")
-                    .map(|(_, t)| t.clone())
-                    .collect();
+                let func_type = function_type(return_type, params);
+                let is_variadic = matches!(func_type, Type::Function(_, _, true));
+                self.record_tags(&func_type);
 
-                let func_type = Type::Function(Box::new(return_type.clone()), param_types, is_variadic);
+                // A function may be declared any number of times, but every declaration
+                // must agree on its type and only one may give it a body
+                if self.symbol_table.lookup_depth(name) == Some(0) {
+                    let previous = self.symbol_table.lookup(name).unwrap();
+                    if !matches!(previous, Type::Function(_, _, _)) {
+                        return Err(type_error(
+                            location,
+                            format!("{} redeclared as a function", name),
+                        ));
+                    }
+                    if !self.is_same_type(&previous, &func_type) {
+                        return Err(type_error(
+                            location,
+                            format!("Conflicting types for function {}: {} and {}", name, previous, func_type),
+                        ));
+                    }
+                }
+                if body.is_some() && !self.defined_functions.insert(name.clone()) {
+                    return Err(type_error(
+                        location,
+                        format!("Redefinition of function {}", name),
+                    ));
+                }
 
                 self.symbol_table.define(name, func_type);
 
                 if let Some(body) = body {
                     let prev_return_type = self.current_function_return_type.clone();
                     self.current_function_return_type = Some(return_type.clone());
+                    self.variadic_last_param = params
+                        .iter()
+                        .rev()
+                        .find(|(name, _)| name != "...")
+                        .filter(|_| is_variadic)
+                        .map(|(name, _)| name.clone());
+                    self.unassigned.clear();
+                    self.labels.clear();
+                    self.gotos.clear();
 
                     self.symbol_table.enter_scope();
 
@@ -538,10 +1356,38 @@ impl TypeChecker {
                         self.symbol_table.define(param_name, param_type.clone());
                     }
 
-                    self.check_node(body)?;
+                    // The outermost block of the body shares the scope of the parameters,
+                    // so a local cannot redeclare a parameter
+                    match body.as_ref() {
+                        Node::BlockStmt(statements, _) => {
+                            for stmt in statements {
+                                self.check_node(stmt)?;
+                            }
+                            self.types.insert(body, Type::Void);
+                        }
+                        _ => {
+                            self.check_node(body)?;
+                        }
+                    }
 
                     self.symbol_table.exit_scope();
 
+                    // Every goto must jump to a label in the same function
+                    if let Some((label, location)) = self.gotos.iter().find(|(label, _)| !self.labels.contains(label)) {
+                        return Err(type_error(
+                            location,
+                            format!("Label {} is not defined in function {}", label, name),
+                        ));
+                    }
+
+                    // main implicitly returns 0 when it falls off the end
+                    if !matches!(return_type, Type::Void) && name != "main" && !self.always_returns(body) {
+                        self.warn(
+                            location,
+                            format!("Function {} may reach the end without returning a value", name),
+                        );
+                    }
+
                     self.current_function_return_type = prev_return_type;
                 }
 
@@ -553,3 +1399,39 @@ impl TypeChecker {
         }
     }
 }
+
+/// The type of a declared function. A trailing `...` parameter makes it variadic
+/// rather than being a parameter itself.
+fn function_type(return_type: &Type, params: &[(String, Type)]) -> Type {
+    let is_variadic = params.iter().any(|(name, _)| name == "...");
+    let param_types = params
+        .iter()
+        .filter(|(name, _)| name != "...")
+        .map(|(_, type_)| type_.clone())
+        .collect();
+    Type::Function(Box::new(return_type.clone()), param_types, is_variadic)
+}
+
+/// Check if an expression is an lvalue, designating an object or function rather
+/// than a value: a name, a dereference (which includes subscripts and `->`), a
+/// member of an lvalue, or a string literal. Only these can be assigned to or
+/// have their address taken.
+fn is_lvalue(node: &Node) -> bool {
+    match node {
+        Node::Identifier(_, _) | Node::StringLiteral(_, _) => true,
+        Node::UnaryExpr { op: UnaryOp::Dereference, .. } => true,
+        Node::MemberAccess { expr, .. } => is_lvalue(expr),
+        _ => false,
+    }
+}
+
+/// Check if an expression is a null pointer constant: an integer constant with the
+/// value 0, which constant folding has made a literal by now...
+fn is_null_pointer_constant(node: &Node) -> bool {
+    match node {
+        Node::IntLiteral(0, _) | Node::UIntLiteral(0, _) | Node::LongLiteral(0, _) | Node::ULongLiteral(0, _) => true,
+        // ... or such a constant cast to void *
+        Node::Cast { type_: Type::Pointer(inner), expr, .. } if **inner == Type::Void => is_null_pointer_constant(expr),
+        _ => false,
+    }
+}
@@ -0,0 +1,53 @@
+// Taking the address of array elements, dereferences and struct members
+
+#define POINT struct Point { int x; int y; }
+
+// Writes through the pointer it is given
+void set(int *p, int value) {
+    *p = value;
+}
+
+// Sums n ints starting at p
+int sum(int *p, int n) {
+    int total = 0;
+    int i;
+    for (i = 0; i < n; i = i + 1) {
+        total = total + p[i];
+    }
+    return total;
+}
+
+int main() {
+    int arr[5];
+    int i;
+    int *p;
+    POINT pt;
+
+    for (i = 0; i < 5; i = i + 1) {
+        arr[i] = i + 1;
+    }
+
+    // &arr[2] points into the middle of the array
+    if (sum(&arr[2], 3) != 12) {
+        return 1;
+    }
+    set(&arr[2], 30);
+    if (arr[2] != 30 || &arr[4] - &arr[1] != 3) {
+        return 2;
+    }
+
+    // &*p is p itself
+    p = &arr[0];
+    if (&*p != p || *&*p != 1) {
+        return 3;
+    }
+
+    // The address of a member lies inside the struct
+    set(&pt.x, 10);
+    set(&pt.y, 32);
+    if (&pt.y - &pt.x != 1) {
+        return 4;
+    }
+
+    return pt.x + pt.y;
+}
@@ -0,0 +1,11 @@
+// A chain of binary operations on simple operands
+// The peephole pass turns each push/pop pair around the right operand into a move
+int main() {
+    long a = 1;
+    long b = 2;
+    long c = 3;
+    long d = 4;
+
+    // (1 + 2 + 3 + 4) * 2 - 4 / 2 - 6 / 3 == 16
+    return (a + b + c + d) * 2 - d / 2 - 6 / c;
+}
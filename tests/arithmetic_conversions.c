@@ -0,0 +1,45 @@
+// Test the usual arithmetic conversions: char operands are promoted to int
+// before arithmetic, and mixed operands are converted to the wider type, a long
+// holding every unsigned int value
+int main() {
+    char a;
+    char b;
+    unsigned char c;
+    int i;
+    unsigned int u;
+    long l;
+    long big;
+
+    // Characters add as ints, so neither the type nor the sum is a char
+    a = 100;
+    b = 100;
+    c = 200;
+    if (sizeof('a' + 'b') != 4 || sizeof(a + b) != 4 || sizeof(-a) != 4 || sizeof(a << 1) != 4) {
+        return 1;
+    }
+    if (a + b != 200 || c + c != 400 || a * b != 10000) {
+        return 2;
+    }
+
+    // An int operand meeting a long is widened, and the arithmetic is done in 64 bits
+    i = 2147483647;
+    l = 1;
+    if (sizeof(i + l) != 8 || sizeof(a + l) != 8) {
+        return 3;
+    }
+    big = i + l;
+    if (big != 2147483648 || big / 2 != 1073741824) {
+        return 4;
+    }
+
+    // An int meeting an unsigned int is compared as unsigned, but a long can hold
+    // every unsigned int, so a negative long stays negative
+    i = -1;
+    u = 1;
+    l = -1;
+    if (i < u || !(l < u) || sizeof(i + u) != 4 || sizeof(l + u) != 8) {
+        return 5;
+    }
+
+    return 42;
+}
@@ -0,0 +1,73 @@
+// Test arrays used as values: each decays to a pointer to its first element, so
+// it can be passed to a pointer parameter, assigned to a pointer or compared
+#include <stdio.h>
+
+int sum(int *values, int count) {
+    int total = 0;
+    int i;
+    for (i = 0; i < count; i = i + 1) {
+        total = total + values[i];
+    }
+    return total;
+}
+
+// An array parameter is a pointer parameter, written another way
+int first(int values[]) {
+    return values[0];
+}
+
+int *middle(int *values) {
+    return values + 1;
+}
+
+int row_sum(int rows[][3], int row) {
+    return sum(rows[row], 3);
+}
+
+int global_values[3];
+
+int main() {
+    int values[3];
+    int grid[2][3];
+    int *pointer;
+    void *untyped;
+    int i;
+
+    values[0] = 10;
+    values[1] = 12;
+    values[2] = 20;
+    for (i = 0; i < 6; i = i + 1) {
+        grid[i / 3][i % 3] = i;
+    }
+    global_values[0] = 1;
+
+    // An int[] argument is passed to an int * parameter as the address of its
+    // first element
+    if (sum(values, 3) != 42 || first(values) != 10 || first(global_values) != 1) {
+        return 1;
+    }
+    if (*middle(values) != 12 || middle(values) != &values[1]) {
+        return 2;
+    }
+
+    // Each row of a two-dimensional array is an int[3], and the array itself
+    // decays to a pointer to its first row
+    if (row_sum(grid, 1) != 12 || sum(grid[0], 3) != 3) {
+        return 3;
+    }
+
+    // Assigned to a pointer, or to void *, the array is the address of its start
+    pointer = values;
+    untyped = values;
+    if (pointer != values || untyped != pointer || pointer[2] != 20) {
+        return 4;
+    }
+
+    // &values is the address of the whole array, which starts where its first
+    // element does, and the array keeps its size under sizeof
+    if ((void *)&values != untyped || sizeof(values) != 12) {
+        return 5;
+    }
+
+    return sum(values, 3);
+}
@@ -0,0 +1,46 @@
+// Test sizeof on arrays: a bare array name is the whole array, so
+// sizeof(arr) / sizeof(arr[0]) counts its elements, while anything that uses
+// the array as a value has a pointer's size
+#define COUNT(a) (sizeof(a) / sizeof((a)[0]))
+
+long globals[7];
+
+int sum(int *values, int count) {
+    int total = 0;
+    int i;
+    for (i = 0; i < count; i = i + 1) {
+        total = total + values[i];
+    }
+    return total;
+}
+
+// An array parameter is a pointer
+int parameter_size(int values[10]) {
+    return sizeof(values);
+}
+
+int main() {
+    int values[6];
+    char text[] = "hello";
+    char grid[3][5];
+    int *pointer = values;
+    int i;
+
+    if (COUNT(values) != 6 || sizeof(values) != 24 || sizeof(pointer) != 8) {
+        return 1;
+    }
+    if (COUNT(globals) != 7 || COUNT(text) != 6 || COUNT(grid) != 3 || COUNT(grid[0]) != 5) {
+        return 2;
+    }
+
+    // The array decays to a pointer in arithmetic and after a comma
+    if (sizeof(values + 1) != 8 || sizeof(0, values) != 8 || parameter_size(values) != 8) {
+        return 3;
+    }
+
+    for (i = 0; i < COUNT(values); i = i + 1) {
+        values[i] = i + 5;
+    }
+    // 5 + 6 + 7 + 8 + 9 + 10 = 45
+    return sum(values, COUNT(values)) - 3;
+}
@@ -0,0 +1,30 @@
+// Test array sizes given by macros and constant expressions
+#define N 10
+
+int table[N * 2];
+
+int main() {
+    int a[N];
+    char half[N / 2 + (1 << 1) - 1];
+    int i;
+    int sum;
+
+    sum = 0;
+    for (i = 0; i < N; i = i + 1) {
+        a[i] = i;
+        table[i] = i;
+        table[i + N] = 1;
+    }
+    for (i = 0; i < N / 2 + 1; i = i + 1) {
+        half[i] = 2;
+    }
+
+    for (i = 0; i < N; i = i + 1) {
+        sum = sum + a[i] + table[i] + table[i + N];
+    }
+    for (i = 0; i < N / 2 + 1; i = i + 1) {
+        sum = sum + half[i];
+    }
+
+    return sum; // 45 + 45 + 10 + 12 = 112
+}
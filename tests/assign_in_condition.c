@@ -0,0 +1,34 @@
+// Test the warning for an assignment used as the condition of an if or while.
+// Compiling this file should warn on lines 14 and 18 only; the assignments
+// wrapped in their own parentheses are intended and stay quiet.
+int next(int *n) {
+    *n = *n - 1;
+    return *n;
+}
+
+int main() {
+    int x;
+    int n;
+    int total = 0;
+
+    if (x = 5) {
+        total = x;
+    }
+    n = 4;
+    while (x = next(&n)) {
+        total = total + x;
+    }
+
+    n = 3;
+    while ((x = next(&n))) {
+        total = total + x;
+    }
+    if ((x = total)) {
+        total = total + 1;
+    }
+    if ((x) == 5 || (n) == 0) {
+        total = total + 1;
+    }
+
+    return total; // 5 + 3 + 2 + 1 + 2 + 1 + 1 + 1 = 16
+}
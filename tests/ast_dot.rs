@@ -0,0 +1,60 @@
+use std::io;
+
+use ferricc::{dump, parse, Options};
+
+/// Parse a program and render it as DOT
+fn dot_of(source: &str) -> String {
+    let program = parse(source, "ast_dot.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    dump::ast_dot(&program)
+}
+
+/// Check if a statement is an edge; a label may contain "->" too, as a function's does
+fn is_edge(line: &str) -> bool {
+    line.split(" [").next().unwrap().contains(" -> ")
+}
+
+/// The node statements of a graph, which have a label but are not edges
+fn nodes(dot: &str) -> Vec<&str> {
+    dot.lines().filter(|line| line.contains("[label=") && !is_edge(line)).collect()
+}
+
+/// The edge statements of a graph
+fn edges(dot: &str) -> Vec<&str> {
+    dot.lines().filter(|line| is_edge(line)).collect()
+}
+
+#[test]
+fn every_node_of_a_small_program_is_in_the_graph() {
+    let dot = dot_of("int add(int a, int b) {\n    return a + b;\n}\n");
+    assert!(dot.starts_with("digraph AST {\n"));
+    assert!(dot.ends_with("}\n"));
+
+    // Program, FunctionDecl, BlockStmt, ReturnStmt, BinaryExpr and two Identifiers,
+    // joined into a tree by one edge fewer than there are nodes
+    assert_eq!(nodes(&dot).len(), 7);
+    assert_eq!(edges(&dot).len(), 6);
+    assert!(dot.contains("n0 [label=\"Program\"];"));
+    assert!(dot.contains("[label=\"FunctionDecl add(a: int, b: int) -> int\"];"));
+    assert!(dot.contains("[label=\"BinaryExpr +\"];"));
+}
+
+#[test]
+fn edges_are_labeled_by_the_role_of_the_child() {
+    let dot = dot_of("int main() {\n    int x = 3;\n    if (x) return 1; else return 2;\n}\n");
+    for role in ["declaration", "body", "statement", "initializer", "condition", "then", "else", "value"] {
+        assert!(
+            edges(&dot).iter().any(|edge| edge.ends_with(&format!("[label=\"{}\"];", role))),
+            "no {} edge in\n{}",
+            role,
+            dot
+        );
+    }
+    assert!(dot.contains("[label=\"VarDecl x: int\"];"));
+    assert!(dot.contains("[label=\"IntLiteral 3\"];"));
+}
+
+#[test]
+fn quotes_and_backslashes_in_labels_are_escaped() {
+    let dot = dot_of("int puts(char *s);\nint main() {\n    puts(\"say \\\"hi\\\"\\n\");\n}\n");
+    assert!(dot.contains(r#"[label="StringLiteral \"say \\\"hi\\\"\\n\""];"#), "{}", dot);
+}
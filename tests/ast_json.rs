@@ -0,0 +1,210 @@
+use std::io;
+
+use ferricc::{dump, parse, Options};
+
+/// A parsed JSON value
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Look up a field of an object
+    fn get(&self, name: &str) -> &Json {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value)
+                .unwrap_or_else(|| panic!("no field {} in {:?}", name, self)),
+            _ => panic!("{:?} is not an object", self),
+        }
+    }
+
+    /// Index an array
+    fn at(&self, index: usize) -> &Json {
+        match self {
+            Json::Array(items) => &items[index],
+            _ => panic!("{:?} is not an array", self),
+        }
+    }
+
+    fn str(&self) -> &str {
+        match self {
+            Json::String(value) => value,
+            _ => panic!("{:?} is not a string", self),
+        }
+    }
+
+    fn number(&self) -> f64 {
+        match self {
+            Json::Number(value) => *value,
+            _ => panic!("{:?} is not a number", self),
+        }
+    }
+}
+
+/// A just-enough JSON reader, so the test reads the output the way another tool would
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Reader<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) {
+        self.skip_whitespace();
+        assert_eq!(self.chars.next(), Some(expected));
+    }
+
+    fn value(&mut self) -> Json {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'}').is_none() {
+                    loop {
+                        self.skip_whitespace();
+                        let Json::String(name) = self.value() else { panic!("field name is not a string") };
+                        self.expect(':');
+                        fields.push((name, self.value()));
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => continue,
+                            Some('}') => break,
+                            other => panic!("unexpected {:?} in object", other),
+                        }
+                    }
+                }
+                Json::Object(fields)
+            }
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&']').is_none() {
+                    loop {
+                        items.push(self.value());
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => continue,
+                            Some(']') => break,
+                            other => panic!("unexpected {:?} in array", other),
+                        }
+                    }
+                }
+                Json::Array(items)
+            }
+            Some('"') => {
+                self.chars.next();
+                let mut value = String::new();
+                loop {
+                    match self.chars.next().expect("unterminated string") {
+                        '"' => break,
+                        '\\' => match self.chars.next().unwrap() {
+                            'n' => value.push('\n'),
+                            't' => value.push('\t'),
+                            'r' => value.push('\r'),
+                            'u' => {
+                                let hex: String = (0..4).map(|_| self.chars.next().unwrap()).collect();
+                                value.push(char::from_u32(u32::from_str_radix(&hex, 16).unwrap()).unwrap());
+                            }
+                            c => value.push(c),
+                        },
+                        c => value.push(c),
+                    }
+                }
+                Json::String(value)
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || "+-.".contains(*c)) {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "null" => Json::Null,
+                    "true" => Json::Bool(true),
+                    "false" => Json::Bool(false),
+                    number => Json::Number(number.parse().unwrap_or_else(|_| panic!("bad value {:?}", number))),
+                }
+            }
+        }
+    }
+}
+
+/// Parse a program and read back its JSON dump
+fn ast_of(source: &str) -> Json {
+    let program = parse(source, "ast_json.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    let json = dump::ast_json(&program);
+    let mut reader = Reader { chars: json.chars().peekable() };
+    let value = reader.value();
+    reader.skip_whitespace();
+    assert_eq!(reader.chars.next(), None, "trailing output after the JSON value");
+    value
+}
+
+#[test]
+fn functions_keep_their_names_types_and_locations() {
+    let program = ast_of("int add(int a, long *b) {\n    return a + *b;\n}\n");
+    assert_eq!(program.get("kind").str(), "Program");
+    assert_eq!(program.get("location"), &Json::Null);
+
+    let add = program.get("declarations").at(0);
+    assert_eq!(add.get("kind").str(), "FunctionDecl");
+    assert_eq!(add.get("name").str(), "add");
+    assert_eq!(add.get("return_type").get("kind").str(), "int");
+    assert_eq!(add.get("variadic"), &Json::Bool(false));
+    assert_eq!(add.get("location").get("file").str(), "ast_json.c");
+    assert_eq!(add.get("location").get("line").number(), 1.0);
+
+    let b = add.get("params").at(1);
+    assert_eq!(b.get("name").str(), "b");
+    assert_eq!(b.get("type").get("kind").str(), "pointer");
+    assert_eq!(b.get("type").get("to").get("kind").str(), "long");
+
+    let sum = add.get("body").get("statements").at(0).get("value");
+    assert_eq!(sum.get("kind").str(), "BinaryExpr");
+    assert_eq!(sum.get("op").str(), "+");
+    assert_eq!(sum.get("location").get("line").number(), 2.0);
+    assert_eq!(sum.get("left").get("name").str(), "a");
+    assert_eq!(sum.get("right").get("op").str(), "*");
+}
+
+#[test]
+fn literals_and_missing_children_round_trip() {
+    let program = ast_of("int main() {\n    char s[] = \"say \\\"hi\\\"\\n\";\n    if (1) return 0;\n}\n");
+    let statements = program.get("declarations").at(0).get("body").get("statements");
+
+    let declaration = statements.at(0);
+    assert_eq!(declaration.get("storage").str(), "auto");
+    assert_eq!(declaration.get("type").get("kind").str(), "array");
+    assert_eq!(declaration.get("type").get("size").number(), 10.0);
+    assert_eq!(declaration.get("initializer").get("value").str(), "say \"hi\"\n");
+
+    let if_stmt = statements.at(1);
+    assert_eq!(if_stmt.get("condition").get("kind").str(), "IntLiteral");
+    assert_eq!(if_stmt.get("condition").get("value").number(), 1.0);
+    assert_eq!(if_stmt.get("else"), &Json::Null);
+}
+
+#[test]
+fn variadic_functions_are_flagged_rather_than_given_a_parameter() {
+    let program = ast_of("int printf(const char *format, ...);");
+    let printf = program.get("declarations").at(0);
+    assert_eq!(printf.get("variadic"), &Json::Bool(true));
+    assert_eq!(printf.get("body"), &Json::Null);
+
+    let format = printf.get("params").at(0).get("type");
+    assert_eq!(format.get("to").get("kind").str(), "const");
+    assert_eq!(format.get("to").get("type").get("kind").str(), "char");
+    assert!(matches!(printf.get("params"), Json::Array(params) if params.len() == 1));
+}
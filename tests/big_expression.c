@@ -0,0 +1,30 @@
+// A large arithmetic expression whose intermediate values live in scratch registers
+// Nesting deeper than the register pool spills the innermost operands to the stack
+
+// Right-nested so that every level holds a value while the rest is evaluated
+long deep(long x) {
+    return x + (x * (x + (x * (x + (x * (x + (x * (x + (x * (x + 1))))))))));
+}
+
+// Uses the callee-saved scratch registers itself, so its caller's values must survive
+long mix(long a, long b) {
+    return (a - (b - (a - (b - (a - b))))) + (a << (b >> 1)) % 7;
+}
+
+int main() {
+    long a = 3;
+    long b = 5;
+    long c = 7;
+    long sum;
+    long *p;
+
+    // deep(1) == 7, mix(9, 4) == 16
+    sum = a * (b + (c * (a + (b * (c - mix(9, 4) / (deep(1) - 1))))));
+
+    // Store through a pointer while the stored value is held in a register
+    p = &a;
+    *p = sum - (b * (c + 100)) - 200;
+
+    // 3 * (5 + 7 * (3 + 5 * (7 - 2))) == 603, 603 - 535 - 200 == -132
+    return a + 174;
+}
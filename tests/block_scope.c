@@ -0,0 +1,42 @@
+// Variables declared in a block are only visible inside it
+
+int main() {
+    int x = 1;
+    int total = 0;
+    int i;
+
+    // Sibling blocks each declare their own y
+    {
+        int y = 10;
+        total = total + y;
+    }
+    {
+        int y = 20;
+        total = total + y;
+    }
+
+    // An inner x shadows the outer one until the end of its block
+    {
+        int x = 100;
+        x = x + 1;
+        if (x != 101) {
+            return 1;
+        }
+    }
+    if (x != 1 || total != 30) {
+        return 2;
+    }
+
+    // A block in a loop body releases its stack space each iteration, so a large
+    // local does not exhaust the stack
+    for (i = 0; i < 100000; i = i + 1) {
+        char buffer[4096];
+        buffer[4095] = 1;
+        total = total + buffer[4095];
+    }
+    if (total != 100030) {
+        return 3;
+    }
+
+    return total - 100030 + 41 + x;
+}
@@ -0,0 +1,69 @@
+// The _Bool type, which holds 0 or 1 in a single byte.
+// Compiling this file should warn on the marked line only.
+
+#include <stdbool.h>
+
+_Bool global_flag = 7;
+
+// Returns whether n is even, converting the int result to _Bool
+bool is_even(int n) {
+    return n % 2 == 0;
+}
+
+int main() {
+    _Bool b;
+    _Bool from_pointer;
+    int a = 3;
+    int c = 5;
+    int *p = &a;
+    double d = 0.5;
+    double negative_zero = -0.0;
+    unsigned char byte = 0;
+
+    // Comparisons produce 0 or 1
+    if ((3 < 5) != 1 || (5 < 3) != 0 || (a == 3) + (c == 5) != 2) {
+        return 1;
+    }
+
+    // Assigning a comparison stores 0 or 1
+    b = a < c;
+    if (b != 1 || sizeof(b) != 1 || sizeof(_Bool) != 1) {
+        return 2;
+    }
+    b = a > c;
+    if (b != 0) {
+        return 3;
+    }
+
+    // Every nonzero value converts to 1
+    b = 256;
+    if (b != 1 || global_flag != 1) {
+        return 4;
+    }
+    b = d;
+    if (b != 1) {
+        return 5;
+    }
+    b = negative_zero;
+    if (b != 0) {
+        return 6;
+    }
+    from_pointer = p;
+    if (from_pointer != 1) {
+        return 7;
+    }
+
+    // _Bool promotes to int in arithmetic
+    b = true;
+    if (b + b != 2 || is_even(4) != true || is_even(7) != false) {
+        return 8;
+    }
+
+    // a < b < c compares the result of a < b with c, not b with c
+    if (c > a > 1) {    // warns
+        return 9;
+    }
+
+    byte = b;
+    return byte - 1;
+}
@@ -0,0 +1,63 @@
+// Test break and continue in nested loops: each leaves or continues only the
+// innermost loop around it
+int main() {
+    int i;
+    int j;
+    int pairs;
+    int odd;
+    int n;
+
+    // The inner break ends each inner loop early, but the outer loop runs on
+    pairs = 0;
+    for (i = 0; i < 4; i = i + 1) {
+        for (j = 0; j < 10; j = j + 1) {
+            if (j > i) {
+                break;
+            }
+            pairs = pairs + 1;
+        }
+    }
+    if (pairs != 10) {
+        return 1;
+    }
+
+    // continue in a for loop still runs the increment
+    odd = 0;
+    for (i = 0; i < 10; i = i + 1) {
+        if (i % 2 == 0) {
+            continue;
+        }
+        odd = odd + i;
+    }
+    if (odd != 25) {
+        return 2;
+    }
+
+    // continue in a while loop checks the condition again; break leaves a loop
+    // that has no other way out
+    n = 0;
+    i = 0;
+    while (1) {
+        i = i + 1;
+        if (i > 6) {
+            break;
+        }
+        j = 0;
+        while (j < 3) {
+            j = j + 1;
+            if (j == 2) {
+                continue;
+            }
+            n = n + 1;
+        }
+        if (i == 3) {
+            continue;
+        }
+        n = n + 10;
+    }
+    if (n != 62) {
+        return 3;
+    }
+
+    return 0;
+}
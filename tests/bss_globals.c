@@ -0,0 +1,24 @@
+// Zero-initialized globals live in .bss, initialized ones in .data
+
+// A large array that would otherwise fill the object file with zeros
+long table[100000];
+int count;
+int zero = 0;
+int start = 5;
+char *name;
+
+int main() {
+    static int calls;
+    int i;
+
+    for (i = 0; i < 100000; i = i + 1) {
+        table[i] = i;
+    }
+    count = start + zero + calls;
+    if (name || table[99999] != 99999) {
+        return 1;
+    }
+
+    // 5 + 37 == 42
+    return count + table[37];
+}
@@ -0,0 +1,34 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+const PROGRAM: &str = "long table[4096];\nint count;\nint zero = 0;\nint start = 5;\nint main() { return start; }";
+
+/// Get the section directive in effect where a global's label is defined
+fn section_of(assembly: &str, label: &str) -> String {
+    let definition = format!("{}:", label);
+    assembly
+        .lines()
+        .take_while(|line| line.trim() != definition)
+        .filter(|line| matches!(line.trim(), ".data" | ".bss" | ".text"))
+        .last()
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn zero_initialized_globals_go_in_bss() {
+    let assembly = compile(PROGRAM, "bss_sections.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assert_eq!(section_of(&assembly, "table"), ".bss");
+    assert_eq!(section_of(&assembly, "count"), ".bss");
+    assert_eq!(section_of(&assembly, "zero"), ".bss");
+    assert!(assembly.contains(".zero 32768"));
+}
+
+#[test]
+fn initialized_globals_stay_in_data() {
+    let assembly = compile(PROGRAM, "bss_sections.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assert_eq!(section_of(&assembly, "start"), ".data");
+    assert!(assembly.contains(".long 5"));
+}
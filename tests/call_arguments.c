@@ -0,0 +1,26 @@
+// Argument lists: empty, spread over lines with comments, and deeply nested
+
+int zero() {
+    return 0;
+}
+
+int inc(int x) {
+    return x + 1;
+}
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int main() {
+    int sum = zero( /* nothing here */ ) + add(
+        // the first argument
+        10,
+        /* the second */ 20
+    );
+
+    // 12 nested calls
+    sum = sum + inc(inc(inc(inc(inc(inc(inc(inc(inc(inc(inc(inc(zero()))))))))))));
+
+    return sum;
+}
@@ -0,0 +1,74 @@
+// Test casts, told apart from parenthesized expressions by whether the
+// parentheses hold a type name, a typedef name included
+typedef long Wide;
+typedef unsigned char Byte;
+
+int twice(int x) {
+    return x * 2;
+}
+
+int main() {
+    int x;
+    int i;
+    double d;
+    Wide w;
+    char *bytes;
+    int (*f)(int);
+
+    x = 300;
+    d = 3.75;
+
+    // Integer casts keep the low bytes of the value, extended for the new type
+    if ((char)x != 44 || (unsigned char)-1 != 255 || (Byte)x != 44) {
+        return 1;
+    }
+    if (sizeof((char)x) != 1 || sizeof((Wide)x) != 8) {
+        return 2;
+    }
+
+    // A cast converts a floating value to an integer and back
+    if ((int)d != 3 || (double)7 / 2 != 3.5 || (float)0.1 == 0.1) {
+        return 3;
+    }
+
+    // A typedef name in parentheses is a cast
+    w = (Wide)x * 10000000;
+    if (w != 3000000000) {
+        return 4;
+    }
+
+    // A variable in parentheses is just a parenthesized expression, so this is a
+    // subtraction, not a cast of -1
+    if ((x) - 1 != 299) {
+        return 5;
+    }
+    {
+        // ... as is a variable hiding a typedef of the same name
+        int Wide;
+        Wide = 8;
+        if ((Wide) - 1 != 7) {
+            return 6;
+        }
+    }
+
+    // Pointer casts reinterpret the object pointed to
+    i = 0x01020304;
+    bytes = (char *)&i;
+    if (bytes[0] != 4 || bytes[3] != 1) {
+        return 7;
+    }
+    if ((void *)0 != 0 || (long)(char *)0 != 0) {
+        return 8;
+    }
+
+    // A function converts to a function pointer type written without a name
+    f = (int (*)(int))twice;
+    if (f(21) != 42) {
+        return 9;
+    }
+
+    // Casting to void discards a value
+    (void)twice(1);
+
+    return 42;
+}
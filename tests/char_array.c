@@ -0,0 +1,47 @@
+// Reads and writes through char and int pointers touch only the element's bytes
+int main() {
+    char letters[8];
+    int numbers[4];
+    char *p = letters;
+    char c;
+
+    // Write the char array element by element
+    letters[0] = 'a';
+    letters[1] = 'b';
+    letters[2] = 'c';
+    letters[3] = 'd';
+
+    // Read each element back; wider stores would have overwritten the neighbours
+    if (letters[0] != 'a') {
+        return 1;
+    }
+    if (letters[1] != 'b') {
+        return 2;
+    }
+    if (letters[2] != 'c') {
+        return 3;
+    }
+    if (*(p + 3) != 'd') {
+        return 4;
+    }
+
+    // A char holding -1 sign-extends when loaded
+    *(p + 1) = 0 - 1;
+    c = letters[1];
+    if (c != 0 - 1) {
+        return 5;
+    }
+    if (letters[2] != 'c') {
+        return 6;
+    }
+
+    // Int elements are read and written four bytes at a time
+    numbers[0] = 0 - 7;
+    numbers[1] = 100000;
+    numbers[2] = 3;
+    if (numbers[0] + numbers[1] + numbers[2] != 99996) {
+        return 7;
+    }
+
+    return 0;
+}
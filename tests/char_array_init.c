@@ -0,0 +1,59 @@
+// Char arrays initialized with string literals, sized by the string or zero-filled
+
+char greeting[] = "hi \"there\"\n";
+char padded[8] = "abc";
+
+// Length of a NUL-terminated string
+int length(char *s) {
+    int n = 0;
+    while (s[n] != 0) {
+        n = n + 1;
+    }
+    return n;
+}
+
+int main() {
+    char word[] = "hello";
+    char buffer[20] = "hi";
+    char exact[3] = "abc";
+    int i;
+
+    // The string's length plus its terminator sizes the array
+    if (sizeof(word) != 6 || length(word) != 5) {
+        return 1;
+    }
+    if (word[0] != 'h' || word[4] != 'o' || word[5] != 0) {
+        return 2;
+    }
+
+    // The rest of a larger array is zeroed
+    if (buffer[0] != 'h' || buffer[1] != 'i') {
+        return 3;
+    }
+    for (i = 2; i < 20; i = i + 1) {
+        if (buffer[i] != 0) {
+            return 4;
+        }
+    }
+
+    // The array can be written like any other
+    word[0] = 'j';
+    if (word[0] != 'j' || word[1] != 'e') {
+        return 5;
+    }
+
+    // A terminator that does not fit is left out
+    if (exact[2] != 'c') {
+        return 6;
+    }
+
+    // Globals hold their bytes, escapes included
+    if (sizeof(greeting) != 12 || length(greeting) != 11 || greeting[3] != '"' || greeting[10] != '\n') {
+        return 7;
+    }
+    if (length(padded) != 3 || padded[7] != 0) {
+        return 8;
+    }
+
+    return 42;
+}
@@ -0,0 +1,32 @@
+// Character constants have type int, and may hold several characters
+
+int main() {
+    char c = 'A';
+    int ab = 'AB';
+
+    if ('A' + 1 != 'B' || c + 1 != 'B') {
+        return 1;
+    }
+
+    // A character constant is an int, not a char
+    if (sizeof('A') != 4 || sizeof(c) != 1 || sizeof c + 'A' != 66) {
+        return 2;
+    }
+
+    // The first character is the most significant byte
+    if (ab != 'A' * 256 + 'B' || 'ABCD' != 0x41424344) {
+        return 3;
+    }
+
+    // '\xff' is a char converted to int, so it is negative
+    if ('\xff' != -1) {
+        return 4;
+    }
+
+    // sizeof also applies to type names
+    if (sizeof(long) != 8 || sizeof(char *) != 8 || sizeof(int[10]) != 40) {
+        return 5;
+    }
+
+    return '*';
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::io;
+
+use ferricc::target::Target;
+use ferricc::{compile, Options};
+
+/// Echoes its input a character at a time until getchar reports EOF
+const ECHO: &str = "#include <stdio.h>\n\
+                    int main() {\n    int c;\n    c = getchar();\n    while (c != -1) {\n\
+                    \x20       putchar(c);\n        c = getchar();\n    }\n    return 0;\n}\n";
+
+/// Echoes its input through a char, with each character read into a long, which
+/// must hold -1 at EOF rather than just the low 32 bits of it
+const WIDE_ECHO: &str = "#include <stdio.h>\n\
+                         int main() {\n    long c;\n    char ch;\n    c = getchar();\n    while (c != -1) {\n\
+                         \x20       ch = c;\n        putchar(ch);\n        c = getchar();\n    }\n    return 0;\n}\n";
+
+/// Prints string literals whose quotes and backslashes must survive assembly
+const QUOTES: &str = "#include <stdio.h>\n\
+                      int main() {\n    puts(\"a\\\"b\\\\c\");\n    puts(\"tab\\there\\\\\");\n    return 0;\n}\n";
+
+/// Prints a string colored with ANSI escape sequences, followed by bytes above 0x7e
+const ANSI: &str = "#include <stdio.h>\n\
+                    int main() {\n    puts(\"\\x1b[31mred\\x1b[0m\");\n    puts(\"\\xff\\x80\\x7f\");\n    return 0;\n}\n";
+
+/// Compile and link a program for the host, run it with the given input and
+/// return what it wrote
+fn run_with_input(source: &str, name: &str, input: &[u8]) -> Vec<u8> {
+    let options = Options { target: Target::host(), ..Options::default() };
+    let output = common::run_with_input(source, name, &options, input);
+    assert!(output.status.success(), "the program exited with {}", output.status);
+    output.stdout
+}
+
+#[test]
+fn echo_copies_input_until_eof() {
+    let input = b"Hello, world!\nA second line\twith a tab\n";
+    assert_eq!(run_with_input(ECHO, "echo", input), input);
+}
+
+#[test]
+fn echo_passes_bytes_with_the_high_bit_set() {
+    // A 0xff byte reads as 255, which is not EOF
+    let input = [b'a', 0xff, 0x80, b'z'];
+    assert_eq!(run_with_input(ECHO, "echo_high", &input), input);
+}
+
+#[test]
+fn eof_is_minus_one_when_widened_to_long() {
+    let input = b"wide\n";
+    assert_eq!(run_with_input(WIDE_ECHO, "wide_echo", input), input);
+}
+
+#[test]
+fn echo_of_empty_input_writes_nothing() {
+    assert_eq!(run_with_input(ECHO, "echo_empty", b""), b"");
+}
+
+#[test]
+fn quotes_and_backslashes_in_string_literals_print_verbatim() {
+    assert_eq!(run_with_input(QUOTES, "quotes", b""), b"a\"b\\c\ntab\there\\\n");
+}
+
+#[test]
+fn escaped_bytes_are_emitted_as_octal_and_print_unchanged() {
+    let assembly = compile(ANSI, "ansi.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assert!(assembly.contains(r#".ascii "\033[31mred\033[0m""#), "{}", assembly);
+    assert!(assembly.contains(r#".ascii "\377\200\177""#), "{}", assembly);
+
+    assert_eq!(run_with_input(ANSI, "ansi", b""), b"\x1b[31mred\x1b[0m\n\xff\x80\x7f\n");
+}
@@ -0,0 +1,54 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::target::Target;
+use ferricc::{assembler, compile, Options};
+
+const PROGRAM: &str = "#include <stdio.h>\n\
+                       int main() {\n    puts(\"a\\\"b\");\n    return 0;\n}\n";
+
+/// Compile the program for the host, with `.loc` directives when asked for
+fn assembly(debug_info: bool) -> String {
+    let options = Options { target: Target::host(), debug_info, ..Options::default() };
+    compile(PROGRAM, "check.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+}
+
+/// The location and message of the error the assembler check reports
+fn check_error(assembly: &str) -> (Option<String>, String) {
+    match assembler::check(assembly, Target::host()) {
+        Err(CompilerError::CodeGenError { location, message }) => (location.map(|l| l.to_string()), message),
+        other => panic!("expected an assembler error, got {:?}", other),
+    }
+}
+
+#[test]
+fn generated_assembly_passes_the_check() {
+    assembler::check(&assembly(false), Target::host()).unwrap();
+    assembler::check(&assembly(true), Target::host()).unwrap();
+}
+
+#[test]
+fn an_unescaped_string_is_caught_in_its_label() {
+    let assembly = assembly(false).replace(r#".ascii "a\"b""#, r#".ascii "a"b""#);
+    let (location, message) = check_error(&assembly);
+    // The string's data is laid out apart from the statement that uses it
+    assert_eq!(location, None);
+    assert!(message.contains(r#"in .LC0 (`.ascii "a"b"`)"#), "{}", message);
+}
+
+#[test]
+fn an_invalid_instruction_is_reported_at_the_statement_that_generated_it() {
+    let assembly = assembly(true).replace("mov rax, 0\n", "mov rax, 0, 0\n");
+    let (location, message) = check_error(&assembly);
+    assert_eq!(location.as_deref(), Some("check.c:4:5"));
+    assert!(message.contains("in main (`mov rax, 0, 0`)"), "{}", message);
+}
+
+#[test]
+fn without_loc_directives_the_label_and_line_still_locate_the_error() {
+    let assembly = assembly(false).replace("mov rax, 0\n", "mov rax, 0, 0\n");
+    let line = assembly.lines().position(|line| line.trim() == "mov rax, 0, 0").unwrap() + 1;
+    let (location, message) = check_error(&assembly);
+    assert_eq!(location, None);
+    assert!(message.starts_with(&format!("Invalid assembly at line {} in main", line)), "{}", message);
+}
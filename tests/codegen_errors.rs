@@ -0,0 +1,50 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::target::Target;
+use ferricc::{compile, Options};
+
+/// Compile a program the code generator cannot handle, returning the error
+fn codegen_error(source: &str, options: &Options) -> CompilerError {
+    match compile(source, "codegen_errors.c", options, &mut io::sink(), &mut Vec::new()) {
+        Err(error @ CompilerError::CodeGenError { .. }) => error,
+        other => panic!("expected a code generation error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn large_struct_parameter_points_at_the_function() {
+    // System V passes structs of up to 16 bytes in registers; larger ones are not supported
+    let options = Options { target: Target::Linux, ..Options::default() };
+    let source = "struct Big {\n    long a;\n    long b;\n    long c;\n};\nlong first(struct Big big) {\n    return big.a;\n}\n\
+                  int main() {\n    struct Big big;\n    big.a = 1;\n    return first(big);\n}\n";
+    let error = codegen_error(source, &options);
+    assert_eq!(
+        error.to_string(),
+        "Code generation error at codegen_errors.c:6:6: Passing structs larger than 16 bytes by value is not supported yet"
+    );
+}
+
+#[test]
+fn non_constant_global_initializer_points_at_the_initializer() {
+    let error = codegen_error("int y;\nint *p = &y + 1;\nint main() {\n    return 0;\n}\n", &Options::default());
+    match error {
+        CompilerError::CodeGenError { location: Some(location), message } => {
+            assert_eq!(location.line, 2);
+            assert_eq!(message, "Initializer of global variable 'p' is not a constant expression");
+        }
+        other => panic!("expected a located error, got {:?}", other),
+    }
+}
+
+#[test]
+fn stack_parameters_point_at_the_function() {
+    // Windows passes only the first four parameters in registers
+    let options = Options { target: Target::Windows, ..Options::default() };
+    let source = "int five(int a, int b, int c, int d, int e) {\n    return e;\n}\n\
+                  int main() {\n    return five(1, 2, 3, 4, 5);\n}\n";
+    match codegen_error(source, &options) {
+        CompilerError::CodeGenError { location: Some(location), .. } => assert_eq!((location.line, location.column), (1, 5)),
+        other => panic!("expected a located error, got {:?}", other),
+    }
+}
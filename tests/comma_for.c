@@ -0,0 +1,13 @@
+// Comma operator in a for loop: i counts up while j counts down
+int main() {
+    long i;
+    long j;
+    long steps = 0;
+
+    for (i = 0, j = 10; i < j; i = i + 1, j = j - 1) {
+        steps = steps + 1;
+    }
+
+    // 5 steps, then i == j == 5
+    return steps + i + j;
+}
@@ -0,0 +1,5 @@
+// A line comment may end the file without a newline
+int main() {
+    return 0;
+}
+// the end
\ No newline at end of file
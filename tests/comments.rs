@@ -0,0 +1,40 @@
+mod common;
+
+use common::compile_with;
+use ferricc::error::CompilerError;
+use ferricc::Options;
+
+const NESTED: &str = "int main() {\n    /* outer\n       /* inner */\n    return 3;\n}\n";
+
+#[test]
+fn nested_block_comment_is_reported_at_the_inner_opening() {
+    match compile_with(NESTED, "comments.c", &Options::default()).0 {
+        Err(CompilerError::LexicalError { location, message }) => {
+            assert_eq!((location.line, location.column), (3, 8));
+            assert_eq!(message, "'/*' within block comment; block comments do not nest");
+        }
+        other => panic!("expected a lexical error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn nested_block_comment_can_be_only_a_warning() {
+    let options = Options { nested_comment_warnings: true, ..Options::default() };
+    let (result, warnings) = compile_with(NESTED, "comments.c", &options);
+    assert!(result.is_ok());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].location.line, warnings[0].location.column), (3, 8));
+}
+
+#[test]
+fn comment_markers_elsewhere_are_not_nesting() {
+    let source = "// a line comment with /* in it\nint main() { /* a // b */ return 0; } /**/ /***/";
+    let (result, warnings) = compile_with(source, "comments.c", &Options::default());
+    assert!(result.is_ok());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn line_comment_may_end_the_file_without_a_newline() {
+    assert!(compile_with("int main() { return 0; } // no newline after this", "comments.c", &Options::default()).0.is_ok());
+}
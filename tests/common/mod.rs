@@ -0,0 +1,47 @@
+//! Helpers shared by the integration tests. Each test file uses only some of them.
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process::{Command, Output, Stdio};
+
+use ferricc::error::{CompilerError, Warning};
+use ferricc::{compile, Options};
+
+/// Compile a program, returning the result and the warnings collected on the way
+pub fn compile_with(source: &str, file_name: &str, options: &Options) -> (Result<String, CompilerError>, Vec<Warning>) {
+    let mut warnings = Vec::new();
+    let result = compile(source, file_name, options, &mut io::sink(), &mut warnings);
+    (result, warnings)
+}
+
+/// Compile and link a program with options for the host, run it with the given
+/// input, and return how it exited and what it wrote
+pub fn run_with_input(source: &str, name: &str, options: &Options, input: &[u8]) -> Output {
+    let assembly = compile(source, &format!("{}.c", name), options, &mut io::sink(), &mut Vec::new()).unwrap();
+
+    let dir = env::temp_dir().join(format!("ferricc_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let asm_file = dir.join(format!("{}.s", name));
+    let exe_file = dir.join(format!("{}{}", name, options.target.executable_extension()));
+    fs::write(&asm_file, assembly).unwrap();
+
+    let status = Command::new(options.target.linker())
+        .arg("-o")
+        .arg(&exe_file)
+        .arg(&asm_file)
+        .status()
+        .unwrap();
+    assert!(status.success(), "assembling and linking failed");
+
+    let mut child = Command::new(&exe_file)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    output
+}
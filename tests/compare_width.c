@@ -0,0 +1,38 @@
+// Comparisons convert both operands to their common type and compare at its width
+
+int less(int a, int b) {
+    return a < b;
+}
+
+int main() {
+    int neg = -3;
+    int pos = 2;
+    unsigned int all_ones = 4294967295u;
+    unsigned int one = 1;
+    long big = 4294967296;
+    char c = -1;
+
+    // Signed ints keep signed ordering
+    if (!(neg < pos) || neg >= pos || !less(neg, pos) || less(pos, neg)) {
+        return 1;
+    }
+
+    // An int compared with an unsigned int is converted to unsigned: -1 becomes
+    // the largest value
+    if (neg + 2 != all_ones || !(neg > one)) {
+        return 2;
+    }
+
+    // With a long operand the int is sign-extended to 64 bits instead
+    if (!(neg < big) || all_ones >= big || big == 0) {
+        return 3;
+    }
+
+    // A char is promoted to int first, keeping its sign
+    if (c != -1 || c >= 0 || !(c < pos)) {
+        return 4;
+    }
+
+    // Comparisons yield an int 0 or 1
+    return (neg < pos) + (pos > neg) * 40 + (one == 1);
+}
@@ -0,0 +1,59 @@
+// Test #if, #elif, #else, #ifdef and #ifndef
+#define VERSION 3
+#define FEATURE
+
+#if VERSION >= 2 && VERSION < 4
+int version_check = 1;
+#elif VERSION >= 4
+int version_check = 2;
+#else
+int version_check = 3;
+#endif
+
+// Only the first true arm is taken
+#if VERSION * 2 == 6
+#define ARM 1
+#elif VERSION == 3
+#define ARM 2
+#endif
+
+// Undefined identifiers evaluate to 0
+#if UNDEFINED_NAME || !defined(FEATURE)
+#define UNDEF_CHECK 1
+#else
+#define UNDEF_CHECK 0
+#endif
+
+#ifdef FEATURE
+#ifndef MISSING
+#if defined FEATURE && !defined(MISSING)
+#define NESTED 1
+#endif
+#else
+#define NESTED 2
+#endif
+#endif
+
+// Directives inside a skipped group are not evaluated
+#if 0
+#if 1 / 0
+#error unreachable
+#endif
+#define SKIPPED 1
+#endif
+
+int main() {
+    if (version_check != 1) {
+        return 1;
+    }
+
+    if (ARM != 1 || UNDEF_CHECK != 0 || NESTED != 1) {
+        return 2;
+    }
+
+#ifdef SKIPPED
+    return 3;
+#endif
+
+    return 0;
+}
@@ -0,0 +1,61 @@
+// Test the conditional operator: only the chosen arm is evaluated, and the
+// result has the common type of both arms
+int calls;
+
+int count(int value) {
+    calls = calls + 1;
+    return value;
+}
+
+int max(int a, int b) {
+    return a > b ? a : b;
+}
+
+int main() {
+    int small = 1;
+    long big = 4294967296;
+    int value = 5;
+    int *pointer = &value;
+    char c = -1;
+
+    if (max(3, 7) != 7 || max(9, 2) != 9) {
+        return 1;
+    }
+
+    // Only one arm is evaluated
+    calls = 0;
+    if ((small ? count(10) : count(20)) != 10 || calls != 1) {
+        return 2;
+    }
+
+    // An int arm of a long result is widened to the long's 8 bytes
+    if (sizeof(small ? small : big) != 8 || (small ? small : big) != 1) {
+        return 3;
+    }
+    if ((!small ? small : big) != 4294967296) {
+        return 4;
+    }
+
+    // Arms are converted to their common type: -1 as an unsigned int is large,
+    // and an int arm of a double result is a double
+    if ((small ? -1 : 0u) < 4294967295 || sizeof(small ? 1 : 2.0) != 8) {
+        return 5;
+    }
+    if ((small ? c : 0) != -1 || (small ? 3 : 0.5) != 3) {
+        return 6;
+    }
+
+    // A null pointer constant arm takes the other arm's pointer type
+    if (*(small ? pointer : 0) != 5 || (small ? 0 : pointer) != 0) {
+        return 7;
+    }
+
+    // ?: groups to the right, and binds looser than || but tighter than =
+    value = small == 0 ? 1 : small == 1 ? 2 : 3;
+    if (value != 2 || (0 || small ? 4 : 5) != 4) {
+        return 8;
+    }
+
+    // The middle arm may be a comma expression
+    return small ? (calls = 40, calls + 2) : 0;
+}
@@ -0,0 +1,46 @@
+// Test constant folding of integer expressions
+int g = 2 + 3 * 4;
+long big = 1 << 20;
+
+int main() {
+    int a[2 * 5];
+    int i;
+    int sum;
+
+    // The folded array size gives room for all ten elements
+    for (i = 0; i < 10; i = i + 1) {
+        a[i] = i;
+    }
+
+    sum = 0;
+    for (i = 0; i < 10; i = i + 1) {
+        sum = sum + a[i];
+    }
+
+    if (sum != 45) {
+        return 1;
+    }
+
+    if (g != 14) {
+        return 2;
+    }
+
+    if (big != 1048576) {
+        return 3;
+    }
+
+    // Signed overflow wraps like the runtime arithmetic
+    if (2147483647 + 1 != -2147483647 - 1) {
+        return 4;
+    }
+
+    if ((7 - 10) / 2 != -1 || -7 % 3 != -1) {
+        return 5;
+    }
+
+    if (!(1 < 2) || ~0 != -1) {
+        return 6;
+    }
+
+    return 0;
+}
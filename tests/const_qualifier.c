@@ -0,0 +1,25 @@
+// Test reading and initializing const-qualified variables
+const int limit = 10;
+
+int main() {
+    const int x = 5;
+    int const y = x + 1;
+    char a;
+    char b;
+    const char *p;
+    char *const q = &a;
+
+    // The pointer to const may itself be reassigned
+    p = &a;
+    p = &b;
+
+    // The const pointer may be used to write to its pointee
+    *q = 'z';
+    b = 'y';
+
+    if (*p != 'y' || a != 'z') {
+        return 1;
+    }
+
+    return limit - x - y + 1;
+}
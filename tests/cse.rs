@@ -0,0 +1,64 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+/// Loading an int through the address in RAX
+const LOAD: &str = "movsxd rax, dword ptr [rax]";
+
+/// Compile a program at -O1
+fn optimized(source: &str) -> String {
+    let options = Options { opt_level: 1, ..Options::default() };
+    compile(source, "cse.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+}
+
+/// The body of a function in the generated assembly
+fn body<'a>(assembly: &'a str, name: &str) -> &'a str {
+    let start = assembly.find(&format!("\n{}:\n", name)).unwrap();
+    let end = assembly[start..].find(&format!(".{}ret:", name)).unwrap();
+    &assembly[start..start + end]
+}
+
+#[test]
+fn repeated_element_is_loaded_once() {
+    let source = "int square(int *a, int i) {\n    return a[i] * a[i];\n}\nint main() { return 0; }\n";
+    let assembly = optimized(source);
+    assert_eq!(body(&assembly, "square").matches(LOAD).count(), 1);
+
+    // Without -O1 each occurrence loads it
+    let unoptimized = compile(source, "cse.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assert_eq!(body(&unoptimized, "square").matches(LOAD).count(), 2);
+}
+
+#[test]
+fn larger_repeated_expressions_are_computed_once() {
+    let source = "int f(int *a, int i) {\n    int x;\n    x = (a[i] + a[i + 1]) * (a[i] + a[i + 1]);\n    return x;\n}\n\
+                  int main() { return 0; }\n";
+    let assembly = optimized(source);
+    assert_eq!(body(&assembly, "f").matches(LOAD).count(), 2);
+}
+
+#[test]
+fn calls_are_not_deduplicated() {
+    let source = "int next();\nint f() {\n    return next() * next();\n}\nint main() { return 0; }\n";
+    assert_eq!(body(&optimized(source), "f").matches("call next").count(), 2);
+}
+
+#[test]
+fn statements_with_assignments_are_left_alone() {
+    // The assignment inside changes a[0] between the two reads of it
+    let source = "int f(int *a) {\n    return a[0] + (a[0] = 5) + a[0];\n}\nint main() { return 0; }\n";
+    assert_eq!(body(&optimized(source), "f").matches(LOAD).count(), 2);
+}
+
+#[test]
+fn volatile_objects_are_read_each_time() {
+    let source = "int f(volatile int *p) {\n    return *p * *p;\n}\nint main() { return 0; }\n";
+    assert_eq!(body(&optimized(source), "f").matches(LOAD).count(), 2);
+}
+
+#[test]
+fn right_operands_of_logical_operators_are_not_hoisted() {
+    // Dividing before checking the divisor would trap
+    let source = "int f(int a, int b) {\n    return b != 0 && a / b + a / b > 2;\n}\nint main() { return 0; }\n";
+    assert_eq!(body(&optimized(source), "f").matches("idiv").count(), 2);
+}
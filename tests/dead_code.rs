@@ -0,0 +1,38 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+/// Compile a program at an optimization level
+fn compile_at(source: &str, opt_level: u8) -> String {
+    let options = Options { opt_level, ..Options::default() };
+    compile(source, "dead_code.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+}
+
+#[test]
+fn code_after_return_is_not_emitted() {
+    let source = "int main() {\n    return 7;\n    return 12345;\n}\n";
+    assert!(compile_at(source, 0).contains("12345"));
+
+    let assembly = compile_at(source, 1);
+    assert!(!assembly.contains("12345"));
+    assert!(assembly.contains("mov rax, 7"));
+}
+
+#[test]
+fn code_after_goto_is_not_emitted_up_to_the_label() {
+    let source = "int main() {\n    int x;\n    x = 1;\n    goto done;\n    x = 12345;\n    int y = 54321;\n\
+                  done:\n    y = 99;\n    return x + y;\n}\n";
+    let assembly = compile_at(source, 1);
+    assert!(!assembly.contains("12345"));
+    assert!(!assembly.contains("54321"));
+    assert!(assembly.contains("mov rax, 99"));
+}
+
+#[test]
+fn reachable_code_is_unaffected() {
+    let source = "int twice(int x) {\n    int result;\n    if (x > 10) {\n        result = x;\n    } else {\n\
+                  \x20       result = x * 2;\n    }\n    return result;\n}\n\
+                  int main() {\n    int i;\n    int sum;\n    sum = 0;\n    for (i = 0; i < 3; i = i + 1) {\n\
+                  \x20       sum = sum + twice(i);\n    }\n    return sum;\n}\n";
+    assert_eq!(compile_at(source, 0), compile_at(source, 1));
+}
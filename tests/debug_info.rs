@@ -0,0 +1,30 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+const PROGRAM: &str = "int twice(int x) {\n    int y = x;\n    y = y + x;\n    return y;\n}\n";
+
+/// Compile the test program, with or without line information
+fn compile_with(debug_info: bool) -> String {
+    let options = Options { debug_info, ..Options::default() };
+    compile(PROGRAM, "twice.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+}
+
+#[test]
+fn statements_get_loc_directives() {
+    let assembly = compile_with(true);
+    let lines: Vec<usize> = assembly
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(".loc 1 "))
+        .map(|rest| rest.split(' ').next().unwrap().parse().unwrap())
+        .collect();
+    assert!(assembly.contains(".file 1 \"twice.c\""));
+    assert_eq!(lines, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn no_line_information_without_debug_info() {
+    let assembly = compile_with(false);
+    assert!(!assembly.contains(".loc"));
+    assert!(!assembly.contains(".file"));
+}
@@ -0,0 +1,30 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+/// Compile a function dividing two operands of a type, and return its assembly
+fn divide(type_: &str) -> String {
+    let source = format!("{0} divide({0} a, {0} b) {{\n    return a / b;\n}}\nint main() {{\n    return 0;\n}}\n", type_);
+    compile(&source, "division.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap()
+}
+
+#[test]
+fn int_division_uses_32_bit_registers() {
+    let assembly = divide("int");
+    assert!(assembly.contains("cdq") && assembly.contains("idiv ecx"), "{}", assembly);
+    assert!(!assembly.contains("cqo"), "{}", assembly);
+}
+
+#[test]
+fn long_division_uses_64_bit_registers() {
+    let assembly = divide("long");
+    assert!(assembly.contains("cqo") && assembly.contains("idiv rcx"), "{}", assembly);
+    assert!(!assembly.contains("cdq"), "{}", assembly);
+}
+
+#[test]
+fn unsigned_division_needs_no_overflow_check() {
+    let assembly = divide("unsigned int");
+    assert!(assembly.contains("div ecx") && !assembly.contains("idiv"), "{}", assembly);
+    assert!(!assembly.contains("cmp ecx, -1"), "{}", assembly);
+}
@@ -0,0 +1,60 @@
+// Test int division, done on 32-bit registers, apart from long division, done on
+// 64-bit ones, including the most negative value divided by -1, which overflows
+int int_divide(int a, int b) {
+    return a / b;
+}
+
+int int_remainder(int a, int b) {
+    return a % b;
+}
+
+long long_divide(long a, long b) {
+    return a / b;
+}
+
+long long_remainder(long a, long b) {
+    return a % b;
+}
+
+unsigned int unsigned_divide(unsigned int a, unsigned int b) {
+    return a / b;
+}
+
+int main() {
+    int int_min = -2147483647 - 1;
+    long long_min = -9223372036854775807 - 1;
+
+    // Signed division truncates toward zero, and the remainder takes the sign of
+    // the dividend
+    if (int_divide(-7, 2) != -3 || int_remainder(-7, 2) != -1 || int_remainder(7, -2) != 1) {
+        return 1;
+    }
+
+    // INT_MIN / -1 wraps around to INT_MIN, and INT_MIN % -1 is 0
+    if (int_divide(int_min, -1) != int_min || int_remainder(int_min, -1) != 0) {
+        return 2;
+    }
+    if (int_divide(int_min, 1) != int_min || int_divide(int_min, 2) != -1073741824) {
+        return 3;
+    }
+
+    // long operands divide all 64 bits
+    if (long_divide(10000000000, 3) != 3333333333 || long_remainder(10000000000, 3) != 1) {
+        return 4;
+    }
+    if (long_divide(long_min, -1) != long_min || long_remainder(long_min, -1) != 0) {
+        return 5;
+    }
+
+    // An int mixed with a long is divided as a long
+    if (long_divide(int_min, -1) != 2147483648) {
+        return 6;
+    }
+
+    // Unsigned division treats the high bit as part of the value
+    if (unsigned_divide(4294967295, 2) != 2147483647) {
+        return 7;
+    }
+
+    return int_divide(-84, -2);
+}
@@ -0,0 +1,84 @@
+// Floating point locals, arithmetic, conversions and return values
+// Each check that fails returns its own exit code
+
+double scale = 2.5;
+float third = 0.3333333333333333;
+
+// Doubles are passed and returned in XMM registers
+double average(double a, double b) {
+    return (a + b) / 2;
+}
+
+// An int parameter mixed with a double one is converted before the arithmetic
+double times(int n, double x) {
+    return n * x;
+}
+
+// The returned double is truncated to the int return type
+int truncate(double x) {
+    return x;
+}
+
+int main() {
+    double a = 3.0;
+    double b = 2.0;
+    double sum = 0;
+    double half = 0.5e0;
+    float f = 0.25f;
+    int i;
+    int n = 7;
+
+    if (a / b != 1.5) {
+        return 1;
+    }
+    if (!(3.0 / 2.0 == 1.5)) {
+        return 2;
+    }
+
+    // Mixing with integers converts the integer to double
+    if (n / b != 3.5) {
+        return 3;
+    }
+    if (a * n - 1 != 20) {
+        return 4;
+    }
+
+    // Comparisons in both directions
+    if (!(a > b && b < a && a >= 3 && b <= 2.0)) {
+        return 5;
+    }
+
+    // Negation flips the sign; a negative zero is still false
+    if (-a + 4 != 1 || -half * -half != .25) {
+        return 6;
+    }
+    if (-0.0) {
+        return 7;
+    }
+
+    for (i = 0; i < 10; i = i + 1) {
+        sum = sum + half;
+    }
+    if (sum != 5) {
+        return 8;
+    }
+
+    if (average(a, 4) != 3.5 || times(3, scale) != 7.5) {
+        return 9;
+    }
+
+    // Float variables are stored in single precision
+    f = f * 2;
+    if (f != 0.5 || third == 1.0 / 3) {
+        return 10;
+    }
+
+    // Converting to an integer truncates toward zero
+    i = 7.9;
+    n = -a / 2;
+    if (i != 7 || n != -1 || truncate(2.99) != 2) {
+        return 11;
+    }
+
+    return average(40, 44);
+}
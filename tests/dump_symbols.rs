@@ -0,0 +1,74 @@
+use ferricc::{compile, Options};
+
+/// Compile a program with the symbol dump on, returning whether it compiled and
+/// the symbol lines logged
+fn symbols_of(source: &str) -> (bool, Vec<String>) {
+    let options = Options { dump_symbols: true, ..Options::default() };
+    let mut log = Vec::new();
+    let compiled = compile(source, "dump_symbols.c", &options, &mut log, &mut Vec::new()).is_ok();
+    let log = String::from_utf8(log).unwrap();
+    (compiled, log.lines().filter(|line| line.starts_with("Symbol: ")).map(str::to_string).collect())
+}
+
+#[test]
+fn parameters_and_locals_appear_with_their_types_and_depths() {
+    let source = "long scale;\n\
+                  int add(int a, char *b) {\n    int sum;\n    sum = a + *b;\n    {\n        long inner;\n        inner = sum;\n    }\n    return sum;\n}\n\
+                  int main() {\n    char c;\n    c = 1;\n    return add(2, &c);\n}\n";
+    let (compiled, symbols) = symbols_of(source);
+    assert!(compiled);
+    for expected in [
+        "Symbol: scale: long at depth 0",
+        "Symbol: add: int(int, char *) at depth 0",
+        "Symbol: a: int at depth 1",
+        "Symbol: b: char * at depth 1",
+        "Symbol: sum: int at depth 1",
+        "Symbol: inner: long at depth 2",
+        "Symbol: c: char at depth 1",
+    ] {
+        assert!(symbols.iter().any(|symbol| symbol == expected), "no {:?} in {:#?}", expected, symbols);
+    }
+}
+
+#[test]
+fn a_function_declared_twice_is_listed_once() {
+    let (_, symbols) = symbols_of("int f(int x);\nint f(int x) { return x; }\nint main() { return f(1); }\n");
+    assert_eq!(symbols.iter().filter(|symbol| symbol.starts_with("Symbol: f:")).count(), 1);
+}
+
+#[test]
+fn shadowing_variables_are_listed_at_each_depth() {
+    let source = "int x;\nint main() {\n    long x;\n    x = 1;\n    {\n        char x;\n        x = 2;\n    }\n    return 0;\n}\n";
+    let (_, symbols) = symbols_of(source);
+    let xs: Vec<&String> = symbols.iter().filter(|symbol| symbol.starts_with("Symbol: x:")).collect();
+    assert_eq!(xs, ["Symbol: x: int at depth 0", "Symbol: x: long at depth 1", "Symbol: x: char at depth 2"]);
+}
+
+#[test]
+fn symbols_are_dumped_when_type_checking_fails() {
+    let (compiled, symbols) = symbols_of("int main() {\n    int defined;\n    defined = 1;\n    return undefined;\n}\n");
+    assert!(!compiled);
+    assert!(symbols.iter().any(|symbol| symbol == "Symbol: defined: int at depth 1"));
+}
+
+#[test]
+fn nothing_is_dumped_by_default() {
+    let mut log = Vec::new();
+    compile("int main() { return 0; }", "dump_symbols.c", &Options::default(), &mut log, &mut Vec::new()).unwrap();
+    assert!(!String::from_utf8(log).unwrap().contains("Symbol:"));
+}
+
+#[test]
+fn else_if_chain_branches_are_at_the_same_depth() {
+    let mut source = String::from("int main() {\n    int n = 3;\n    if (n == 0) {\n        int v = 0;\n    }");
+    for i in 1..5 {
+        source.push_str(&format!(" else if (n == {0}) {{\n        int v = {0};\n    }}", i));
+    }
+    source.push_str(" else {\n        int v = 5;\n    }\n    return 0;\n}\n");
+
+    let (compiled, symbols) = symbols_of(&source);
+    assert!(compiled);
+    let vs: Vec<&String> = symbols.iter().filter(|symbol| symbol.starts_with("Symbol: v:")).collect();
+    assert_eq!(vs.len(), 6, "{:#?}", symbols);
+    assert!(vs.iter().all(|symbol| *symbol == "Symbol: v: int at depth 3"), "{:#?}", vs);
+}
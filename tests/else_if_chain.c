@@ -0,0 +1,36 @@
+// Test a chain of else if branches that each declare a variable of the same name:
+// each branch sees only its own
+int pick(int n) {
+    int result = 0;
+    if (n == 1) {
+        int value = 10;
+        result = value;
+    } else if (n == 2) {
+        long value = 20;
+        result = value;
+    } else if (n == 3) {
+        char value = 30;
+        result = value;
+    } else if (n == 4) {
+        int value = 40;
+        value = value + 1;
+        result = value;
+    } else if (n == 5) {
+        int value = 50;
+        result = value;
+    } else {
+        int value = 42;
+        result = value;
+    }
+    return result;
+}
+
+int main() {
+    if (pick(1) != 10 || pick(2) != 20 || pick(3) != 30) {
+        return 1;
+    }
+    if (pick(4) != 41 || pick(5) != 50) {
+        return 2;
+    }
+    return pick(6);
+}
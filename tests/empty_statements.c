@@ -0,0 +1,32 @@
+// Test empty statements: a loop whose work is all in its condition or step, and
+// stray semicolons, which do nothing
+int count;
+
+int step() {
+    count = count + 1;
+    return count < 10;
+}
+
+int main() {
+    int i;
+    int total = 0;
+
+    while (step());
+    if (count != 10) {
+        return 1;
+    }
+
+    for (i = 0; i < 32; total = total + (i = i + 1));
+    if (i != 32) {
+        return 2;
+    }
+
+    ;;
+    if (count == 10)
+        ;
+    else
+        return 3;
+
+    // 1 + 2 + ... + 32 = 528, and 528 - 486 = 42
+    return total - 486;
+}
@@ -0,0 +1,18 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+#[test]
+fn empty_loop_body_emits_no_code() {
+    let source = "int n;\nint step() {\n    n = n + 1;\n    return n < 10;\n}\n\
+                  int main() {\n    while (step());\n    ;\n    return n;\n}\n";
+    let assembly = compile(source, "empty.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+
+    // The loop tests its condition and jumps straight back, with no value computed
+    // for its body in between
+    let main = &assembly[assembly.find("main:").unwrap()..];
+    let lines: Vec<&str> = main.lines().map(str::trim).collect();
+    let exit = lines.iter().position(|line| line.starts_with("je .endwhile")).unwrap();
+    assert!(lines[exit + 1].starts_with("jmp .while"), "{}", main);
+    assert!(!main.contains("mov rax, 0"), "{}", main);
+}
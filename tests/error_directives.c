@@ -0,0 +1,24 @@
+// #error and #warning directives. Only the #warning on line 17 should be
+// reported; every #error is in a group that is skipped.
+
+#define VERSION 2
+
+#if VERSION < 2
+#error "VERSION must be at least 2"
+#endif
+
+#ifdef UNDEFINED_FEATURE
+#error UNDEFINED_FEATURE is not supported
+#elif VERSION == 2
+#define ANSWER 42
+#else
+#error "no answer"
+#endif
+#warning "VERSION 2 is deprecated"
+
+int main() {
+#if 0
+#error "never compiled"
+#endif
+    return ANSWER;
+}
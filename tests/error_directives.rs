@@ -0,0 +1,44 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::{compile, Options};
+
+/// Compile a program that must be stopped by the preprocessor, returning the
+/// line and column of the error and its message
+fn preprocessor_error(source: &str) -> (usize, usize, String) {
+    match compile(source, "error_directives.c", &Options::default(), &mut io::sink(), &mut Vec::new()) {
+        Err(CompilerError::PreprocessorError { location, message }) => (location.line, location.column, message),
+        other => panic!("expected a preprocessor error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn error_reports_its_message_at_the_directive() {
+    let (line, column, message) = preprocessor_error("int x;\n  #error \"unsupported platform\"\nint main() { return 0; }");
+    assert_eq!((line, column), (2, 3));
+    assert_eq!(message, "#error unsupported platform");
+}
+
+#[test]
+fn error_message_is_the_rest_of_the_line() {
+    let (_, _, message) = preprocessor_error("#error needs 64 bit long\nint main() { return 0; }");
+    assert_eq!(message, "#error needs 64 bit long");
+}
+
+#[test]
+fn error_in_a_taken_branch_aborts() {
+    let (line, _, _) = preprocessor_error("#define A 1\n#if A\n#error \"A is set\"\n#endif\nint main() { return 0; }");
+    assert_eq!(line, 3);
+}
+
+#[test]
+fn error_in_an_untaken_branch_is_ignored() {
+    let source = "#if 0\n#error \"skipped\"\n#elif 1\n#define OK 1\n#else\n#error \"skipped too\"\n#endif\nint main() { return OK; }";
+    assert!(compile(source, "error_directives.c", &Options::default(), &mut io::sink(), &mut Vec::new()).is_ok());
+}
+
+#[test]
+fn warning_does_not_stop_compilation() {
+    let source = "#warning \"this is only a warning\"\nint main() { return 0; }";
+    assert!(compile(source, "error_directives.c", &Options::default(), &mut io::sink(), &mut Vec::new()).is_ok());
+}
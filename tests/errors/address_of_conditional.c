@@ -0,0 +1,7 @@
+// The result of ?: is a value with no address of its own
+int main() {
+    int a = 1;
+    int b = 2;
+    int *p = &(a ? a : b);
+    return *p;
+}
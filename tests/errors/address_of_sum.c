@@ -0,0 +1,7 @@
+// A sum is a value with no address of its own
+int main() {
+    int a = 1;
+    int b = 2;
+    int *p = &(a + b);
+    return *p;
+}
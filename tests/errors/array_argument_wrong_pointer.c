@@ -0,0 +1,14 @@
+// An array of structs decays to a pointer to struct, which is not an int *
+struct Point {
+    int x;
+    int y;
+};
+
+int sum(int *values, int count) {
+    return values[0] + count;
+}
+
+int main() {
+    struct Point points[2];
+    return sum(points, 2);
+}
@@ -0,0 +1,6 @@
+// Arrays can only be initialized with a string literal
+
+int main() {
+    int values[3] = 5;
+    return values[0];
+}
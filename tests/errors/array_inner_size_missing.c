@@ -0,0 +1,7 @@
+// Only the outermost size of an array of arrays may be left out; the rows need
+// a size for their elements to be found
+
+int main() {
+    int m[3][];
+    return 0;
+}
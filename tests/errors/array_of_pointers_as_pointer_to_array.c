@@ -0,0 +1,8 @@
+// An array of 3 pointers decays to a pointer to a pointer, which is not a
+// pointer to an array of 3 ints
+int main() {
+    int *pointers[3];
+    int (*row)[3];
+    row = pointers;
+    return 0;
+}
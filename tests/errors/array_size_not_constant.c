@@ -0,0 +1,7 @@
+// An array size must be an integer constant expression
+int main() {
+    int n;
+    n = 4;
+    int a[n];
+    return 0;
+}
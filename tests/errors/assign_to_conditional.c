@@ -0,0 +1,7 @@
+// The result of ?: is a value, not an object that can be assigned
+int main() {
+    int a = 1;
+    int b = 2;
+    (a ? a : b) = 3;
+    return a;
+}
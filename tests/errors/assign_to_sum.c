@@ -0,0 +1,7 @@
+// A sum is a value, not an object that can be assigned
+int main() {
+    int a = 1;
+    int b = 2;
+    (a + b) = 3;
+    return a;
+}
@@ -0,0 +1,9 @@
+// break can only be used inside a loop
+int main() {
+    int x;
+    x = 1;
+    if (x) {
+        break;
+    }
+    return 0;
+}
@@ -0,0 +1,8 @@
+// An integer variable cannot be called, so the call is rejected rather than
+// left for the linker or a jump to its value
+
+int main() {
+    int count;
+    count = 3;
+    return count(1);
+}
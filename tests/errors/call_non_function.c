@@ -0,0 +1,7 @@
+// Only functions and function pointers can be called
+
+int main() {
+    int values[2];
+    values[0] = 1;
+    return values[0](1);
+}
@@ -0,0 +1,9 @@
+// A pointer does not convert to a floating type, even with a cast
+
+int main() {
+    int x;
+    double d;
+    x = 1;
+    d = (double)&x;
+    return 0;
+}
@@ -0,0 +1,12 @@
+// The arms of ?: must have types that combine: an int * and a struct do not
+struct Point {
+    int x;
+    int y;
+};
+
+int main() {
+    int value = 1;
+    struct Point point;
+    point.x = 1;
+    return *(value ? &value : point);
+}
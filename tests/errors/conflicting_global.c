@@ -0,0 +1,7 @@
+// Every declaration of a global must agree on its type
+int count;
+long count;
+
+int main() {
+    return 0;
+}
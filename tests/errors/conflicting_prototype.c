@@ -0,0 +1,11 @@
+// A definition must match the prototype declared before it
+
+int f(int);
+
+int f(char x, char y) {
+    return x + y;
+}
+
+int main() {
+    return f(1, 2);
+}
@@ -0,0 +1,6 @@
+// Assigning to a const variable must be rejected
+int main() {
+    const int x = 5;
+    x = 6;
+    return x;
+}
@@ -0,0 +1,7 @@
+// Writing through a pointer to const must be rejected
+int main() {
+    char c;
+    const char *p = &c;
+    *p = 'a';
+    return 0;
+}
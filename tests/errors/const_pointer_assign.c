@@ -0,0 +1,8 @@
+// Reassigning a const pointer must be rejected
+int main() {
+    char c;
+    char d;
+    char *const p = &c;
+    p = &d;
+    return 0;
+}
@@ -0,0 +1,8 @@
+// A const volatile object can still not be assigned
+
+const volatile int status = 1;
+
+int main() {
+    status = 2;
+    return status;
+}
@@ -0,0 +1,5 @@
+// continue can only be used inside a loop
+int main() {
+    continue;
+    return 0;
+}
@@ -0,0 +1,7 @@
+// A constant zero divisor is rejected at compile time, even after folding
+
+int main() {
+    int x = 10;
+    int y = 1 / 0;
+    return x / (2 - 2) + y;
+}
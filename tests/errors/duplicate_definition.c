@@ -0,0 +1,13 @@
+// A function can only be given a body once
+
+int f(int x) {
+    return x;
+}
+
+int f(int x) {
+    return x + 1;
+}
+
+int main() {
+    return f(1);
+}
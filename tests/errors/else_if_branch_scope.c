@@ -0,0 +1,11 @@
+// A variable declared in one branch of an else if chain is not visible in the next
+int main() {
+    int n = 2;
+    if (n == 1) {
+        int only = 1;
+        n = only;
+    } else if (n == 2) {
+        return only;
+    }
+    return n;
+}
@@ -0,0 +1,4 @@
+// A character constant needs at least one character
+int main() {
+    return '';
+}
@@ -0,0 +1,11 @@
+// An #error in a group that is compiled stops compilation
+
+#define VERSION 1
+
+#if VERSION < 2
+#error "VERSION must be at least 2"
+#endif
+
+int main() {
+    return 0;
+}
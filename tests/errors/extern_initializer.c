@@ -0,0 +1,6 @@
+// A block-scope extern declaration cannot define the variable
+
+int main() {
+    extern int x = 1;
+    return x;
+}
@@ -0,0 +1,14 @@
+// A function name stands for the function's address, but is not a variable
+// that can be assigned
+int helper() {
+    return 1;
+}
+
+int other() {
+    return 2;
+}
+
+int main() {
+    helper = other;
+    return helper();
+}
@@ -0,0 +1,10 @@
+// Calls through a function pointer are checked against its parameters
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int main() {
+    int (*op)(int, int) = add;
+    return (*op)(1);
+}
@@ -0,0 +1,7 @@
+// Only one declaration of a global may initialize it
+int count = 1;
+int count = 2;
+
+int main() {
+    return count;
+}
@@ -0,0 +1,10 @@
+// A goto must name a label defined in the same function
+int helper() {
+target:
+    return 1;
+}
+
+int main() {
+    goto target;
+    return 0;
+}
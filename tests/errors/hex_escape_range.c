@@ -0,0 +1,4 @@
+// A hex escape that doesn't fit in a byte must be rejected
+int main() {
+    return '\x141';
+}
@@ -0,0 +1,6 @@
+// Pasting tokens that do not form a single token must be rejected
+#define PASTE(a, b) a ## b
+
+int main() {
+    return PASTE(+, /);
+}
@@ -0,0 +1,5 @@
+// A decimal literal larger than any integer type
+int main() {
+    unsigned long x = 18446744073709551616;
+    return 0;
+}
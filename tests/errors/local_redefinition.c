@@ -0,0 +1,6 @@
+// A local cannot be declared twice in the same scope
+int main() {
+    int x = 1;
+    long x = 2;
+    return x;
+}
@@ -0,0 +1,5 @@
+// The . operator only applies to structs
+int main() {
+    int n = 5;
+    return n.x;
+}
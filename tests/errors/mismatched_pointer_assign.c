@@ -0,0 +1,8 @@
+// Assigning a long * to an int * is rejected although long and int convert to
+// each other as values
+int main() {
+    long l = 1;
+    int *p;
+    p = &l;
+    return 0;
+}
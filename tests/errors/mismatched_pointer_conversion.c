@@ -0,0 +1,6 @@
+// Pointers to different arithmetic types do not convert to each other: an int *
+// cannot initialize a double *
+int main() {
+    double *d = (int *)0 + 1;
+    return 0;
+}
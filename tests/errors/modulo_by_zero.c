@@ -0,0 +1,6 @@
+// The divisor is folded to zero, so the remainder can never be computed
+
+int main() {
+    int x = 10;
+    return x % (3 * 4 - 12);
+}
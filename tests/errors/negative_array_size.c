@@ -0,0 +1,5 @@
+// An array size must be a positive constant
+int main() {
+    int a[-1];
+    return 0;
+}
@@ -0,0 +1,7 @@
+// Block comments do not nest: the first */ ends the comment
+int main() {
+    /* commented out:
+    int x = 1; /* one */
+    */
+    return 0;
+}
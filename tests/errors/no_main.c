@@ -0,0 +1,4 @@
+// An executable needs a main function to start in
+int helper(int x) {
+    return x + 1;
+}
@@ -0,0 +1,6 @@
+// Only an integer constant 0 is a null pointer constant; 1 is not a pointer
+int main() {
+    int *p;
+    p = 1;
+    return 0;
+}
@@ -0,0 +1,9 @@
+// The outermost block of a function shares the scope of its parameters
+int f(int x) {
+    int x = 2;
+    return x;
+}
+
+int main() {
+    return f(1);
+}
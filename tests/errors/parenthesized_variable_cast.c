@@ -0,0 +1,10 @@
+// A variable in parentheses is not a type name, so it cannot be cast to; the
+// expression after it is a syntax error rather than a misparsed cast
+
+int main() {
+    int count;
+    int x;
+    x = 3;
+    count = (count)x;
+    return count;
+}
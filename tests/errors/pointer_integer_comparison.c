@@ -0,0 +1,10 @@
+// Only the constant 0 is a null pointer; a pointer does not compare with any
+// other integer
+
+int main() {
+    int x;
+    int *p;
+    x = 0;
+    p = &x;
+    return p == 1;
+}
@@ -0,0 +1,4 @@
+// void has no size
+int main() {
+    return sizeof(void);
+}
@@ -0,0 +1,6 @@
+// A string longer than the array it initializes is rejected
+
+int main() {
+    char s[3] = "abcd";
+    return s[0];
+}
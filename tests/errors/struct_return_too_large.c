@@ -0,0 +1,18 @@
+// Only structs of up to 16 bytes can be returned, in RAX and RDX; returning a
+// larger one through a hidden pointer is not supported yet
+struct Big {
+    long a;
+    long b;
+    long c;
+};
+
+struct Big make(long a) {
+    struct Big big;
+    big.a = a;
+    return big;
+}
+
+int main() {
+    struct Big big = make(1);
+    return 0;
+}
@@ -0,0 +1,5 @@
+// Only arrays and pointers can be subscripted
+int main() {
+    int x = 1;
+    return x[1];
+}
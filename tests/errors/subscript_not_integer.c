@@ -0,0 +1,6 @@
+// An array subscript must be an integer
+int main() {
+    int a[3];
+    double d = 1.0;
+    return a[d];
+}
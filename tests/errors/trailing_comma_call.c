@@ -0,0 +1,8 @@
+// A call cannot end its argument list with a comma
+int add(int a, int b) {
+    return a + b;
+}
+
+int main() {
+    return add(1, 2,);
+}
@@ -0,0 +1,6 @@
+// A global referenced before its declaration must be rejected
+int main() {
+    return counter;
+}
+
+int counter = 3;
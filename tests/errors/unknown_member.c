@@ -0,0 +1,6 @@
+// Accessing a member the struct does not have must be rejected
+int main() {
+    struct Point { int x; int y; } p;
+    p.x = 1;
+    return p.z;
+}
@@ -0,0 +1,5 @@
+// A conditional group without #endif must be rejected
+#if 1
+int main() {
+    return 0;
+}
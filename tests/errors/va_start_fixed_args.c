@@ -0,0 +1,12 @@
+// va_start needs a function that takes variable arguments
+#include <stdarg.h>
+
+int first(int count) {
+    va_list ap;
+    va_start(ap, count);
+    return va_arg(ap, int);
+}
+
+int main() {
+    return first(1);
+}
@@ -0,0 +1,9 @@
+// A function returning void has no value to assign
+void log_value(int value) {
+}
+
+int main() {
+    int x;
+    x = log_value(1);
+    return x;
+}
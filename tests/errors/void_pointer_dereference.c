@@ -0,0 +1,8 @@
+// A pointer to void must be converted before it is dereferenced
+int main() {
+    int x;
+    void *p;
+    x = 1;
+    p = &x;
+    return *p;
+}
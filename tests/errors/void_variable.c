@@ -0,0 +1,5 @@
+// A variable cannot have type void
+int main() {
+    void nothing;
+    return 0;
+}
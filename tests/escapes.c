@@ -0,0 +1,29 @@
+// Test hex, octal and single-character escape sequences
+int main() {
+    char *ansi = "\033[0m";
+    char *mixed = "\x41\102\a\b\f\v";
+
+    if ('\x41' != 'A' || '\101' != 'A' || '\0' != 0) {
+        return 1;
+    }
+
+    // An escape ends at the first character that is not a digit of its base
+    if (ansi[0] != 27 || ansi[1] != '[' || ansi[2] != '0' || ansi[3] != 'm') {
+        return 2;
+    }
+
+    if (mixed[0] != 'A' || mixed[1] != 'B' || mixed[2] != 7 || mixed[3] != 8) {
+        return 3;
+    }
+
+    if (mixed[4] != 12 || mixed[5] != 11 || mixed[6] != 0) {
+        return 4;
+    }
+
+    // Bytes above 127 are negative in a signed char
+    if ('\xff' != -1 || '\377' != -1) {
+        return 5;
+    }
+
+    return 0;
+}
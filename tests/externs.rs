@@ -0,0 +1,32 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+/// Compile a program and return the `.extern` directives of its assembly
+fn externs(source: &str) -> Vec<String> {
+    let assembly = compile(source, "externs.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assembly
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(".extern "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn program_without_calls_declares_no_externs() {
+    assert!(externs("int main() { return 0; }").is_empty());
+}
+
+#[test]
+fn only_called_library_functions_are_declared() {
+    let source = "#include <stdio.h>\nint main() { putchar(65); puts(\"a\"); putchar(10); return 0; }";
+    assert_eq!(externs(source), ["putchar", "puts"]);
+}
+
+#[test]
+fn locally_defined_puts_is_not_extern() {
+    let source = "int count;\n\
+                  int puts(char *s) { count = count + 1; return 0; }\n\
+                  int main() { puts(\"hello\"); return count; }";
+    assert!(externs(source).is_empty());
+}
@@ -0,0 +1,32 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+/// Compile a program and return the instructions of one of its functions
+fn function_body(source: &str, name: &str) -> Vec<String> {
+    let assembly = compile(source, "frame_layout.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assembly
+        .lines()
+        .skip_while(|line| line.trim() != format!("{}:", name))
+        .take_while(|line| line.trim() != "ret")
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn frame_is_allocated_once_in_the_prologue() {
+    let body = function_body(
+        "int f(int a) { char c = 1; long l = 2; { int x = 3; } { long y[4]; y[0] = a; } return c + l; }",
+        "f",
+    );
+    let allocations: Vec<&String> = body.iter().filter(|line| line.starts_with("sub rsp")).collect();
+    assert_eq!(allocations.len(), 1);
+    assert_eq!(body[3], *allocations[0]);
+    assert!(!body.iter().any(|line| line.starts_with("push") && line != "push rbp"));
+}
+
+#[test]
+fn frame_keeps_the_stack_aligned() {
+    let body = function_body("int f() { char c = 1; return c; }", "f");
+    assert!(body.contains(&"sub rsp, 16".to_string()));
+}
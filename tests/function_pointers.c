@@ -0,0 +1,56 @@
+// Function pointers: declared, assigned functions, passed around and called through
+
+#include <stdio.h>
+
+#define OPERATION struct Operation { char symbol; int (*apply)(int, int); }
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int multiply(int a, int b) {
+    return a * b;
+}
+
+// A global function pointer, initialized with a function
+int (*combine)(int, int) = add;
+
+// Calls the function it is given on both arguments
+int apply(int (*f)(int, int), int a, int b) {
+    return f(a, b);
+}
+
+int main() {
+    int (*op)(int, int);
+    int (*other)(int, int) = &multiply, (**indirect)(int, int);
+    int (*print)(const char *format, ...) = printf;
+    OPERATION operation;
+
+    // Assigning two functions in turn to the same pointer
+    op = add;
+    if (op(2, 3) != 5) {
+        return 1;
+    }
+    op = multiply;
+    if (op(2, 3) != 6 || (*op)(4, 5) != 20) {
+        return 2;
+    }
+
+    // Pointers are compared and passed like other values
+    if (op != other || op == add || apply(add, 20, 22) != 42 || apply(other, 6, 7) != 42) {
+        return 3;
+    }
+
+    // A pointer to a function pointer, and a member that is one
+    indirect = &op;
+    operation.symbol = '+';
+    operation.apply = combine;
+    if ((*indirect)(3, 3) != 9 || operation.apply(40, 2) != 42) {
+        return 4;
+    }
+
+    // A library function, called through a pointer with variable arguments
+    print("%d %c %d = %d\n", 40, operation.symbol, 2, combine(40, 2));
+
+    return apply(combine, 40, 2);
+}
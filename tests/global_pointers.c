@@ -0,0 +1,37 @@
+// Test pointer and struct globals, with string and address initializers
+struct point {
+    long x;
+    long y;
+} origin;
+
+long after = 5;
+char *msg = "hi";
+int counter;
+int *counter_ptr = &counter;
+
+int main() {
+    struct point *p;
+
+    if (msg[0] != 'h' || msg[1] != 'i' || msg[2] != 0) {
+        return 1;
+    }
+
+    // The pointer global holds the address of another global
+    *counter_ptr = 3;
+    if (counter != 3) {
+        return 2;
+    }
+
+    msg = "ok";
+    if (msg[0] != 'o') {
+        return 3;
+    }
+
+    // The struct occupies its own storage without overlapping its neighbours
+    p = &origin;
+    if (p != &origin || after != 5) {
+        return 4;
+    }
+
+    return 0;
+}
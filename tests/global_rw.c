@@ -0,0 +1,26 @@
+// Globals are read and written relative to RIP, so the program links as a
+// position-independent executable
+
+int counter = 40;
+long total;
+char flag;
+int *pointer = &counter;
+
+void bump(int by) {
+    counter = counter + by;
+    total = total + counter;
+}
+
+int main() {
+    bump(1);
+    bump(1);
+
+    // 41 + 42 == 83
+    if (total != 83 || flag != 0) {
+        return 1;
+    }
+
+    flag = 'y';
+    *pointer = *pointer - 1;
+    return counter + (flag == 'y');
+}
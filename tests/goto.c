@@ -0,0 +1,22 @@
+// Test forward and backward gotos
+int main() {
+    int i;
+    int sum;
+
+    i = 0;
+    sum = 0;
+
+again:
+    sum = sum + i;
+    i = i + 1;
+    if (i < 5) {
+        goto again;  // backward jump
+    }
+
+    goto done;  // forward jump
+    sum = 100;
+
+done:
+    // 0 + 1 + 2 + 3 + 4 == 10
+    return sum - 10;
+}
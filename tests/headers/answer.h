@@ -0,0 +1,2 @@
+// Found only through an include path given with -I
+#define ANSWER 42
@@ -0,0 +1,2 @@
+// Shares its name with ../answer.h, to show which of the two an include finds
+#define ANSWER 7
@@ -0,0 +1,55 @@
+use std::io;
+use std::path::PathBuf;
+
+use ferricc::ast::Node;
+use ferricc::error::CompilerError;
+use ferricc::{parse, Options};
+
+/// Parse a program as if it were a file in tests/, so quoted includes are found
+/// relative to that directory and `<answer.h>` is found in tests/headers
+fn parse_in_tests(source: &str) -> ferricc::error::Result<Node> {
+    let file_name = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/include_directive.c");
+    let options = Options { include_paths: vec![PathBuf::from("tests/headers")], ..Options::default() };
+    parse(source, &file_name.to_string_lossy(), &options, &mut io::sink(), &mut Vec::new())
+}
+
+/// The names of a program's top-level declarations, in order
+fn declared_names(program: &Node) -> Vec<&str> {
+    let Node::Program(declarations) = program else { panic!("not a program") };
+    declarations
+        .iter()
+        .map(|declaration| match declaration {
+            Node::VarDecl { name, .. } | Node::FunctionDecl { name, .. } => name.as_str(),
+            other => panic!("unexpected declaration {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn quoted_include_keeps_the_next_line() {
+    let program = parse_in_tests("#include \"headers/answer.h\"\nint first = ANSWER;\nint main() { return first; }").unwrap();
+    assert_eq!(declared_names(&program), ["first", "main"]);
+}
+
+#[test]
+fn angled_include_keeps_the_next_line() {
+    let program = parse_in_tests("#include <answer.h>\nint first = ANSWER;\nint main() { return first; }").unwrap();
+    assert_eq!(declared_names(&program), ["first", "main"]);
+}
+
+#[test]
+fn consecutive_includes_and_a_declaration_after_them() {
+    let source = "#include <answer.h>\n#include \"headers/answer.h\"\n#include <stdbool.h>\nbool first;\nint main() { return ANSWER; }";
+    let program = parse_in_tests(source).unwrap();
+    assert_eq!(declared_names(&program), ["first", "main"]);
+}
+
+#[test]
+fn filename_must_be_on_the_directive_line() {
+    for source in ["#include\n\"headers/answer.h\"\nint main() { return 0; }", "#include <answer.h\n>\nint main() { return 0; }"] {
+        match parse_in_tests(source) {
+            Err(CompilerError::PreprocessorError { .. }) => {}
+            other => panic!("expected a preprocessor error for {:?}, got {:?}", source, other),
+        }
+    }
+}
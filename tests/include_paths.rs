@@ -0,0 +1,56 @@
+use std::io;
+use std::path::PathBuf;
+
+use ferricc::{compile, Options};
+
+const PROGRAM: &str = "#include <answer.h>\nint main() { return ANSWER; }";
+
+const QUOTED_PROGRAM: &str = "#include \"answer.h\"\nint main() { return ANSWER; }";
+
+/// Compile the test program searching the given include directories
+fn compile_with(include_paths: Vec<PathBuf>) -> ferricc::error::Result<String> {
+    compile_source(PROGRAM, "include_paths.c", include_paths)
+}
+
+/// Compile a program as the given file, searching the given include directories
+fn compile_source(source: &str, file_name: &str, include_paths: Vec<PathBuf>) -> ferricc::error::Result<String> {
+    let options = Options { include_paths, ..Options::default() };
+    compile(source, file_name, &options, &mut io::sink(), &mut Vec::new())
+}
+
+#[test]
+fn relative_include_path_is_searched() {
+    let assembly = compile_with(vec![PathBuf::from("tests/headers")]).unwrap();
+    assert!(assembly.contains("mov rax, 42"));
+}
+
+#[test]
+fn absolute_include_path_is_searched() {
+    let headers = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/headers");
+    assert!(compile_with(vec![headers]).is_ok());
+}
+
+#[test]
+fn header_outside_include_paths_is_not_found() {
+    assert!(compile_with(Vec::new()).is_err());
+}
+
+#[test]
+fn quoted_include_falls_back_to_include_paths() {
+    let assembly = compile_source(QUOTED_PROGRAM, "include_paths.c", vec![PathBuf::from("tests/headers")]).unwrap();
+    assert!(assembly.contains("mov rax, 42"));
+    assert!(compile_source(QUOTED_PROGRAM, "include_paths.c", Vec::new()).is_err());
+}
+
+#[test]
+fn quoted_include_prefers_the_current_directory() {
+    // Beside tests/headers/main.c is the header defining 42, while the include
+    // path has one defining 7
+    let include_paths = vec![PathBuf::from("tests/headers/fallback")];
+    let quoted = compile_source(QUOTED_PROGRAM, "tests/headers/main.c", include_paths.clone()).unwrap();
+    assert!(quoted.contains("mov rax, 42"));
+
+    // A system header is only looked for in the include paths
+    let angled = compile_source(PROGRAM, "tests/headers/main.c", include_paths).unwrap();
+    assert!(angled.contains("mov rax, 7"));
+}
@@ -0,0 +1,45 @@
+// Integer literals in every radix, and the types their values give them
+
+int main() {
+    unsigned long max = 0xFFFFFFFFFFFFFFFF;
+    unsigned long big = 18446744073709551615;
+    long wide = 4294967296;
+    long shifted = 1L << 40;
+
+    // Binary literals, a GCC extension
+    if (0b101 != 5 || 0B11111111 != 255 || 0b0 != 0) {
+        return 1;
+    }
+
+    // The largest unsigned long in hexadecimal and decimal
+    if (max != big || max + 1 != 0) {
+        return 2;
+    }
+
+    // A hexadecimal literal too large for int is an unsigned int, which wraps
+    // around when negated, while a decimal one is a long
+    if (-0xFFFFFFFF != 1 || -4294967295 + wide != 1) {
+        return 3;
+    }
+
+    // Octal literals
+    if (017 != 15 || 0777 != 511) {
+        return 4;
+    }
+
+    // An l or L suffix makes a literal at least a long, whatever its value, and
+    // ul an unsigned long
+    if (sizeof(2L) != 8 || sizeof(2l) != 8 || sizeof(2UL) != 8 || sizeof(2lu) != 8 || sizeof(2) != 4) {
+        return 5;
+    }
+    if (shifted != 1099511627776 || 100000L * 100000L != 10000000000) {
+        return 6;
+    }
+    // A hexadecimal long literal is a long when its value fits, so it stays
+    // positive when widened, and an unsigned long one wraps when negated
+    if (0xFFFFFFFFL + 1 != 4294967296 || -1UL != 0xFFFFFFFFFFFFFFFF) {
+        return 7;
+    }
+
+    return 0;
+}
@@ -0,0 +1,48 @@
+use std::io;
+
+use ferricc::ast::Node;
+use ferricc::{compile, intrinsics, parse, Options};
+
+/// Parse a program, fold its intrinsics and return the value main returns
+fn folded_return(source: &str) -> Node {
+    let program = parse(source, "intrinsics.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    let Node::Program(declarations) = intrinsics::fold_program(program) else { unreachable!() };
+    let main = declarations.into_iter().find(|declaration| {
+        matches!(declaration, Node::FunctionDecl { name, body: Some(_), .. } if name == "main")
+    });
+    let Some(Node::FunctionDecl { body: Some(body), .. }) = main else { panic!("no main") };
+    let Node::BlockStmt(statements, _) = *body else { panic!("main has no block") };
+    match statements.into_iter().last() {
+        Some(Node::ReturnStmt(Some(value), _)) => *value,
+        other => panic!("main does not end in a return: {:?}", other),
+    }
+}
+
+#[test]
+fn strlen_of_a_literal_becomes_its_length() {
+    let value = folded_return("#include <string.h>\nint main() { return strlen(\"hello\"); }");
+    assert!(matches!(value, Node::ULongLiteral(5, _)), "got {:?}", value);
+}
+
+#[test]
+fn strlen_of_a_variable_is_still_called() {
+    let value = folded_return("#include <string.h>\nint main() { char *s = \"hi\"; return strlen(s); }");
+    assert!(matches!(value, Node::FunctionCall { .. }), "got {:?}", value);
+}
+
+#[test]
+fn a_strlen_defined_by_the_program_is_still_called() {
+    let value = folded_return("int strlen(char *s) { return 7; }\nint main() { return strlen(\"hello\"); }");
+    assert!(matches!(value, Node::FunctionCall { .. }), "got {:?}", value);
+}
+
+#[test]
+fn no_builtin_keeps_the_library_call() {
+    let source = "#include <string.h>\nint main() { return strlen(\"hello\"); }";
+    let folded = compile(source, "intrinsics.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assert!(!folded.contains("strlen"));
+
+    let options = Options { no_builtin: true, ..Options::default() };
+    let called = compile(source, "intrinsics.c", &options, &mut io::sink(), &mut Vec::new()).unwrap();
+    assert!(called.contains("call strlen"));
+}
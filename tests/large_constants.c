@@ -0,0 +1,26 @@
+// Test constants that do not fit in a 32-bit immediate, which are loaded with
+// the 64-bit immediate form of mov
+int main() {
+    long min = -9223372036854775807L - 1;
+    long max = 9223372036854775807L;
+    long big;
+    long small;
+
+    if (min + 1 != -9223372036854775807L || min >> 62 != -2 || max + min != -1) {
+        return 1;
+    }
+
+    // Just past the range of an int in either direction
+    big = 4294967296;
+    small = -2147483649;
+    if (big >> 32 != 1 || small + 2147483649 != 0 || big - 4294967295 != 1) {
+        return 2;
+    }
+
+    // A double whose bits are a 64-bit constant
+    double half = 0.5;
+    if (half * 4 != 2) {
+        return 3;
+    }
+    return 42;
+}
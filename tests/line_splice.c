@@ -0,0 +1,18 @@
+// Test backslash-newline line splicing
+#define TOTAL \
+    (40 + 2)
+#define GREETING "Hello, \
+World"
+
+int main() {
+    char *s = GREETING;
+    int n = TO\
+TAL;
+
+    // The spliced string has no newline in it
+    if (s[6] != ' ' || s[7] != 'W') {
+        return 1;
+    }
+
+    return n - 42;
+}
@@ -0,0 +1,45 @@
+// Test a self-referential struct: each node points to the next through a
+// pointer to its own struct type, named by tag alone
+struct Node {
+    int value;
+    struct Node *next;
+};
+
+// A struct can contain another struct, referred to by its tag
+struct List {
+    struct Node *head;
+    struct Node last;
+};
+
+int sum(struct Node *node) {
+    int total = 0;
+    while (node) {
+        total = total + node->value;
+        node = node->next;
+    }
+    return total;
+}
+
+int main() {
+    struct Node first;
+    struct Node second;
+    struct List list;
+
+    first.value = 12;
+    first.next = &second;
+    second.value = 30;
+    second.next = &list.last;
+    list.last.value = 0;
+    // A null pointer ends the list
+    list.last.next = 0;
+    list.head = &first;
+
+    // A pointer to the struct is 8 bytes, whatever the struct holds
+    if (sizeof(first.next) != 8 || sizeof(list.head->next) != 8) {
+        return 1;
+    }
+    if (list.head->next->next->value != 0 || list.head->next->next->next != 0) {
+        return 2;
+    }
+    return sum(list.head);
+}
@@ -0,0 +1,37 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::{compile, Options};
+
+/// Compile a program that must be rejected by the lexer, returning the message
+fn lexical_error(source: &str) -> String {
+    match compile(source, "literal_errors.c", &Options::default(), &mut io::sink(), &mut Vec::new()) {
+        Err(CompilerError::LexicalError { message, .. }) => message,
+        other => panic!("expected a lexical error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn decimal_overflow_names_the_literal() {
+    assert_eq!(
+        lexical_error("long x = 99999999999999999999;"),
+        "Integer literal 99999999999999999999 is too large for any integer type"
+    );
+}
+
+#[test]
+fn hexadecimal_overflow_keeps_its_prefix() {
+    assert!(lexical_error("long x = 0x10000000000000000;").contains("0x10000000000000000"));
+}
+
+#[test]
+fn prefix_without_digits_is_invalid() {
+    assert_eq!(lexical_error("int x = 0b;"), "Invalid binary literal");
+    assert_eq!(lexical_error("int x = 0x;"), "Invalid hexadecimal literal");
+}
+
+#[test]
+fn largest_unsigned_long_is_accepted() {
+    let source = "int main() { unsigned long x = 0xFFFFFFFFFFFFFFFF; return x == 18446744073709551615; }";
+    assert!(compile(source, "literal_errors.c", &Options::default(), &mut io::sink(), &mut Vec::new()).is_ok());
+}
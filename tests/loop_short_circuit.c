@@ -0,0 +1,83 @@
+// Test that loop conditions built from && and || stop at the operand that decides
+// them: the next operand would read past the end of an array, or through a null
+// pointer, on the iteration that ends the loop
+int values[4];
+int reads;
+int out_of_bounds;
+
+// Read an element of values, recording a read past its end
+int at(int i) {
+    reads = reads + 1;
+    if (i >= 4) {
+        out_of_bounds = 1;
+        return 0;
+    }
+    return values[i];
+}
+
+int main() {
+    int i;
+    int n;
+    int *p;
+
+    values[0] = 5;
+    values[1] = 6;
+    values[2] = 7;
+    values[3] = 8;
+    n = 4;
+
+    // No element is zero, so only i < n ends the loop
+    i = 0;
+    while (i < n && at(i) != 0) {
+        i = i + 1;
+    }
+    if (i != 4 || reads != 4 || out_of_bounds) {
+        return 1;
+    }
+
+    // The same in a for loop, where the condition also runs after each increment
+    reads = 0;
+    for (i = 0; i < n && at(i) != 0; i = i + 1) {
+    }
+    if (i != 4 || reads != 4 || out_of_bounds) {
+        return 2;
+    }
+
+    // With || the loop goes on while the first operand holds, without the second
+    reads = 0;
+    i = 0;
+    while (!(i >= n || at(i) == 0)) {
+        i = i + 1;
+    }
+    if (i != 4 || reads != 4 || out_of_bounds) {
+        return 3;
+    }
+
+    // A loop that stops early reads each element up to the one that ends it
+    values[2] = 0;
+    reads = 0;
+    i = 0;
+    while (i < n && at(i) != 0) {
+        i = i + 1;
+    }
+    if (i != 2 || reads != 3) {
+        return 4;
+    }
+
+    // A null pointer ends the loop before it is dereferenced
+    p = &values[0];
+    i = 0;
+    while (p != 0 && *p != 0) {
+        i = i + 1;
+        if (i == 2) {
+            p = 0;
+        }
+    }
+    if (i != 2) {
+        return 5;
+    }
+    for (p = 0; p && *p; ) {
+        return 6;
+    }
+    return 42;
+}
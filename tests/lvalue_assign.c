@@ -0,0 +1,56 @@
+// Assignment to every kind of lvalue: array elements, members and dereferences
+
+#define PAIR struct Pair { int first; char tag; long second; }
+
+long g[3];
+
+int main() {
+    int arr[4];
+    int i;
+    int *p;
+    PAIR s;
+    PAIR pairs[2];
+    PAIR *q;
+
+    // arr[i] = v with a variable index, read back in reverse
+    for (i = 0; i < 4; i = i + 1) {
+        arr[i] = 10 * i;
+    }
+    if (arr[3] != 30 || arr[0] != 0) {
+        return 1;
+    }
+
+    // *(p + 1) = v writes the element after the one p points to
+    p = arr;
+    *(p + 1) = 11;
+    p[2] = *(p + 1) + 1;
+    if (arr[1] != 11 || arr[2] != 12) {
+        return 2;
+    }
+
+    // s.f = v writes only that member, with the member's own width
+    s.first = -1;
+    s.second = 5;
+    s.tag = 300;
+    if (s.first != -1 || s.second != 5 || s.tag != 44) {
+        return 3;
+    }
+
+    // Members of array elements, and through pointers
+    pairs[1].second = 7;
+    q = &pairs[1];
+    q->first = 3;
+    if (pairs[1].first + pairs[1].second != 10) {
+        return 4;
+    }
+
+    // Chained assignment to a global array element and a member
+    g[2] = s.first = 42;
+    if (s.first != 42) {
+        return 5;
+    }
+
+    // Struct assignment into an array element copies the whole struct
+    pairs[0] = s;
+    return pairs[0].first + g[2] - 42;
+}
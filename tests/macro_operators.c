@@ -0,0 +1,33 @@
+// Test function-like macros with the # and ## operators
+#define STR(x) #x
+#define CONCAT(a, b) a ## b
+#define SQUARE(x) ((x) * (x))
+#define ADD(a, b) (a + b)
+#define ZERO() 0
+
+int main() {
+    char *name = STR(hello world);
+    char *expr = STR(a+1);
+    int value12 = 7;
+
+    // Pasting builds the identifier value12 and the number 34
+    if (CONCAT(value, 12) != 7 || CONCAT(3, 4) != 34) {
+        return 1;
+    }
+
+    // A multi-token argument is joined with single spaces
+    if (name[5] != ' ' || name[6] != 'w' || name[11] != 0) {
+        return 2;
+    }
+
+    if (expr[0] != 'a' || expr[1] != ' ' || expr[2] != '+' || expr[4] != '1') {
+        return 3;
+    }
+
+    // Arguments are expanded before substitution, and nested commas stay in one argument
+    if (SQUARE(ADD(1, 2)) != 9 || ZERO() != 0) {
+        return 4;
+    }
+
+    return 0;
+}
@@ -0,0 +1,59 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::{compile, Options};
+
+/// Compile a program, returning the error that stopped it
+fn compile_error(source: &str) -> CompilerError {
+    compile(source, "macro_recursion.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap_err()
+}
+
+#[test]
+fn self_referential_macro_expands_once() {
+    // A expands to A, which is left alone and so names an undefined variable
+    match compile_error("#define A A\nint main() {\n    return A;\n}\n") {
+        CompilerError::TypeError { location, message } => {
+            assert_eq!((location.line, location.column), (3, 12));
+            assert_eq!(message, "Undefined variable: A");
+        }
+        other => panic!("expected a type error, got {:?}", other),
+    }
+}
+
+#[test]
+fn mutually_referential_macros_terminate() {
+    // PING becomes PONG, then PING again, where expansion stops
+    let source = "#define PING PONG\n#define PONG PING\nint main() {\n    return PING;\n}\n";
+    match compile_error(source) {
+        CompilerError::TypeError { message, .. } => assert_eq!(message, "Undefined variable: PING"),
+        other => panic!("expected a type error, got {:?}", other),
+    }
+}
+
+#[test]
+fn deeply_nested_expansion_is_an_error() {
+    // Each macro expands to the next, 300 deep, without ever repeating a name
+    let mut source = String::from("#define M300 42\n");
+    for i in 0..300 {
+        source.push_str(&format!("#define M{} M{}\n", i, i + 1));
+    }
+    source.push_str("int main() {\n    return M0;\n}\n");
+
+    match compile_error(&source) {
+        CompilerError::PreprocessorError { location, message } => {
+            assert_eq!(location.line, 303);
+            assert_eq!(message, "Expanding macro M200 nests more than 200 macro expansions");
+        }
+        other => panic!("expected a preprocessor error, got {:?}", other),
+    }
+}
+
+#[test]
+fn long_chains_within_the_limit_expand() {
+    let mut source = String::from("#define M100 42\n");
+    for i in 0..100 {
+        source.push_str(&format!("#define M{} M{}\n", i, i + 1));
+    }
+    source.push_str("int main() {\n    return M0;\n}\n");
+    assert!(compile(&source, "macro_recursion.c", &Options::default(), &mut io::sink(), &mut Vec::new()).is_ok());
+}
@@ -0,0 +1,36 @@
+// Test that an expansion ending in the name of a function-like macro picks up
+// the arguments that follow it in the source
+#define f(x) (x + 1)
+#define g f
+#define ID(x) x
+#define APPLY(m) m
+#define h(x) h_ + x
+
+int main() {
+    // g becomes f, which takes (1) from after g
+    if (g(1) != 2) {
+        return 1;
+    }
+    // The argument of ID is f, which takes (2) from after ID(f)
+    if (ID(f)(2) != 3) {
+        return 2;
+    }
+    // Expansions nest: APPLY(g) becomes g, then f, which takes (10)
+    if (APPLY(g)(10) != 11) {
+        return 3;
+    }
+
+    // second becomes first, which takes (5) and becomes second again while
+    // second is still being expanded, so it stops there, naming the variable
+    int second = 4;
+#define first(x) second
+#define second first
+    if (second(5) != 4) {
+        return 4;
+    }
+
+    // h(11) becomes h_ + 11, while h alone stays an ordinary identifier
+    int h_ = 30;
+    int h = 1;
+    return APPLY(h)(11) + h;
+}
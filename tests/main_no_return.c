@@ -0,0 +1,6 @@
+// Test that main returns 0 when it falls off the end without a return
+int main() {
+    int x;
+    x = 42;
+    x = x * 2;
+}
@@ -0,0 +1,47 @@
+// A function with many locals of mixed sizes, some in nested blocks, all of
+// which live at fixed slots of a frame allocated once in the prologue
+
+long mix(int a, char b, long c, double d) {
+    char c1 = 1;
+    long l1 = 1000000000000;
+    int i1 = -5;
+    char c2 = b;
+    double d1 = d * 2;
+    int arr[5];
+    long l2;
+    int i;
+
+    for (i = 0; i < 5; i = i + 1) {
+        int square = i * i;
+        arr[i] = square;
+    }
+    {
+        long inner = c;
+        char tag = 'x';
+        l2 = inner + tag;
+    }
+    {
+        int other = 7;
+        l2 = l2 + other;
+    }
+
+    // 1 + 1000000000000 - 5 + 2 + 30 + 30 + (3 + 120 + 7) + 4 == 1000000000192
+    return c1 + l1 + i1 + c2 + d1 + (arr[0] + arr[1] + arr[2] + arr[3] + arr[4]) + l2 + a;
+}
+
+int main() {
+    long result = mix(4, 2, 3, 15.0);
+    int a = 1;
+    int b = 2;
+    int c = 3;
+    char d = 4;
+    long e = 5;
+
+    if (result != 1000000000192) {
+        return 1;
+    }
+    if (a + b + c + d + e != 15) {
+        return 2;
+    }
+    return result - 1000000000150;
+}
@@ -0,0 +1,30 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::{check_main, compile, parse, Options};
+
+/// Parse a program and check it for a main function
+fn check(source: &str) -> ferricc::error::Result<()> {
+    let program = parse(source, "missing_main.c", &Options::default(), &mut io::sink(), &mut Vec::new())?;
+    check_main(&program, "missing_main.c")
+}
+
+#[test]
+fn empty_translation_unit_compiles() {
+    assert!(compile("", "empty.c", &Options::default(), &mut io::sink(), &mut Vec::new()).is_ok());
+    assert!(compile("// nothing\n/* here */\n", "comments.c", &Options::default(), &mut io::sink(), &mut Vec::new()).is_ok());
+}
+
+#[test]
+fn empty_file_has_no_main() {
+    match check("") {
+        Err(CompilerError::SemanticError { message, .. }) => assert_eq!(message, "no 'main' function found"),
+        other => panic!("expected a missing main error, got {:?}", other),
+    }
+}
+
+#[test]
+fn declaring_main_is_not_defining_it() {
+    assert!(check("int main();\nint helper() { return 1; }").is_err());
+    assert!(check("int main() { return 0; }").is_ok());
+}
@@ -0,0 +1,31 @@
+// Test the warning for non-void functions that can fall off the end.
+// Compiling this file should warn about `sign` only.
+int sign(int n) {
+    if (n > 0) {
+        return 1;
+    } else if (n < 0) {
+        return -1;
+    }
+    // warning: no return when n == 0
+}
+
+int clamp(int n) {
+    if (n > 10) {
+        return 10;
+    } else {
+        return n;
+    }
+}
+
+void nothing() {
+}
+
+int main() {
+    nothing();
+
+    if (sign(5) != 1 || sign(-5) != -1) {
+        return 1;
+    }
+
+    return clamp(3) - 3;
+}
@@ -0,0 +1,83 @@
+// Test that locals and parameters of mixed sizes each get their own bytes in the
+// frame: every one is written first and read back afterwards, so any overlap
+// would clobber an earlier value
+struct Pair {
+    char tag;
+    int value;
+};
+
+// Four parameters at most: parameters passed on the stack are not supported
+// yet, and Windows passes only the first four in registers
+long mix(char c, int i, long l, double d) {
+    char c2;
+    long l2;
+    int i2;
+    char buffer[3];
+    c2 = c + 1;
+    l2 = l * 2;
+    i2 = i - 1;
+    buffer[0] = 'x';
+    buffer[1] = 'y';
+    buffer[2] = 'z';
+
+    if (c != 'a' || i != -7 || l != 5000000000 || d != 2.5) {
+        return 1;
+    }
+    if (c2 != 'b' || l2 != 10000000000 || i2 != -8) {
+        return 2;
+    }
+    if (buffer[0] != 'x' || buffer[1] != 'y' || buffer[2] != 'z') {
+        return 3;
+    }
+    return 0;
+}
+
+int main() {
+    char a;
+    long b;
+    char c;
+    int d;
+    _Bool e;
+    struct Pair pair;
+    char name[5] = "abcd";
+    double f;
+    unsigned char g;
+    int *p;
+
+    a = 1;
+    b = -2;
+    c = 3;
+    d = 400000;
+    e = 1;
+    pair.tag = 'q';
+    pair.value = -50;
+    f = 0.75;
+    g = 255;
+    p = &d;
+
+    if (a != 1 || b != -2 || c != 3 || d != 400000 || e != 1) {
+        return 1;
+    }
+    if (pair.tag != 'q' || pair.value != -50 || f != 0.75 || g != 255 || *p != 400000) {
+        return 2;
+    }
+    if (name[0] != 'a' || name[3] != 'd' || name[4] != 0) {
+        return 3;
+    }
+
+    {
+        // A nested block's locals sit below the enclosing ones
+        char h;
+        long i;
+        h = 9;
+        i = 123456789012;
+        if (h != 9 || i != 123456789012 || a != 1 || b != -2 || c != 3) {
+            return 4;
+        }
+    }
+
+    if (mix('a', -7, 5000000000, 2.5) != 0) {
+        return 5;
+    }
+    return 42;
+}
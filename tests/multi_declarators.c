@@ -0,0 +1,25 @@
+// Test declarations with several declarators sharing one base type
+int g1 = 1, g2 = 2;
+
+int main() {
+    int *p, a[4], q = 5, r;
+    long x = 10, y;
+    int i, sum;
+
+    // Each declarator gets its own pointer, array and initializer
+    p = a;
+    for (i = 0; i < 4; i = i + 1) {
+        *(p + i) = i + 1;
+    }
+
+    r = q * 2;
+    y = x + r;
+
+    sum = 0;
+    for (i = 0; i < 4; i = i + 1) {
+        sum = sum + a[i];
+    }
+
+    // 10 + 5 + 20 + 3 == 38
+    return sum + q + y + g1 + g2 - 38;
+}
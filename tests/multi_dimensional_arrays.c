@@ -0,0 +1,72 @@
+// Test arrays of arrays: each subscript selects a row of the array before it,
+// so m[i][j] is at m + (i * columns + j) * sizeof(int)
+typedef int Row[4];
+
+int grid[2][3];
+
+// A parameter declared as an array of rows points to the first row
+int sum(int rows, int m[][4]) {
+    int total;
+    int i;
+    int j;
+    total = 0;
+    i = 0;
+    while (i < rows) {
+        j = 0;
+        while (j < 4) {
+            total = total + m[i][j];
+            j = j + 1;
+        }
+        i = i + 1;
+    }
+    return total;
+}
+
+int main() {
+    int m[3][4];
+    Row rows[2];
+    char cube[2][3][4];
+    int i;
+    int j;
+
+    i = 0;
+    while (i < 3) {
+        j = 0;
+        while (j < 4) {
+            m[i][j] = i * 4 + j;
+            j = j + 1;
+        }
+        i = i + 1;
+    }
+
+    // The elements are laid out row after row
+    if (m[1][0] != 4 || m[2][3] != 11 || *(&m[0][0] + 6) != 6) {
+        return 1;
+    }
+    if (sizeof(m) != 48 || sizeof(m[1]) != 16 || sizeof(m[1][2]) != 4) {
+        return 2;
+    }
+
+    // A typedef of an array type makes an array of arrays too
+    rows[1][3] = 7;
+    if (sizeof(rows) != 32 || rows[1][3] != 7) {
+        return 3;
+    }
+
+    cube[1][2][3] = 9;
+    cube[0][0][0] = 1;
+    if (sizeof(cube) != 24 || sizeof(cube[1]) != 12 || cube[1][2][3] + cube[0][0][0] != 10) {
+        return 4;
+    }
+
+    grid[1][2] = 5;
+    if (grid[1][2] != 5 || grid[0][2] != 0 || &grid[1][0] != &grid[0][0] + 3) {
+        return 5;
+    }
+
+    // 0 + 1 + ... + 11 is 66
+    if (sum(3, m) != 66) {
+        return 6;
+    }
+    return 42;
+}
@@ -0,0 +1,26 @@
+// Test calling functions defined further down the file, with no prototype:
+// is_even and is_odd call each other
+int is_even(int n) {
+    if (n == 0) {
+        return 1;
+    }
+    return is_odd(n - 1);
+}
+
+int is_odd(int n) {
+    if (n == 0) {
+        return 0;
+    }
+    return is_even(n - 1);
+}
+
+int main() {
+    if (!is_even(10) || is_even(7) || !is_odd(7) || is_odd(0)) {
+        return 1;
+    }
+    return twice(21);
+}
+
+int twice(int n) {
+    return n * 2;
+}
@@ -0,0 +1,50 @@
+// Arguments are evaluated into stack slots and only loaded into the argument
+// registers right before the call, so calls nested in arguments cannot clobber them
+
+long f(long a, long b) {
+    return a * 10 + b;
+}
+
+long g(long x) {
+    return x + 1;
+}
+
+long h(long x) {
+    return x * 3;
+}
+
+long pick(long a, long b, long c, long d) {
+    return a - b + c - d;
+}
+
+int main() {
+    long a = 4;
+
+    // f(g(1), h(2)) == 2 * 10 + 6 == 26
+    if (f(g(1), h(2)) != 26) {
+        return 1;
+    }
+
+    // Each argument register is loaded after every nested call has returned
+    // pick(26, 3, 12, 5) == 30
+    if (pick(f(g(1), h(2)), g(2), h(a), f(0, 5)) != 30) {
+        return 2;
+    }
+
+    // A call nested inside an operand of a larger expression
+    // a * (3 + 26) == 116
+    if (a * (3 + f(g(g(0)), h(g(1)))) != 116) {
+        return 3;
+    }
+
+    // Arguments that are expressions of their own use RCX as a temporary
+    // f(3, 4 + 6) == 40, pick(1, 8, 3, 7) == -11
+    if (f(g(2), a + h(2)) != 40) {
+        return 4;
+    }
+    if (pick(1, a * 2, a - 1, 7) != -11) {
+        return 5;
+    }
+
+    return 0;
+}
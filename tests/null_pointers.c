@@ -0,0 +1,75 @@
+// Test the null pointer constant: assigning, initializing, returning and passing
+// 0 or NULL as a pointer, and comparing pointers with it and with each other
+#include <stdlib.h>
+
+int values[3];
+
+// A null pointer converts to whatever pointer type the function returns
+int *find(int value) {
+    if (value == values[1]) {
+        return &values[1];
+    }
+    return 0;
+}
+
+// A null pointer may be passed for a pointer parameter
+int count_set(int *first, char *second) {
+    return (first != NULL) + (second != 0);
+}
+
+int main() {
+    int *missing = 0;
+    char *name = NULL;
+    int *p;
+    int *q;
+    void *any;
+
+    p = &values[1];
+    q = &values[1];
+
+    if (p == 0 || !(p != 0)) {
+        return 1;
+    }
+    if (missing != 0 || 0 != missing || missing != NULL || name != 0) {
+        return 2;
+    }
+    if (!(missing == 0) || !(NULL == missing)) {
+        return 3;
+    }
+
+    // Two pointers are equal when they point to the same object
+    if (p != q || !(p == q)) {
+        return 4;
+    }
+    q = &values[2];
+    if (p == q || !(p != q)) {
+        return 5;
+    }
+
+    // Any object pointer compares with a void pointer
+    any = p;
+    if (any != p || q == any) {
+        return 6;
+    }
+
+    // An array compares as a pointer to its first element
+    p = &values[0];
+    if (values != p || values == 0) {
+        return 7;
+    }
+
+    p = 0;
+    q = NULL;
+    if (p != q || p != 0) {
+        return 8;
+    }
+
+    values[1] = 9;
+    if (find(9) != &values[1] || find(8) != 0) {
+        return 9;
+    }
+    if (count_set(0, NULL) != 0 || count_set(values, 0) != 1) {
+        return 10;
+    }
+    return 42;
+}
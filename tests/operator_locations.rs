@@ -0,0 +1,75 @@
+use std::io;
+
+use ferricc::ast::{BinaryOp, Node};
+use ferricc::{parse, Options};
+
+/// Parse `int f(int a, int b, int c) { return <expr>; }` and return the value
+fn parse_expression(expr: &str) -> Node {
+    let source = format!("int f(int a, int b, int c) {{ return {}; }}", expr);
+    let program = parse(&source, "operators.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    let Node::Program(mut declarations) = program else { panic!("expected a program") };
+    let Node::FunctionDecl { body: Some(body), .. } = declarations.remove(0) else { panic!("expected a function") };
+    let Node::BlockStmt(mut statements, _) = *body else { panic!("expected a block") };
+    let Node::ReturnStmt(Some(value), _) = statements.remove(0) else { panic!("expected a return value") };
+    *value
+}
+
+/// The operator of a binary expression and the column it is located at
+fn operator(node: &Node) -> (BinaryOp, usize) {
+    match node {
+        Node::BinaryExpr { op, location, .. } => (op.clone(), location.column),
+        other => panic!("expected a binary expression, got {:?}", other),
+    }
+}
+
+/// The operands of a binary expression
+fn operands(node: &Node) -> (&Node, &Node) {
+    match node {
+        Node::BinaryExpr { left, right, .. } => (left, right),
+        other => panic!("expected a binary expression, got {:?}", other),
+    }
+}
+
+// The expression starts at column 37, after "int f(int a, int b, int c) { return "
+
+#[test]
+fn assignment_is_located_at_its_operator() {
+    let value = parse_expression("a = b = c");
+    assert_eq!(operator(&value), (BinaryOp::Assign, 39));
+    assert_eq!(operator(operands(&value).1), (BinaryOp::Assign, 43));
+}
+
+#[test]
+fn chained_relational_and_equality_group_to_the_left() {
+    // a < b == c is (a < b) == c, and a < b < c is (a < b) < c
+    let value = parse_expression("a < b == c");
+    assert_eq!(operator(&value), (BinaryOp::Equal, 43));
+    assert_eq!(operator(operands(&value).0), (BinaryOp::Less, 39));
+
+    let value = parse_expression("a < b < c");
+    assert_eq!(operator(&value), (BinaryOp::Less, 43));
+    assert_eq!(operator(operands(&value).0), (BinaryOp::Less, 39));
+}
+
+#[test]
+fn every_binary_operator_is_located_at_its_token() {
+    let value = parse_expression("a || b && c != a >= b << c - a % b");
+    let mut node = &value;
+    let mut found = Vec::new();
+    while let Node::BinaryExpr { op, right, location, .. } = node {
+        found.push((op.clone(), location.column));
+        node = right;
+    }
+    assert_eq!(
+        found,
+        [
+            (BinaryOp::LogicalOr, 39),
+            (BinaryOp::LogicalAnd, 44),
+            (BinaryOp::NotEqual, 49),
+            (BinaryOp::GreaterEqual, 54),
+            (BinaryOp::ShiftLeft, 59),
+            (BinaryOp::Subtract, 64),
+            (BinaryOp::Modulo, 68),
+        ]
+    );
+}
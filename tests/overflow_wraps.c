@@ -0,0 +1,30 @@
+// Test signed overflow without -ftrap-overflow: the result wraps around to the
+// other end of the type's range
+int add(int a, int b) {
+    return a + b;
+}
+
+long multiply(long a, long b) {
+    return a * b;
+}
+
+int main() {
+    int int_max = 2147483647;
+    int int_min = -2147483647 - 1;
+    long long_max = 9223372036854775807;
+
+    if (add(int_max, 1) != int_min || int_min - 1 != int_max) {
+        return 1;
+    }
+    if (int_max * 2 != -2 || multiply(long_max, 2) != -2) {
+        return 2;
+    }
+
+    // An int that wrapped is still an int once widened
+    long widened = int_max + 1;
+    if (widened != int_min) {
+        return 3;
+    }
+
+    return add(40, 2);
+}
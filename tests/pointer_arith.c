@@ -0,0 +1,45 @@
+// Pointer arithmetic moves by whole elements, not bytes
+int main() {
+    long values[4];
+    int counts[4];
+    long *lp = values;
+    int *ip = counts;
+    long i = 0;
+
+    // Indexing a long array uses a stride of 8 bytes
+    while (i < 4) {
+        values[i] = (i + 1) * 100;
+        i = i + 1;
+    }
+    if (values[0] != 100) {
+        return 1;
+    }
+    if (values[3] != 400) {
+        return 2;
+    }
+    if (*(lp + 2) != 300) {
+        return 3;
+    }
+    if (*(values + 1) + *(3 + values) != 600) {
+        return 4;
+    }
+
+    // Subtracting an integer from a pointer moves back by whole elements
+    lp = values + 3;
+    if (*(lp - 1) != 300) {
+        return 5;
+    }
+
+    // The difference of two pointers counts elements for both int and long
+    if ((values + 3) - values != 3) {
+        return 6;
+    }
+    if ((ip + 3) - ip != 3) {
+        return 7;
+    }
+    if ((counts + 2) - (counts + 1) != 1) {
+        return 8;
+    }
+
+    return 0;
+}
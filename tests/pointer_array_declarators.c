@@ -0,0 +1,97 @@
+// Test parenthesized declarators: `int *a[3]` is an array of 3 pointers, while
+// `int (*a)[3]` is a pointer to an array of 3 ints
+int table[2][3];
+
+// Each step through a pointer to an array moves a whole row
+int (*last_row)[3];
+
+typedef int (*Row)[3];
+
+struct Grid {
+    int cells[2][3];
+    int (*row)[3];
+};
+
+struct Grid grid;
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int subtract(int a, int b) {
+    return a - b;
+}
+
+// An array of function pointers has its size inside the parentheses
+int (*operations[2])(int, int);
+
+int sum(int (*rows)[3], int count) {
+    int total = 0;
+    int i;
+    int j;
+    for (i = 0; i < count; i = i + 1) {
+        for (j = 0; j < 3; j = j + 1) {
+            total = total + rows[i][j];
+        }
+    }
+    return total;
+}
+
+// A function returning a pointer to an array has its parameters inside the
+// parentheses, before the array size
+int (*row_at(int i))[3] {
+    return table + i;
+}
+
+int main() {
+    int x = 1;
+    int y = 2;
+    int z = 3;
+    int *pointers[3];
+    int (*row)[3] = table;
+    Row second = &table[1];
+    int (*(*indirect))[3] = &last_row;
+
+    if (sizeof(pointers) != 24 || sizeof(row) != 8 || sizeof(*row) != 12) {
+        return 1;
+    }
+    if (sizeof(int *[3]) != 24 || sizeof(int (*)[3]) != 8) {
+        return 2;
+    }
+
+    pointers[0] = &x;
+    pointers[1] = &y;
+    pointers[2] = &z;
+    if (*pointers[0] + *pointers[1] + *pointers[2] != 6) {
+        return 3;
+    }
+
+    table[0][1] = 5;
+    (*second)[2] = 30;
+    if (row[1][2] != 30 || second[0][2] != 30 || row + 1 != second) {
+        return 4;
+    }
+    if ((char *)second - (char *)row != 12) {
+        return 5;
+    }
+
+    last_row = row_at(1);
+    if ((**indirect)[2] != 30 || sizeof(*row_at(0)) != 12) {
+        return 6;
+    }
+
+    grid.cells[1][0] = 7;
+    grid.row = grid.cells;
+    if (grid.row[1][0] != 7 || sizeof(grid) != 32) {
+        return 7;
+    }
+
+    operations[0] = add;
+    operations[1] = subtract;
+    if (sizeof(operations) != 16 || operations[0](2, 3) != 5) {
+        return 8;
+    }
+
+    // 5 + 30 from the table, and 7 more
+    return operations[1](sum(table, 2), -7);
+}
@@ -0,0 +1,32 @@
+// Functions may be declared before they are defined, as often as needed, as long
+// as every declaration agrees on the function's type
+
+int twice(int);
+int twice(const int x);
+long sum(long *, int count);
+int zero(void);
+
+int main() {
+    long values[3];
+    values[0] = 10;
+    values[1] = 11;
+    values[2] = twice(zero());
+    return sum(values, 3) + twice(10);
+}
+
+int twice(int x) {
+    return x * 2;
+}
+
+int zero(void) {
+    return 0;
+}
+
+long sum(long *values, int count) {
+    long total = 0;
+    int i;
+    for (i = 0; i < count; i = i + 1) {
+        total = total + values[i];
+    }
+    return total + 1;
+}
@@ -0,0 +1,23 @@
+// Test macros that refer to themselves: a macro's name is not expanded again
+// inside its own expansion, so each use expands once and stops
+#define SELF SELF
+#define PING PONG
+#define PONG PING
+#define TWICE(x) (TWICE + x * 2)
+
+int main() {
+    // SELF expands to SELF, which is then an ordinary identifier
+    int SELF = 20;
+
+    // PING becomes PONG, which becomes PING again and stops there, and PONG
+    // likewise ends as PONG
+    int PING = 1;
+    int PONG = 2;
+    if (PING != 1 || PONG != 2) {
+        return 1;
+    }
+
+    // A function-like macro's name inside its body is left as is too
+    int TWICE = 2;
+    return SELF + TWICE(10);
+}
@@ -0,0 +1,33 @@
+// A global may be declared several times, a local only once per scope
+
+int count;
+extern int count;
+int count = 40;
+int count;
+
+int shadow(int x) {
+    // An inner block may shadow a parameter
+    {
+        int x = 1;
+        x = x + 1;
+    }
+    return x;
+}
+
+int main() {
+    int x = 1;
+    {
+        // Shadowing a local in an inner scope is allowed
+        long x = 2;
+        {
+            char x = 3;
+            if (x != 3) {
+                return 1;
+            }
+        }
+        if (x != 2) {
+            return 2;
+        }
+    }
+    return count + x + shadow(1) - 1 + 1;
+}
@@ -0,0 +1,52 @@
+// Test that a returned value takes the width of the declared return type,
+// whatever the type of the returned expression
+char minus_one() {
+    int value = -1;
+    return value;
+}
+
+// 511 keeps only its low byte, 0xff, which is -1 as a char
+char low_byte(int value) {
+    return value;
+}
+
+unsigned char as_unsigned(int value) {
+    return value;
+}
+
+// An int result of a long expression keeps its low 32 bits, sign-extended
+int low_half(long value) {
+    return value;
+}
+
+unsigned int as_unsigned_int(int value) {
+    return value;
+}
+
+long widened(char value) {
+    return value;
+}
+
+int main() {
+    int result = minus_one();
+    long wide = low_byte(511);
+
+    if (result != -1 || wide != -1) {
+        return 1;
+    }
+    if (as_unsigned(-1) != 255) {
+        return 2;
+    }
+    // 4294967295 + 2 wraps the low half to 1
+    if (low_half(4294967297) != 1 || low_half(4294967295) != -1) {
+        return 3;
+    }
+    wide = as_unsigned_int(-1);
+    if (wide != 4294967295) {
+        return 4;
+    }
+    if (widened(-2) != -2) {
+        return 5;
+    }
+    return 43 + minus_one();
+}
@@ -0,0 +1,27 @@
+use std::io;
+
+use ferricc::{compile, Options};
+
+/// Compile a function returning its parameter as another type, and return the
+/// assembly of its body up to the jump to its epilogue
+fn convert(from: &str, to: &str) -> String {
+    let source = format!("{1} convert({0} value) {{\n    return value;\n}}\nint main() {{\n    return 0;\n}}\n", from, to);
+    let assembly = compile(&source, "return_widths.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    let body = assembly.split("convert:").nth(1).unwrap();
+    body[..body.find("jmp .convertret").unwrap()].to_string()
+}
+
+#[test]
+fn a_narrower_return_type_extends_from_its_low_bytes() {
+    assert!(convert("int", "char").ends_with("movsx rax, al\n    "), "{}", convert("int", "char"));
+    assert!(convert("int", "unsigned char").ends_with("movzx eax, al\n    "), "{}", convert("int", "unsigned char"));
+    assert!(convert("long", "int").ends_with("movsxd rax, eax\n    "), "{}", convert("long", "int"));
+    assert!(convert("int", "unsigned int").ends_with("mov eax, eax\n    "), "{}", convert("int", "unsigned int"));
+}
+
+#[test]
+fn a_value_of_the_return_type_is_returned_as_it_is() {
+    let body = convert("char", "char");
+    assert_eq!(body.matches("movsx").count(), 1, "{}", body);
+    assert!(!convert("long", "long").contains("movsxd"), "{}", convert("long", "long"));
+}
@@ -0,0 +1,53 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Write a program to a fresh directory and compile it there with --run and any
+/// further arguments, so its output/ directory stays out of the source tree
+fn compile_and_run(name: &str, source: &str, args: &[&str]) -> Output {
+    let dir = env::temp_dir().join(format!("ferricc_run_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let source_file: PathBuf = dir.join(format!("{}.c", name));
+    fs::write(&source_file, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ferricc"))
+        .current_dir(&dir)
+        .arg("--run")
+        .arg(&source_file)
+        .args(args)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    output
+}
+
+#[test]
+fn exit_code_of_the_program_is_passed_on() {
+    let output = compile_and_run("answer", "int main() {\n    return 42;\n}\n", &[]);
+    assert_eq!(output.status.code(), Some(42));
+}
+
+#[test]
+fn output_of_the_program_is_all_that_is_printed() {
+    let source = "#include <stdio.h>\nint main() {\n    puts(\"hello\");\n    return 0;\n}\n";
+    let output = compile_and_run("hello", source, &[]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+}
+
+#[test]
+fn arguments_after_double_dash_go_to_the_program() {
+    let source = "#include <stdio.h>\n\
+                  int main(int argc, char **argv) {\n    puts(argv[1]);\n    puts(argv[2]);\n    return argc;\n}\n";
+    let output = compile_and_run("echo_args", source, &["--", "first", "-v"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "first\n-v\n");
+}
+
+#[test]
+fn a_program_that_fails_to_compile_is_not_run() {
+    let output = compile_and_run("broken", "int main() {\n    return missing;\n}\n", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
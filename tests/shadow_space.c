@@ -0,0 +1,16 @@
+// Calls reserve 32 bytes of shadow space below the arguments, so a callee that
+// spills its register arguments there leaves the caller's saved registers alone
+
+long sum4(long a, long b, long c, long d) {
+    return a + b * 2 + c * 3 + d * 4;
+}
+
+// The caller's own parameters and the operands it holds across the call survive
+long scaled(long x, long y) {
+    return x * (y + sum4(1, 2, 3, 4)) + x - y;
+}
+
+int main() {
+    // sum4(1, 2, 3, 4) == 30, scaled(5, 7) == 5 * 37 + 5 - 7 == 183
+    return scaled(5, 7) - 141;
+}
@@ -0,0 +1,66 @@
+// Test that && and || skip their right operand once the left one decides the
+// result: the skipped side would count a call or dereference a null pointer
+int calls;
+
+int *null = 0;
+
+int count(int value) {
+    calls = calls + 1;
+    return value;
+}
+
+int main() {
+    int x;
+    int result;
+
+    x = 5;
+
+    // A false left side of && decides the result without the right side
+    calls = 0;
+    result = count(0) && count(1);
+    if (result != 0 || calls != 1) {
+        return 1;
+    }
+    if (x == 0 && *null == 1) {
+        return 2;
+    }
+
+    // A true left side of || decides the result without the right side
+    calls = 0;
+    result = count(3) || count(0);
+    if (result != 1 || calls != 1) {
+        return 3;
+    }
+    if (x == 5 || *null == 1) {
+        x = 6;
+    }
+    if (x != 6) {
+        return 4;
+    }
+
+    // Otherwise the right side is evaluated and decides the result
+    calls = 0;
+    result = count(2) && count(0);
+    if (result != 0 || calls != 2) {
+        return 5;
+    }
+    calls = 0;
+    result = count(0) || count(7);
+    if (result != 1 || calls != 2) {
+        return 6;
+    }
+
+    // Chains stop at the first operand that decides them
+    calls = 0;
+    result = count(1) && count(0) && count(1);
+    if (result != 0 || calls != 2) {
+        return 7;
+    }
+    calls = 0;
+    result = count(0) || count(4) || count(1);
+    if (result != 1 || calls != 2) {
+        return 8;
+    }
+
+    return 0;
+}
@@ -0,0 +1,33 @@
+// Test that the operand of sizeof is not evaluated: only its type is used, so
+// assignments and calls inside it never happen
+int calls;
+
+int bump() {
+    calls = calls + 1;
+    return calls;
+}
+
+int main() {
+    int i;
+    char c;
+    long n;
+
+    i = 1;
+    c = 'a';
+    // sizeof(i = 5) stands in for sizeof(i++), as the parser does not accept the
+    // increment and decrement operators yet; both would change i if evaluated
+    n = sizeof(i = 5) + sizeof(bump()) + sizeof(c = 'b');
+    if (n != 9) {
+        return 1;
+    }
+    if (i != 1 || calls != 0 || c != 'a') {
+        return 2;
+    }
+
+    // Nor is it evaluated as part of a larger expression
+    n = sizeof(i = i + 1) * 10 + i;
+    if (n != 41 || i != 1) {
+        return 3;
+    }
+    return 42;
+}
@@ -0,0 +1,62 @@
+use std::io;
+
+use ferricc::ast::Node;
+use ferricc::{parse, Options};
+
+/// Parse a function and return the value of its first return statement
+fn returned_value(source: &str) -> Node {
+    let program = parse(source, "spans.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    let Node::Program(declarations) = program else { panic!("expected a program") };
+    let Some(Node::FunctionDecl { body: Some(body), .. }) = declarations.into_iter().last() else {
+        panic!("expected a function definition last")
+    };
+    let Node::BlockStmt(statements, _) = *body else { panic!("expected a block") };
+    statements
+        .into_iter()
+        .find_map(|statement| match statement {
+            Node::ReturnStmt(Some(value), _) => Some(*value),
+            _ => None,
+        })
+        .expect("no return statement with a value")
+}
+
+/// The start and end (line, column) of a node's span
+fn span_of(node: &Node) -> ((usize, usize), (usize, usize)) {
+    let span = node.span().unwrap();
+    ((span.line, span.column), (span.end_line, span.end_column))
+}
+
+#[test]
+fn binary_expression_covers_both_operands() {
+    let value = returned_value("int f(int a, int b, int c) {\n    return a + b * c;\n}\n");
+    assert!(matches!(value, Node::BinaryExpr { .. }));
+    assert_eq!(span_of(&value), ((2, 12), (2, 21)));
+
+    let Node::BinaryExpr { right, .. } = &value else { unreachable!() };
+    assert_eq!(span_of(right), ((2, 16), (2, 21)));
+}
+
+#[test]
+fn tokens_end_after_their_last_character() {
+    let value = returned_value("int f(long count) {\n    return count >= 1000;\n}\n");
+    let Node::BinaryExpr { left, right, .. } = &value else { panic!("expected a comparison") };
+    assert_eq!(span_of(left), ((2, 12), (2, 17)));
+    assert_eq!(span_of(right), ((2, 21), (2, 25)));
+}
+
+#[test]
+fn call_spans_lines_up_to_its_closing_parenthesis() {
+    let source = "int g(int x, int y) { return x + y; }\nint f() {\n    return g(1,\n             2 * 3) - 4;\n}\n";
+    let value = returned_value(source);
+    assert_eq!(span_of(&value), ((3, 12), (4, 24)));
+
+    let Node::BinaryExpr { left, .. } = &value else { panic!("expected a subtraction") };
+    assert!(matches!(left.as_ref(), Node::FunctionCall { .. }));
+    assert_eq!(span_of(left), ((3, 12), (4, 20)));
+}
+
+#[test]
+fn subscript_and_unary_operators_are_included() {
+    let value = returned_value("int f(int *p, int i) {\n    return -p[i + 1];\n}\n");
+    assert_eq!(span_of(&value), ((2, 12), (2, 21)));
+}
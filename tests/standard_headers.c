@@ -0,0 +1,37 @@
+// The bundled standard headers declare the library functions they name
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+    char buffer[16];
+    int *numbers;
+    int i;
+
+    // string.h
+    strcpy(buffer, "abc");
+    strcat(buffer, "de");
+    if (strlen(buffer) != 5 || strcmp(buffer, "abcde") != 0) {
+        return 1;
+    }
+
+    // stdlib.h: void * converts to and from other object pointers
+    numbers = malloc(4 * sizeof(int));
+    for (i = 0; i < 4; i = i + 1) {
+        numbers[i] = i * i;
+    }
+    memset(buffer, 0, 16);
+    if (numbers[3] != 9 || buffer[0] != 0 || atoi("42") != 42 || abs(-3) != 3) {
+        return 2;
+    }
+    free(numbers);
+
+    // stdio.h
+    sprintf(buffer, "%d-%s", 7, "x");
+    if (strcmp(buffer, "7-x") != 0) {
+        return 3;
+    }
+    printf("%s\n", buffer);
+    return 0;
+}
@@ -0,0 +1,34 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::{compile, Options};
+
+/// Compile a program with the default options
+fn compile_source(source: &str) -> ferricc::error::Result<String> {
+    compile(source, "standard_headers.c", &Options::default(), &mut io::sink(), &mut Vec::new())
+}
+
+#[test]
+fn printf_is_checked_against_its_prototype() {
+    assert!(compile_source("#include <stdio.h>\nint main() { printf(\"%d\\n\", 42); return 0; }").is_ok());
+
+    match compile_source("#include <stdio.h>\nint main() { printf(42); return 0; }") {
+        Err(CompilerError::TypeError { message, .. }) => assert!(message.contains("printf")),
+        other => panic!("expected a type error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn every_bundled_header_can_be_included() {
+    let source = "#include <stdarg.h>\n#include <stdbool.h>\n#include <stdio.h>\n\
+                  #include <stdlib.h>\n#include <string.h>\nint main() { return 0; }";
+    assert!(compile_source(source).is_ok());
+}
+
+#[test]
+fn missing_headers_are_still_reported() {
+    match compile_source("#include <nonexistent.h>\nint main() { return 0; }") {
+        Err(CompilerError::PreprocessorError { message, .. }) => assert!(message.contains("nonexistent.h")),
+        other => panic!("expected a preprocessor error, got {:?}", other.map(|_| ())),
+    }
+}
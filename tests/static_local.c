@@ -0,0 +1,45 @@
+// Static locals keep their value across calls, static globals are private to
+// this file and extern declarations name variables defined elsewhere
+
+extern int total;
+
+static int calls;
+
+// The counter is initialized once, not on every call
+int counter() {
+    static int count = 10;
+    count = count + 1;
+    calls = calls + 1;
+    return count;
+}
+
+// A static local of the same name in another function is a separate variable
+int other() {
+    static int count;
+    count = count + 100;
+    return count;
+}
+
+int main() {
+    int i;
+    int last = 0;
+
+    for (i = 0; i < 3; i = i + 1) {
+        last = counter();
+    }
+
+    // counter() has now been called 3 times: count == 13
+    if (last != 13 || calls != 3 || other() != 100) {
+        return 1;
+    }
+
+    {
+        extern int total;
+        total = total + last;
+    }
+
+    // total starts at 29: 29 + 13 == 42
+    return total;
+}
+
+int total = 29;
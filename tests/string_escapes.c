@@ -0,0 +1,28 @@
+// Test string literals holding quotes, backslashes and control characters, which
+// must be escaped for the assembler to read back the same bytes
+#include <stdio.h>
+#include <string.h>
+
+int main() {
+    char *quoted = "a\"b\\c";
+    char *controls = "\t\r\001\177";
+    char *trailing = "end\\";
+
+    puts("a\"b\\c");
+
+    if (strlen(quoted) != 5 || quoted[1] != '"' || quoted[3] != '\\' || quoted[4] != 'c') {
+        return 1;
+    }
+    if (controls[0] != 9 || controls[1] != 13 || controls[2] != 1 || controls[3] != 127 || controls[4] != 0) {
+        return 2;
+    }
+
+    // A backslash right before the closing quote must not escape it
+    if (strlen(trailing) != 4 || trailing[3] != '\\') {
+        return 3;
+    }
+    if (strcmp(quoted, "a\"b\\c") != 0) {
+        return 4;
+    }
+    return 42;
+}
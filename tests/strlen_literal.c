@@ -0,0 +1,30 @@
+// strlen of a string literal is folded to its length at compile time
+
+#include <stdio.h>
+#include <string.h>
+
+int main() {
+    char *s = "four";
+    char *t = "ab";
+    char *u = "é";
+
+    // Prints 5 and 3: the length stops at an embedded NUL
+    printf("%d\n", strlen("hello"));
+    printf("%d\n", strlen("abc\0def"));
+
+    // A folded length takes part in constant folding, and still agrees with a real call
+    if (strlen("") != 0 || strlen("ab") * 2 != 4 || strlen(s) != strlen("four")) {
+        return 1;
+    }
+
+    // The length is unsigned, so subtracting past zero wraps around both ways
+    if ((strlen("ab") - 3 > 0) != (strlen(t) - 3 > 0) || strlen("ab") - 3 < 0) {
+        return 2;
+    }
+
+    // A character outside ASCII takes the bytes of its UTF-8 encoding
+    if (strlen("é") != 2 || strlen(u) != 2) {
+        return 3;
+    }
+    return strlen("0123456789") + 32;
+}
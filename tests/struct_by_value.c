@@ -0,0 +1,98 @@
+// Structs of up to 16 bytes are passed to and returned from functions by value
+// Each mention of a struct spells out its members
+#define PAIR struct Pair { int first; int second; }
+#define TRIPLE struct Triple { long a; char b; int c; }
+#define QUAD struct Quad { long a; long b; }
+
+PAIR make_pair(int first, int second) {
+    PAIR p;
+    p.first = first;
+    p.second = second;
+    return p;
+}
+
+// The parameter is a copy, so changing it leaves the caller's struct alone
+PAIR swap(PAIR p) {
+    int first = p.first;
+    p.first = p.second;
+    p.second = first;
+    return p;
+}
+
+int sum(PAIR p) {
+    return p.first + p.second;
+}
+
+// A 13-byte struct comes back in two registers
+TRIPLE make_triple(long a, int c) {
+    TRIPLE t;
+    TRIPLE *tp = &t;
+    tp->a = a;
+    tp->b = 'x';
+    tp->c = c;
+    return t;
+}
+
+// A 16-byte struct parameter takes two registers, and the arguments after it
+// the registers that follow
+long weigh(int scale, QUAD q, int offset) {
+    return scale * q.a + q.b + offset;
+}
+
+QUAD make_quad(long a, long b) {
+    QUAD q;
+    q.a = a;
+    q.b = b;
+    return q;
+}
+
+// Changing the 13-byte copy leaves the caller's struct alone
+TRIPLE shift(TRIPLE t, int n) {
+    t.a = t.a + n;
+    t.c = t.c - n;
+    return t;
+}
+
+int main() {
+    PAIR p = make_pair(3, 4);
+    PAIR q;
+    TRIPLE t;
+    TRIPLE u;
+    QUAD r;
+
+    if (p.first != 3 || p.second != 4) {
+        return 1;
+    }
+
+    q = swap(p);
+    if (q.first != 4 || q.second != 3) {
+        return 2;
+    }
+    if (p.first != 3) {
+        return 3;
+    }
+
+    // Assigning a struct copies all of its members
+    q = p;
+    q.second = 10;
+    if (sum(q) != 13 || sum(p) != 7) {
+        return 4;
+    }
+
+    t = make_triple(100000000000, -5);
+    if (t.a != 100000000000 || t.b != 'x' || t.c != -5) {
+        return 5;
+    }
+
+    u = shift(t, 7);
+    if (u.a != 100000000007 || u.b != 'x' || u.c != -12 || t.a != 100000000000 || t.c != -5) {
+        return 6;
+    }
+
+    r = make_quad(3, 4);
+    if (weigh(10, r, 5) != 39 || weigh(2, make_quad(5, 6), 1) != 17) {
+        return 7;
+    }
+
+    return sum(swap(make_pair(40, 2)));
+}
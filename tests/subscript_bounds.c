@@ -0,0 +1,36 @@
+// Test the warning for constant subscripts outside an array of known size.
+// Compiling this file should warn on the marked lines only.
+
+int table[4];
+
+// Never called; only compiled for its warnings
+int out_of_bounds() {
+    int a[3];
+    a[3] = 1;           // warns: one past the end
+    return a[-1]        // warns: before the start
+        + table[7];     // warns: global array
+}
+
+int main() {
+    int a[3];
+    int *p = a;
+    int *end;
+    int i = 5;
+
+    a[0] = 1;
+    a[2] = 2;
+    2[a] = 3;
+
+    // One past the end may be pointed to, but not read
+    end = &a[3];
+    if (end - p != 3) {
+        return 1;
+    }
+
+    // Pointers and variable indices have no known bound
+    p[1] = 4;
+    if (i > 2) {
+        i = 0;
+    }
+    return a[i] + a[1] + a[2] - 8;
+}
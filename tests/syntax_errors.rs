@@ -0,0 +1,26 @@
+use std::io;
+
+use ferricc::error::CompilerError;
+use ferricc::{compile, Options};
+
+/// Compile a program that must be rejected with a syntax error, returning its
+/// line, column and message
+fn syntax_error(source: &str) -> (usize, usize, String) {
+    match compile(source, "syntax_errors.c", &Options::default(), &mut io::sink(), &mut Vec::new()) {
+        Err(CompilerError::SyntaxError { location, message }) => (location.line, location.column, message),
+        other => panic!("expected a syntax error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn trailing_comma_in_call_points_at_the_comma() {
+    let (line, column, message) = syntax_error("int f(int a) { return a; }\nint main() { return f(1,); }");
+    assert_eq!((line, column), (2, 24));
+    assert!(message.contains("after ','"));
+}
+
+#[test]
+fn comma_without_arguments_is_rejected() {
+    let (_, column, _) = syntax_error("int f() { return 0; }\nint main() { return f(,); }");
+    assert_eq!(column, 23);
+}
@@ -0,0 +1,86 @@
+use std::io;
+
+use ferricc::target::Target;
+use ferricc::{compile, Options};
+
+const PROGRAM: &str = "int add(int a, double b, int c) { return a + b + c; }\n\
+                       int main() { return add(1, 2.0, 3); }";
+
+/// Compile the test program for a target
+fn compile_for(target: Target) -> String {
+    let options = Options { target, ..Options::default() };
+    compile(PROGRAM, "targets.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+}
+
+#[test]
+fn windows_passes_arguments_by_position() {
+    let assembly = compile_for(Target::Windows);
+    assert!(assembly.contains("mov rcx, qword ptr [rsp+0]"));
+    assert!(assembly.contains("movq xmm1, qword ptr [rsp+8]"));
+    assert!(assembly.contains("mov r8, qword ptr [rsp+16]"));
+    assert!(assembly.contains("movsd qword ptr [rbp-16], xmm1"));
+    assert!(assembly.contains("call add"));
+}
+
+#[test]
+fn linux_passes_integer_and_floating_arguments_separately() {
+    let assembly = compile_for(Target::Linux);
+    assert!(assembly.contains("mov rdi, qword ptr [rsp+0]"));
+    assert!(assembly.contains("movq xmm0, qword ptr [rsp+8]"));
+    assert!(assembly.contains("mov rsi, qword ptr [rsp+16]"));
+    assert!(assembly.contains("movsd qword ptr [rbp-16], xmm0"));
+    assert!(assembly.contains("mov qword ptr [rbp-24], rsi"));
+    assert!(!assembly.contains("rcx, qword ptr"));
+}
+
+#[test]
+fn macos_prefixes_symbols_with_an_underscore() {
+    let assembly = compile_for(Target::Macos);
+    assert!(assembly.contains(".globl _main"));
+    assert!(assembly.contains("_add:"));
+    assert!(assembly.contains("call _add"));
+}
+
+#[test]
+fn library_function_addresses_come_from_the_global_offset_table() {
+    let source = "int puts(const char *s);\n\
+                  int main() { int (*say)(const char *) = puts; return say(\"hi\"); }";
+    let compile_to = |target| {
+        let options = Options { target, ..Options::default() };
+        compile(source, "targets.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+    };
+    assert!(compile_to(Target::Linux).contains("mov rax, qword ptr [rip + puts@GOTPCREL]"));
+    assert!(compile_to(Target::Windows).contains("lea rax, [rip + puts]"));
+}
+
+#[test]
+fn linux_marks_the_stack_as_not_executable() {
+    let section = ".section .note.GNU-stack,\"\",@progbits";
+    assert!(compile_for(Target::Linux).contains(section));
+    assert!(!compile_for(Target::Windows).contains(".note.GNU-stack"));
+    assert!(!compile_for(Target::Macos).contains(".note.GNU-stack"));
+}
+
+#[test]
+fn sixteen_byte_structs_take_two_registers_on_linux_and_a_copy_on_windows() {
+    let source = "struct Quad { long a; long b; };\n\
+                  long second(int n, struct Quad q) { return q.b; }\n\
+                  int main() { struct Quad q; q.a = 1; q.b = 2; return second(1, q); }";
+    let compile_to = |target| {
+        let options = Options { target, ..Options::default() };
+        compile(source, "targets.c", &options, &mut io::sink(), &mut Vec::new()).unwrap()
+    };
+
+    // System V splits the struct across RSI and RDX, and the callee stores both
+    let linux = compile_to(Target::Linux);
+    assert!(linux.contains("mov rdx, qword ptr [rsp+16]"));
+    assert!(linux.contains("mov qword ptr [rbp-24], rsi"));
+    assert!(linux.contains("mov qword ptr [rbp-16], rdx"));
+
+    // Windows passes the address of a copy in RDX, and the callee copies it in turn
+    let windows = compile_to(Target::Windows);
+    assert!(windows.contains("mov rdx, qword ptr [rsp+8]"));
+    assert!(windows.contains("mov qword ptr [rbp-16], rdx"));
+    assert!(windows.contains("mov rax, qword ptr [rbp-16]"));
+    assert!(windows.contains("mov qword ptr [rbp-32+8], rdx"));
+}
@@ -0,0 +1,46 @@
+use ferricc::{parse, Options};
+
+/// Tokenize source and return the position and length of the first token of the
+/// given kind, as reported by the token dump. Parsing may fail afterwards; the
+/// tokens are dumped before it starts.
+fn locate(source: &str, kind: &str) -> (usize, usize, usize) {
+    let options = Options { dump_tokens: true, ..Options::default() };
+    let mut log = Vec::new();
+    let _ = parse(source, "token_locations.c", &options, &mut log, &mut Vec::new());
+
+    let log = String::from_utf8(log).unwrap();
+    let prefix = format!("Token: {} at ", kind);
+    let line = log
+        .lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .unwrap_or_else(|| panic!("no {} token in:\n{}", kind, log));
+
+    let (position, length) = line.split_once(", length ").unwrap();
+    let (line, column) = position.split_once(':').unwrap();
+    (line.parse().unwrap(), column.parse().unwrap(), length.parse().unwrap())
+}
+
+#[test]
+fn shift_assign_spans_three_characters() {
+    assert_eq!(locate("int f(int x) {\n    x >>= 2;\n}", "ShiftRightAssign"), (2, 7, 3));
+}
+
+#[test]
+fn arrow_is_located_at_its_dash() {
+    assert_eq!(locate("int f() {\n  return p->next;\n}", "Arrow"), (2, 11, 2));
+}
+
+#[test]
+fn equality_after_other_tokens_keeps_its_column() {
+    assert_eq!(locate("int f(int a) { return a>>1 == a<<1; }", "Equal"), (1, 28, 2));
+}
+
+#[test]
+fn comments_do_not_shift_the_next_token() {
+    assert_eq!(locate("int x /* a comment */ == 1;", "Equal"), (1, 23, 2));
+}
+
+#[test]
+fn literals_span_their_quotes_and_escapes() {
+    assert_eq!(locate("char *s = \"a\\n\";", "StringLiteral(\"a\\n\")"), (1, 11, 5));
+}
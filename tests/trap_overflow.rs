@@ -0,0 +1,56 @@
+mod common;
+
+use std::io;
+use std::process::ExitStatus;
+
+use ferricc::target::Target;
+use ferricc::{compile, Options};
+
+/// Compile and link a program for the host with -ftrap-overflow, and run it
+fn run_trapping(source: &str, name: &str) -> ExitStatus {
+    let options = Options { target: Target::host(), trap_overflow: true, ..Options::default() };
+    common::run_with_input(source, name, &options, b"").status
+}
+
+/// Add one to an int given at run time, so the sum is not folded
+fn increment(value: &str) -> String {
+    format!("int add(int a, int b) {{\n    return a + b;\n}}\nint main() {{\n    return add({}, 1) == 0;\n}}\n", value)
+}
+
+#[test]
+fn int_max_plus_one_aborts() {
+    let status = run_trapping(&increment("2147483647"), "trap_int_max");
+    assert!(!status.success());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(status.signal(), Some(6), "{}", status);
+    }
+}
+
+#[test]
+fn arithmetic_that_fits_does_not_trap() {
+    assert_eq!(run_trapping(&increment("-1"), "trap_fits").code(), Some(1));
+
+    let source = "long multiply(long a, long b) {\n    return a * b;\n}\n\
+                  int main() {\n    int big = 2147483647;\n    return multiply(big, 4) / big + 38;\n}\n";
+    assert_eq!(run_trapping(source, "trap_long").code(), Some(42));
+}
+
+#[test]
+fn long_overflow_aborts() {
+    let source = "long multiply(long a, long b) {\n    return a * b;\n}\n\
+                  int main() {\n    return multiply(9223372036854775807, 2) == 0;\n}\n";
+    assert!(!run_trapping(source, "trap_long_overflow").success());
+}
+
+#[test]
+fn checks_are_only_emitted_when_asked_for() {
+    let source = increment("1");
+    let wrapping = compile(&source, "wrap.c", &Options::default(), &mut io::sink(), &mut Vec::new()).unwrap();
+    assert!(!wrapping.contains("jo ") && !wrapping.contains("abort"), "{}", wrapping);
+
+    let options = Options { trap_overflow: true, ..Options::default() };
+    let trapping = compile(&source, "trap.c", &options, &mut io::sink(), &mut Vec::new()).unwrap();
+    assert!(trapping.contains("add eax, ecx") && trapping.contains("jo .overflow_trap"), "{}", trapping);
+}
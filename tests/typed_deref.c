@@ -0,0 +1,33 @@
+// Dereferencing values whose types are only known from the type checker
+char *second(char *s) {
+    return s + 1;
+}
+
+int *last(int *values, long count) {
+    return values + count - 1;
+}
+
+int main() {
+    char text[8];
+    int numbers[4];
+
+    text[0] = 'x';
+    text[1] = 'y';
+    text[2] = 'z';
+    numbers[0] = 1;
+    numbers[1] = 2;
+    numbers[2] = 0 - 3;
+
+    // The call's return type decides the load width of the dereference
+    if (*second(text) != 'y') {
+        return 1;
+    }
+    if (*second(text + 1) != 'z') {
+        return 2;
+    }
+    if (*last(numbers, 3) != 0 - 3) {
+        return 3;
+    }
+
+    return 0;
+}
@@ -0,0 +1,58 @@
+// Test typedef names: a declaration may start with one anywhere in a block, and
+// an ordinary identifier of the same name hides it
+typedef int Number;
+typedef struct Point {
+    Number x;
+    Number y;
+} Point;
+typedef Point *PointRef;
+typedef int Pair[2];
+typedef int (*Operation)(int, int);
+
+int add(int a, int b) {
+    return a + b;
+}
+
+Number sum(PointRef point) {
+    return point->x + point->y;
+}
+
+int main() {
+    int total;
+    total = 0;
+
+    // A declaration after a statement may begin with a typedef name
+    typedef int T;
+    T x = 5;
+    total = total + x;
+
+    Point point;
+    point.x = 3;
+    point.y = 4;
+    total = total + sum(&point);
+
+    Pair pair;
+    pair[0] = 1;
+    pair[1] = 2;
+    if (sizeof(pair) != 8) {
+        return 1;
+    }
+    total = total + pair[0] + pair[1];
+
+    Operation operation = add;
+    total = operation(total, 10);
+
+    {
+        // A variable named like a typedef hides it in its scope...
+        int T;
+        T = 6;
+        total = total * T;
+    }
+
+    // ... and the typedef is back once that scope ends
+    T y = 0;
+    if (total != 150 || y != 0) {
+        return 2;
+    }
+    return 42;
+}
@@ -0,0 +1,28 @@
+// Test the warning for locals that may be read before being assigned.
+// Compiling this file should warn about `x` on the marked line only.
+int main() {
+    int x;
+    int y;
+    int z;
+    int flag;
+
+    flag = 0;
+    y = 2;
+
+    if (flag) {
+        x = 1;
+    }
+
+    if (flag) {
+        return x;  // warning: x may be used uninitialized
+    }
+
+    // Assigned on both paths, so no warning
+    if (flag) {
+        z = 1;
+    } else {
+        z = 3;
+    }
+
+    return z - y - 1;
+}
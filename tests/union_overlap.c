@@ -0,0 +1,50 @@
+// Union members share storage: every member starts at offset 0 and the union is
+// as large as its largest member
+
+#define BYTES union Bytes { int i; char c[4]; }
+#define WIDE union Wide { long l; int i; char c; }
+
+// A small union is passed and returned by value like a struct
+WIDE low_byte(WIDE w) {
+    WIDE result;
+    result.l = w.c;
+    return result;
+}
+
+int main() {
+    BYTES b;
+    WIDE w;
+    WIDE copy;
+    WIDE arr[2];
+    BYTES *p;
+
+    // Writing through one member and reading a byte through another: x86 is
+    // little-endian, so the first byte is the lowest one
+    b.i = 0x01020304;
+    if (b.c[0] != 4 || b.c[3] != 1) {
+        return 1;
+    }
+
+    // Writing a byte changes the int that overlaps it
+    p = &b;
+    p->c[1] = 0;
+    if (b.i != 0x01020004) {
+        return 2;
+    }
+
+    // The union is as large as its widest member, so array elements do not overlap
+    arr[0].l = -1;
+    arr[1].l = 5;
+    if (arr[0].i != -1 || arr[1].c != 5) {
+        return 3;
+    }
+
+    w.l = 0x100000000 + 0x12A;
+    copy = w;
+    w = low_byte(copy);
+    if (copy.i != 0x12A || w.l != 0x2A) {
+        return 4;
+    }
+
+    return w.l;
+}
@@ -0,0 +1,37 @@
+// Unsigned arithmetic must use unsigned comparisons, division, and shifts
+int main() {
+    unsigned int big = 0u - 1u;
+
+    // 0u - 1u wraps around to the largest unsigned int, which is greater than 0
+    if (!(0u - 1u > 0)) {
+        return 1;
+    }
+
+    // The signed counterpart is negative
+    if (0 - 1 > 0) {
+        return 2;
+    }
+
+    // Unsigned division of a wrapped value differs from signed division
+    if ((0u - 2u) / 2u == (0 - 2) / 2) {
+        return 3;
+    }
+    if ((0u - 2u) / 2u != 2147483647u) {
+        return 4;
+    }
+
+    // Logical right shift versus arithmetic right shift
+    if (big >> 28 != 15) {
+        return 5;
+    }
+    if ((0 - 16) >> 2 != 0 - 4) {
+        return 6;
+    }
+
+    // 4294967295 % 10 == 5, while -1 % 10 == -1
+    if (big % 10u != 5) {
+        return 7;
+    }
+
+    return 0;
+}
@@ -0,0 +1,82 @@
+// Reading variable arguments with va_start, va_arg and va_end
+
+#include <stdarg.h>
+
+// Sums the count ints that follow it
+int sum(int count, ...) {
+    va_list ap;
+    int total = 0;
+    int i;
+
+    va_start(ap, count);
+    for (i = 0; i < count; i = i + 1) {
+        total = total + va_arg(ap, int);
+    }
+    va_end(ap);
+    return total;
+}
+
+// Sums count longs read from a va_list started by the caller
+long sum_list(int count, va_list ap) {
+    long total = 0;
+    while (count > 0) {
+        total = total + va_arg(ap, long);
+        count = count - 1;
+    }
+    return total;
+}
+
+// Passes its variable arguments on to sum_list
+long sum_longs(int count, ...) {
+    va_list ap;
+    long total;
+
+    va_start(ap, count);
+    total = sum_list(count, ap);
+    va_end(ap);
+    return total;
+}
+
+// Returns the length of the string at index which among the char * arguments
+int length_of(int which, char *first, ...) {
+    va_list ap;
+    char *s = first;
+    int n = 0;
+
+    va_start(ap, first);
+    while (which > 0) {
+        s = va_arg(ap, char *);
+        which = which - 1;
+    }
+    va_end(ap);
+
+    while (s[n]) {
+        n = n + 1;
+    }
+    return n;
+}
+
+int main() {
+    if (sum(0) != 0 || sum(1, 42) != 42 || sum(3, 1, 2, 3) != 6) {
+        return 1;
+    }
+
+    // More arguments than registers, so the rest are read from the stack
+    if (sum(10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10) != 55) {
+        return 2;
+    }
+    if (sum(3, -1, -2, -3) != -6) {
+        return 3;
+    }
+
+    // A va_list can be handed to another function
+    if (sum_longs(7, 10000000000, 1, 2, 3, 4, 5, 6) != 10000000021) {
+        return 4;
+    }
+
+    if (length_of(0, "a", "bcd") != 1 || length_of(5, "a", "bb", "c", "d", "e", "last") != 4) {
+        return 5;
+    }
+
+    return 0;
+}
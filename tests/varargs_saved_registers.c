@@ -0,0 +1,41 @@
+// Variable arguments in a function whose body needs the callee-saved scratch
+// registers, which must not move the arguments va_arg reads
+
+#include <stdarg.h>
+
+// Sums the count ints that follow it, folding each one into an expression
+// nested deeply enough to hold intermediate values in rbx and r12
+int sum(int count, ...) {
+    va_list ap;
+    int total = 0;
+    int i;
+    int x;
+
+    va_start(ap, count);
+    for (i = 0; i < count; i = i + 1) {
+        x = va_arg(ap, int);
+        total = total + (x * (1 + (x * (1 + (x * (1 + (x * 0)))))) - x * (x + x * x));
+    }
+    va_end(ap);
+    return total;
+}
+
+int main() {
+    int n = 1;
+
+    if (sum(5, 1, 2, 3, 4, 5) != 15) {
+        return 1;
+    }
+
+    // Two of the arguments are passed on the stack under System V
+    if (sum(8, 1, 2, 3, 4, 5, 6, 7, 8) != 36) {
+        return 2;
+    }
+
+    // The caller keeps its own nested values in the same registers across the call
+    if (n + (n + (n + (n + (n + sum(3, 4, 5, 6))))) != 20) {
+        return 3;
+    }
+
+    return 0;
+}
@@ -0,0 +1,30 @@
+use ferricc::{compile, Options};
+
+const PROGRAM: &str = "int main() { return 42; }";
+
+/// Compile the test program at the given verbosity, returning what was logged
+fn log_at(verbosity: u8) -> String {
+    let options = Options { verbosity, ..Options::default() };
+    let mut log = Vec::new();
+    let assembly = compile(PROGRAM, "verbosity.c", &options, &mut log, &mut Vec::new()).unwrap();
+    assert!(assembly.contains("main:"));
+    String::from_utf8(log).unwrap()
+}
+
+#[test]
+fn quiet_compile_logs_nothing() {
+    assert_eq!(log_at(0), "");
+}
+
+#[test]
+fn verbose_compile_logs_phases_without_tokens() {
+    let log = log_at(1);
+    assert!(log.contains("Parsing complete"));
+    assert!(log.contains("Code generation complete"));
+    assert!(!log.contains("Token:"));
+}
+
+#[test]
+fn very_verbose_compile_dumps_tokens() {
+    assert!(log_at(2).contains("Token: Int at 1:1"));
+}
@@ -0,0 +1,53 @@
+// The volatile qualifier and the register storage class, in every position they
+// can appear
+
+#define COUNTER struct Counter { volatile int ticks; int limit; }
+
+volatile int flag = 1;
+int volatile *volatile flag_pointer;
+const volatile long deadline = 40;
+
+// register parameters behave like any other
+int add(register int a, register int b) {
+    return a + b;
+}
+
+// Reads through a pointer to volatile
+int read(volatile int *p) {
+    return *p;
+}
+
+int main() {
+    register int i;
+    register int total = 0;
+    volatile char c = 'a';
+    COUNTER counter;
+    volatile COUNTER *pointer = &counter;
+
+    for (i = 0; i < 4; i = i + 1) {
+        total = add(total, i);
+    }
+    if (total != 6) {
+        return 1;
+    }
+
+    // volatile objects are read and written like unqualified ones
+    flag_pointer = &flag;
+    *flag_pointer = 2;
+    if (read(&flag) != 2 || c + 1 != 'b') {
+        return 2;
+    }
+
+    // Members of a volatile struct are volatile, and still assignable
+    pointer->ticks = 3;
+    pointer->limit = 4;
+    if (counter.ticks + counter.limit != 7) {
+        return 3;
+    }
+
+    if (sizeof(volatile int) != 4 || sizeof(flag_pointer) != 8) {
+        return 4;
+    }
+
+    return deadline + flag;
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::compile_with;
+use ferricc::error::CompilerError;
+use ferricc::Options;
+
+const WARNED: &str = "int main() {\n    int x;\n    if (x = 1) return x;\n    return 0;\n}\n";
+
+#[test]
+fn warnings_are_collected_with_their_locations() {
+    let (result, warnings) = compile_with(WARNED, "warnings.c", &Options::default());
+    assert!(result.is_ok());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].location.line, warnings[0].location.column), (3, 11));
+    assert!(warnings[0].to_string().starts_with("Warning at warnings.c:3:11: Assignment used as a condition"));
+}
+
+#[test]
+fn werror_makes_a_warned_program_fail() {
+    let options = Options { warnings_as_errors: true, ..Options::default() };
+    let (result, warnings) = compile_with(WARNED, "warnings.c", &options);
+    assert!(matches!(result, Err(CompilerError::WarningsAsErrors { count: 1 })));
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn werror_accepts_a_program_without_warnings() {
+    let options = Options { warnings_as_errors: true, ..Options::default() };
+    let (result, warnings) = compile_with("int main() { return 0; }", "warnings.c", &options);
+    assert!(result.is_ok());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn warning_directives_are_collected_and_fail_under_werror() {
+    let source = "#warning \"old header\"\nint main() { return 0; }";
+    let (result, warnings) = compile_with(source, "warnings.c", &Options::default());
+    assert!(result.is_ok());
+    assert_eq!(warnings[0].message, "#warning old header");
+
+    let options = Options { warnings_as_errors: true, ..Options::default() };
+    assert!(matches!(compile_with(source, "warnings.c", &options).0, Err(CompilerError::WarningsAsErrors { count: 1 })));
+}
+
+#[test]
+fn sizeof_neither_reads_nor_assigns_its_operand() {
+    // Sizing an uninitialized variable does not use its value
+    let (result, warnings) = compile_with("int main() {\n    int x;\n    return sizeof(x);\n}\n", "warnings.c", &Options::default());
+    assert!(result.is_ok());
+    assert!(warnings.is_empty());
+
+    // An assignment under sizeof never happens, so x is still uninitialized
+    let source = "int main() {\n    int x;\n    long n = sizeof(x = 1);\n    return x + n;\n}\n";
+    let (result, warnings) = compile_with(source, "warnings.c", &Options::default());
+    assert!(result.is_ok());
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].message.contains("Variable x may be used uninitialized"));
+    assert_eq!(warnings[0].location.line, 4);
+}